@@ -3,6 +3,11 @@
 //! C++'s comparison operators have different semantics from Rust's `PartialOrd` and `Ord` traits.
 //! If all the operators (`Lt`, `Le`, `Gt`, `Ge`) are implemented for a type, the pointer types
 //! (`CppBox`, `Ptr`, `MutPtr`, `Ref`, `MutRef`) automatically implement `PartialOrd`.
+//!
+//! C++'s three-way comparison operator (`operator<=>`) is represented by the `Cmp` trait.
+//! `Lt`, `Le`, `Gt` and `Ge` are automatically implemented for any type implementing `Cmp`.
+
+use std::cmp::Ordering;
 
 /// Represents C++'s `operator<`.
 pub trait Lt<Rhs: ?Sized = Self> {
@@ -28,3 +33,33 @@ pub trait Ge<Rhs: ?Sized = Self> {
     /// operator.
     unsafe fn ge(&self, other: &Rhs) -> bool;
 }
+
+/// Represents C++'s `operator<=>`.
+pub trait Cmp<Rhs: ?Sized = Self> {
+    /// This method returns an `Ordering` between `self` and `other`.
+    unsafe fn cmp(&self, other: &Rhs) -> Ordering;
+}
+
+impl<T: Cmp<U> + ?Sized, U: ?Sized> Lt<U> for T {
+    unsafe fn lt(&self, other: &U) -> bool {
+        Cmp::cmp(self, other) == Ordering::Less
+    }
+}
+
+impl<T: Cmp<U> + ?Sized, U: ?Sized> Le<U> for T {
+    unsafe fn le(&self, other: &U) -> bool {
+        Cmp::cmp(self, other) != Ordering::Greater
+    }
+}
+
+impl<T: Cmp<U> + ?Sized, U: ?Sized> Gt<U> for T {
+    unsafe fn gt(&self, other: &U) -> bool {
+        Cmp::cmp(self, other) == Ordering::Greater
+    }
+}
+
+impl<T: Cmp<U> + ?Sized, U: ?Sized> Ge<U> for T {
+    unsafe fn ge(&self, other: &U) -> bool {
+        Cmp::cmp(self, other) != Ordering::Less
+    }
+}
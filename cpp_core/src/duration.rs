@@ -0,0 +1,72 @@
+//! Conversions between C++ durations and `std::time::Duration`
+//!
+//! `ritual` passes C++ duration types (like `std::chrono::milliseconds`) through FFI
+//! as a tick count. The period of a tick in seconds is specified as a ratio
+//! `numerator / denominator`, just like in `std::ratio`.
+
+use std::time::Duration;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Converts a C++ tick count to a `Duration`. `Duration` can't be negative,
+/// so negative tick counts are converted to zero duration.
+pub fn duration_from_ticks(ticks: i64, numerator: u64, denominator: u64) -> Duration {
+    if ticks <= 0 {
+        return Duration::from_secs(0);
+    }
+    let nanos = (ticks as u128)
+        .saturating_mul(u128::from(numerator))
+        .saturating_mul(NANOS_PER_SEC)
+        / u128::from(denominator);
+    let secs = nanos / NANOS_PER_SEC;
+    if secs > u128::from(u64::max_value()) {
+        return Duration::new(u64::max_value(), (NANOS_PER_SEC - 1) as u32);
+    }
+    Duration::new(secs as u64, (nanos % NANOS_PER_SEC) as u32)
+}
+
+/// Converts a `Duration` to a C++ tick count. The value is rounded towards zero
+/// and saturated at `i64::max_value()`.
+pub fn duration_to_ticks(duration: Duration, numerator: u64, denominator: u64) -> i64 {
+    let ticks = duration.as_nanos().saturating_mul(u128::from(denominator))
+        / (u128::from(numerator) * NANOS_PER_SEC);
+    if ticks > i64::max_value() as u128 {
+        i64::max_value()
+    } else {
+        ticks as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{duration_from_ticks, duration_to_ticks};
+    use std::time::Duration;
+
+    #[test]
+    fn milliseconds() {
+        assert_eq!(
+            duration_from_ticks(1500, 1, 1000),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(
+            duration_to_ticks(Duration::from_millis(1500), 1, 1000),
+            1500
+        );
+        assert_eq!(duration_to_ticks(Duration::from_micros(1500), 1, 1000), 1);
+    }
+
+    #[test]
+    fn minutes() {
+        assert_eq!(duration_from_ticks(2, 60, 1), Duration::from_secs(120));
+        assert_eq!(duration_to_ticks(Duration::from_secs(179), 60, 1), 2);
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(duration_from_ticks(-5, 1, 1), Duration::from_secs(0));
+        assert_eq!(
+            duration_to_ticks(Duration::from_secs(u64::max_value()), 1, 1_000_000_000),
+            i64::max_value()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! C++ exceptions caught by FFI wrappers
+//!
+//! If exception catching is enabled in `ritual`, FFI wrappers of functions that can throw
+//! catch all exceptions and write their messages to a buffer provided by the caller.
+
+use std::error::Error;
+use std::fmt;
+use std::os::raw::c_char;
+
+/// Size of the buffer for the message of a caught C++ exception, including
+/// the terminating null character. Longer messages are truncated.
+pub const EXCEPTION_BUFFER_SIZE: usize = 256;
+
+/// A C++ exception thrown by a wrapped function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CppException {
+    message: String,
+}
+
+impl CppException {
+    /// Reads the exception message written by an FFI wrapper to `buffer`.
+    /// Returns `None` if the buffer is empty, i.e. no exception was thrown.
+    /// If the message is not null-terminated, the whole buffer is used.
+    pub fn from_buffer(buffer: &[c_char]) -> Option<Self> {
+        let len = buffer
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or_else(|| buffer.len());
+        if len == 0 {
+            return None;
+        }
+        let bytes = buffer[..len].iter().map(|&c| c as u8).collect::<Vec<u8>>();
+        Some(CppException {
+            message: String::from_utf8_lossy(&bytes).into_owned(),
+        })
+    }
+
+    /// Returns the message of the exception (the result of
+    /// `std::exception::what()`, if the exception was derived from `std::exception`).
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for CppException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "C++ exception: {}", self.message)
+    }
+}
+
+impl Error for CppException {}
+
+#[cfg(test)]
+mod tests {
+    use super::CppException;
+    use std::os::raw::c_char;
+
+    fn buffer(text: &str) -> Vec<c_char> {
+        text.bytes().map(|b| b as c_char).collect()
+    }
+
+    #[test]
+    fn empty_buffer() {
+        assert_eq!(CppException::from_buffer(&buffer("\0garbage")), None);
+        assert_eq!(CppException::from_buffer(&[]), None);
+    }
+
+    #[test]
+    fn message() {
+        let exception = CppException::from_buffer(&buffer("out of range\0\0\0")).unwrap();
+        assert_eq!(exception.message(), "out of range");
+        assert_eq!(exception.to_string(), "C++ exception: out of range");
+
+        let exception = CppException::from_buffer(&buffer("no terminator")).unwrap();
+        assert_eq!(exception.message(), "no terminator");
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// Calls a function when dropped.
+///
+/// `ritual` returns a `Guard` from wrappers of C++ registration functions
+/// (e.g. adding an observer) that have a corresponding unregistration function.
+/// The unregistration function is called with the token returned by
+/// the registration function when the guard is dropped.
+///
+/// ### Safety
+///
+/// The guard doesn't keep the C++ object alive. The user must ensure that
+/// the object the registration was made on is still alive when the guard is dropped,
+/// or call `forget` to keep the registration.
+pub struct Guard(Option<Box<dyn FnOnce()>>);
+
+impl Guard {
+    /// Creates a guard that will call `release` when dropped.
+    pub fn new<F: FnOnce() + 'static>(release: F) -> Self {
+        Guard(Some(Box::new(release)))
+    }
+
+    /// Destroys the guard without calling the release function.
+    pub fn forget(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if let Some(release) = self.0.take() {
+            release();
+        }
+    }
+}
+
+impl fmt::Debug for Guard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guard")
+            .field("is_active", &self.0.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Guard;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_drop_calls_release() {
+        let value1 = Rc::new(Cell::new(10));
+        let value2 = value1.clone();
+        let guard = Guard::new(move || value2.set(42));
+        assert_eq!(value1.get(), 10);
+        drop(guard);
+        assert_eq!(value1.get(), 42);
+    }
+
+    #[test]
+    fn test_forget_skips_release() {
+        let value1 = Rc::new(Cell::new(10));
+        let value2 = value1.clone();
+        Guard::new(move || value2.set(42)).forget();
+        assert_eq!(value1.get(), 10);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::ops::{Begin, BeginMut, Decrement, End, EndMut, Increment, Indirection};
 use crate::{CppBox, CppDeletable, MutPtr, MutRef, Ptr, Ref};
+use std::convert::TryInto;
+use std::ops::Sub;
 use std::os::raw::c_char;
 
 /// `Iterator` and `DoubleEndedIterator` backed by C++ iterators.
@@ -73,6 +75,84 @@ where
     }
 }
 
+/// `Iterator`, `DoubleEndedIterator` and `ExactSizeIterator` backed by
+/// C++ random access iterators.
+///
+/// Use `cpp_random_access_iter` function to construct it. `CppIterator` doesn't
+/// implement `ExactSizeIterator` because C++ input, forward and bidirectional iterators
+/// don't provide the distance between two iterators.
+pub struct CppRandomAccessIterator<T1, T2>
+where
+    T1: CppDeletable,
+    T2: CppDeletable,
+{
+    inner: CppIterator<T1, T2>,
+}
+
+/// Constructs a Rust-style iterator from C++ random access iterators pointing
+/// to begin and end of the collection. In addition to the capabilities of `CppIterator`,
+/// the returned iterator implements `ExactSizeIterator` if the C++ iterators
+/// support `end - begin` (exposed as the `Sub` trait).
+///
+/// ### Safety
+///
+/// `begin` and `end` must be valid. It's not possible to make any guarantees about safety, since
+/// `CppRandomAccessIterator` will call arbitrary C++ library code when used.
+pub unsafe fn cpp_random_access_iter<T1, T2>(
+    begin: CppBox<T1>,
+    end: CppBox<T2>,
+) -> CppRandomAccessIterator<T1, T2>
+where
+    T1: CppDeletable,
+    T2: CppDeletable,
+{
+    CppRandomAccessIterator {
+        inner: cpp_iter(begin, end),
+    }
+}
+
+impl<T1, T2, D> Iterator for CppRandomAccessIterator<T1, T2>
+where
+    T1: CppDeletable + PartialEq<Ref<T2>> + Indirection + Increment,
+    T2: CppDeletable,
+    for<'a> &'a T2: Sub<Ref<T1>, Output = D>,
+    D: TryInto<usize>,
+{
+    type Item = <T1 as Indirection>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = unsafe { &*self.inner.end - self.inner.begin.as_ref() }
+            .try_into()
+            .unwrap_or(0);
+        (len, Some(len))
+    }
+}
+
+impl<T1, T2, D> DoubleEndedIterator for CppRandomAccessIterator<T1, T2>
+where
+    T1: CppDeletable + PartialEq<Ref<T2>> + Indirection + Increment,
+    T2: CppDeletable + Decrement + Indirection<Output = <T1 as Indirection>::Output>,
+    for<'a> &'a T2: Sub<Ref<T1>, Output = D>,
+    D: TryInto<usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T1, T2, D> ExactSizeIterator for CppRandomAccessIterator<T1, T2>
+where
+    T1: CppDeletable + PartialEq<Ref<T2>> + Indirection + Increment,
+    T2: CppDeletable,
+    for<'a> &'a T2: Sub<Ref<T1>, Output = D>,
+    D: TryInto<usize>,
+{
+}
+
 impl<T, T1, T2> IntoIterator for Ptr<T>
 where
     T: Begin<Output = CppBox<T1>> + End<Output = CppBox<T2>>,
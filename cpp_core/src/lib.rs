@@ -56,7 +56,12 @@
 pub use crate::casts::{DynamicCast, StaticDowncast, StaticUpcast};
 pub use crate::convert::{CastFrom, CastInto};
 pub use crate::cpp_box::{CppBox, CppDeletable};
-pub use crate::iterator::{cpp_iter, CppIterator, SliceAsBeginEnd};
+pub use crate::exception::{CppException, EXCEPTION_BUFFER_SIZE};
+pub use crate::guard::Guard;
+pub use crate::iterator::{
+    cpp_iter, cpp_random_access_iter, CppIterator, CppRandomAccessIterator, SliceAsBeginEnd,
+};
+pub use crate::member_function_pointer::{MemberFunctionPointer, MEMBER_FUNCTION_POINTER_SIZE};
 pub use crate::ptr::{MutPtr, NullPtr, Ptr};
 pub use crate::ref_::{MutRef, Ref};
 pub use libc::wchar_t;
@@ -65,7 +70,11 @@ mod casts;
 pub mod cmp;
 mod convert;
 mod cpp_box;
+pub mod duration;
+mod exception;
+mod guard;
 mod iterator;
+mod member_function_pointer;
 pub mod ops;
 mod ops_impls;
 mod ptr;
@@ -0,0 +1,79 @@
+//! Pointers to C++ member functions
+//!
+//! The size and layout of a C++ pointer to member function depend on the compiler
+//! and the class, so `ritual` passes them through FFI as an opaque byte array.
+
+use std::fmt;
+
+/// Size of the data of `MemberFunctionPointer`. C++ pointers to member functions
+/// larger than this are not supported.
+pub const MEMBER_FUNCTION_POINTER_SIZE: usize = 32;
+
+/// An opaque C++ pointer to member function (like `void (QObject::*)()`).
+///
+/// The value can only be obtained from a C++ function and passed back
+/// to a C++ function expecting the same member function pointer type.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MemberFunctionPointer {
+    data: [u8; MEMBER_FUNCTION_POINTER_SIZE],
+}
+
+impl MemberFunctionPointer {
+    /// Creates a member function pointer from its raw bytes.
+    ///
+    /// # Safety
+    ///
+    /// `data` must contain bytes of a valid C++ pointer to member function
+    /// (padded with zeros) of the type expected by the function it will be passed to.
+    pub unsafe fn from_bytes(data: [u8; MEMBER_FUNCTION_POINTER_SIZE]) -> Self {
+        MemberFunctionPointer { data }
+    }
+
+    /// Returns the raw bytes of the member function pointer.
+    pub fn as_bytes(&self) -> &[u8; MEMBER_FUNCTION_POINTER_SIZE] {
+        &self.data
+    }
+}
+
+impl PartialEq for MemberFunctionPointer {
+    fn eq(&self, other: &Self) -> bool {
+        self.data[..] == other.data[..]
+    }
+}
+
+impl Eq for MemberFunctionPointer {}
+
+impl fmt::Debug for MemberFunctionPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemberFunctionPointer")
+            .field("data", &&self.data[..])
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemberFunctionPointer, MEMBER_FUNCTION_POINTER_SIZE};
+    use std::mem::size_of;
+
+    #[test]
+    fn layout() {
+        assert_eq!(
+            size_of::<MemberFunctionPointer>(),
+            MEMBER_FUNCTION_POINTER_SIZE
+        );
+    }
+
+    #[test]
+    fn bytes() {
+        let mut data = [0; MEMBER_FUNCTION_POINTER_SIZE];
+        data[0] = 42;
+        let pointer = unsafe { MemberFunctionPointer::from_bytes(data) };
+        assert_eq!(pointer.as_bytes()[0], 42);
+        assert_eq!(pointer, unsafe { MemberFunctionPointer::from_bytes(data) });
+        assert_ne!(pointer, unsafe {
+            MemberFunctionPointer::from_bytes([0; MEMBER_FUNCTION_POINTER_SIZE])
+        });
+    }
+}
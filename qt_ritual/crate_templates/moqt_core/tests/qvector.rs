@@ -1,4 +1,4 @@
-use cpp_core::cpp_iter;
+use cpp_core::{cpp_iter, cpp_random_access_iter};
 use moqt_core::{BasicClassField, QVectorOfBasicClassField, QVectorOfInt};
 use std::os::raw::c_int;
 
@@ -58,3 +58,23 @@ fn qvector_iter() {
         }
     }
 }
+
+#[test]
+fn qvector_random_access_iter() {
+    unsafe {
+        let mut vec = QVectorOfInt::new();
+        vec.push(10);
+        vec.push(12);
+        vec.push(14);
+
+        let mut iter = cpp_random_access_iter(vec.begin(), vec.end());
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next().map(|mut_ref| *mut_ref), Some(10));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back().map(|mut_ref| *mut_ref), Some(14));
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        assert_eq!(iter.next().map(|mut_ref| *mut_ref), Some(12));
+        assert_eq!(iter.len(), 0);
+        assert!(iter.next().is_none());
+    }
+}
@@ -4,7 +4,11 @@ use std::os::raw::c_int;
 /// Rust alternative to Qt's `QFlags` types.
 ///
 /// `Flags<E>` is an OR-combination of integer values of the enum type `E`.
+///
+/// `QFlags<E>` has the same representation as `c_int`, so it's ABI-compatible
+/// with values passed through FFI.
 #[derive(Clone, Copy)]
+#[repr(transparent)]
 pub struct QFlags<E> {
     value: c_int,
     _phantom_data: PhantomData<E>,
@@ -53,25 +57,61 @@ impl<E, T: Into<QFlags<E>>> ::std::ops::BitOr<T> for QFlags<E> {
     }
 }
 
-/*
-impl<E: QFlaggableEnum, T: EnumOrFlags<E>> std::ops::BitAnd<T> for QFlags<E> {
+impl<E, T: Into<QFlags<E>>> ::std::ops::BitAnd<T> for QFlags<E> {
     type Output = QFlags<E>;
     fn bitand(self, rhs: T) -> QFlags<E> {
-        let mut r = self.clone();
-        r.value &= rhs.to_flags().to_int();
-        r
+        Self {
+            value: self.value & rhs.into().value,
+            _phantom_data: PhantomData,
+        }
     }
 }
 
-impl<E: QFlaggableEnum, T: EnumOrFlags<E>> std::ops::BitXor<T> for QFlags<E> {
+impl<E, T: Into<QFlags<E>>> ::std::ops::BitXor<T> for QFlags<E> {
     type Output = QFlags<E>;
     fn bitxor(self, rhs: T) -> QFlags<E> {
-        let mut r = self.clone();
-        r.value ^= rhs.to_flags().to_int();
-        r
+        Self {
+            value: self.value ^ rhs.into().value,
+            _phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<E> ::std::ops::Not for QFlags<E> {
+    type Output = QFlags<E>;
+    fn not(self) -> QFlags<E> {
+        Self {
+            value: !self.value,
+            _phantom_data: PhantomData,
+        }
     }
 }
-*/
+
+impl<E, T: Into<QFlags<E>>> ::std::ops::BitOrAssign<T> for QFlags<E> {
+    fn bitor_assign(&mut self, rhs: T) {
+        self.value |= rhs.into().value;
+    }
+}
+
+impl<E, T: Into<QFlags<E>>> ::std::ops::BitAndAssign<T> for QFlags<E> {
+    fn bitand_assign(&mut self, rhs: T) {
+        self.value &= rhs.into().value;
+    }
+}
+
+impl<E, T: Into<QFlags<E>>> ::std::ops::BitXorAssign<T> for QFlags<E> {
+    fn bitxor_assign(&mut self, rhs: T) {
+        self.value ^= rhs.into().value;
+    }
+}
+
+impl<E> PartialEq for QFlags<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<E> Eq for QFlags<E> {}
 
 impl<E> Default for QFlags<E> {
     fn default() -> Self {
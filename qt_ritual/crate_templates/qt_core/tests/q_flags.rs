@@ -0,0 +1,34 @@
+use qt_core::{AlignmentFlag, QFlags};
+use std::mem;
+use std::os::raw::c_int;
+
+#[test]
+fn q_flags() {
+    assert_eq!(
+        mem::size_of::<QFlags<AlignmentFlag>>(),
+        mem::size_of::<c_int>()
+    );
+
+    let flags = AlignmentFlag::AlignLeft | AlignmentFlag::AlignTop;
+    assert!(flags.test_flag(AlignmentFlag::AlignLeft));
+    assert!(flags.test_flag(AlignmentFlag::AlignTop));
+    assert!(!flags.test_flag(AlignmentFlag::AlignRight));
+    assert_eq!(
+        flags.to_int(),
+        AlignmentFlag::AlignLeft.to_int() | AlignmentFlag::AlignTop.to_int()
+    );
+
+    let round_trip = QFlags::<AlignmentFlag>::from(c_int::from(flags));
+    assert_eq!(round_trip, flags);
+
+    let top = flags & AlignmentFlag::AlignTop;
+    assert_eq!(top, QFlags::from(AlignmentFlag::AlignTop));
+    assert!((flags & !top).test_flag(AlignmentFlag::AlignLeft));
+    assert!(!(flags & !top).test_flag(AlignmentFlag::AlignTop));
+
+    let mut flags2 = QFlags::from(AlignmentFlag::AlignLeft);
+    flags2 |= AlignmentFlag::AlignTop;
+    assert_eq!(flags2, flags);
+    flags2 ^= AlignmentFlag::AlignLeft;
+    assert_eq!(flags2, top);
+}
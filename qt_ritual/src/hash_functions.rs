@@ -0,0 +1,150 @@
+use log::trace;
+use ritual::cpp_data::{CppItem, CppPath};
+use ritual::cpp_function::CppFunction;
+use ritual::cpp_type::{CppPointerLikeTypeKind, CppType};
+use ritual::database::ItemWithSource;
+use ritual::processor::ProcessorData;
+use ritual_common::errors::Result;
+
+/// Returns the class hashed by `function` if it's a `qHash` overload
+/// that can be called with a single `const T&` or `T` argument.
+fn hashed_class(function: &CppFunction) -> Option<&CppPath> {
+    if function.path != CppPath::from_good_str("qHash") || function.member.is_some() {
+        return None;
+    }
+    let (first, other) = function.arguments.split_first()?;
+    if other.iter().any(|arg| !arg.has_default_value) {
+        return None;
+    }
+    let class_type = match &first.argument_type {
+        CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::Reference,
+            is_const: true,
+            target,
+        } => &**target,
+        other => other,
+    };
+    if let CppType::Class(path) = class_type {
+        if class_type.is_or_contains_template_parameter() {
+            None
+        } else {
+            Some(path)
+        }
+    } else {
+        None
+    }
+}
+
+/// Creates `std::size_t qHash(const T&)` function calling the `qHash` overload
+/// `function` with default values of the remaining arguments.
+/// The result is converted to `size_t`, so the hash value has the same type
+/// regardless of the Qt version.
+fn create_hash_function(function: &CppFunction) -> Option<CppFunction> {
+    hashed_class(function)?;
+    Some(CppFunction {
+        path: function.path.clone(),
+        member: None,
+        operator: None,
+        return_type: CppType::PointerSizedInteger {
+            path: CppPath::from_good_str("std::size_t"),
+            is_signed: false,
+        },
+        arguments: function.arguments[..1].to_vec(),
+        allows_variadic_arguments: false,
+        cast: None,
+        declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: function.is_noexcept,
+        is_deleted: false,
+    })
+}
+
+/// Adds a hash function returning `size_t` for each class that has a `qHash` overload.
+/// The FFI generator wraps these functions like any other function, so
+/// the generated API can compute hash values of the types.
+/// Classes without a `qHash` overload are skipped.
+pub fn add_hash_functions(data: &mut ProcessorData<'_>) -> Result<()> {
+    let mut functions = Vec::new();
+    for item in data.db.cpp_items() {
+        let function = if let Some(function) = item.item.as_function_ref() {
+            function
+        } else {
+            continue;
+        };
+        if let Some(hash_function) = create_hash_function(function) {
+            functions.push(ItemWithSource::new(&item.id, hash_function));
+        }
+    }
+
+    for item in functions {
+        let text = item.item.short_text();
+        let id = data
+            .db
+            .add_cpp_item(Some(item.source_id), CppItem::Function(item.item))?;
+        if id.is_some() {
+            trace!("adding hash function: {}", text);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn hash_functions() {
+    use ritual::cpp_function::CppFunctionArgument;
+    use ritual::cpp_type::CppBuiltInNumericType;
+
+    let qstring = CppType::Class(CppPath::from_good_str("QString"));
+    let mut function = CppFunction {
+        path: CppPath::from_good_str("qHash"),
+        member: None,
+        operator: None,
+        return_type: CppType::BuiltInNumeric(CppBuiltInNumericType::UInt),
+        arguments: vec![
+            CppFunctionArgument {
+                name: "key".to_string(),
+                argument_type: CppType::new_reference(true, qstring.clone()),
+                has_default_value: false,
+            },
+            CppFunctionArgument {
+                name: "seed".to_string(),
+                argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::UInt),
+                has_default_value: true,
+            },
+        ],
+        allows_variadic_arguments: false,
+        cast: None,
+        declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: true,
+        is_deleted: false,
+    };
+    let hash_function = create_hash_function(&function).unwrap();
+    assert_eq!(hash_function.path, function.path);
+    assert_eq!(hash_function.arguments.len(), 1);
+    assert_eq!(
+        hash_function.arguments[0].argument_type,
+        CppType::new_reference(true, qstring.clone())
+    );
+    assert_eq!(
+        hash_function.return_type,
+        CppType::PointerSizedInteger {
+            path: CppPath::from_good_str("std::size_t"),
+            is_signed: false,
+        }
+    );
+    assert!(hash_function.is_noexcept);
+    assert!(!hash_function.is_same(&function));
+
+    // the seed must be specified
+    function.arguments[1].has_default_value = false;
+    assert!(create_hash_function(&function).is_none());
+
+    function.arguments.truncate(1);
+    function.arguments[0].argument_type = CppType::new_pointer(true, qstring);
+    assert!(create_hash_function(&function).is_none());
+
+    function.arguments[0].argument_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    assert!(create_hash_function(&function).is_none());
+}
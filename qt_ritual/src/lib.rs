@@ -2,6 +2,7 @@ mod detect_signal_argument_types;
 mod detect_signals_and_slots;
 mod doc_decoder;
 mod doc_parser;
+mod hash_functions;
 pub mod lib_configs;
 mod mock_doc_parser;
 mod slot_wrappers;
@@ -2,6 +2,7 @@
 
 use crate::detect_signals_and_slots::detect_signals_and_slots;
 use crate::doc_parser::{parse_docs, set_crate_root_doc};
+use crate::hash_functions::add_hash_functions;
 use crate::slot_wrappers::add_slot_wrappers;
 use log::info;
 use qt_ritual_common::{all_crate_names, get_full_build_config, lib_dependencies, lib_folder_name};
@@ -215,6 +216,13 @@ pub fn create_config(crate_name: &str, qmake_path: Option<&str>) -> Result<Confi
     for cpp_parser_stage in &["cpp_parser", "cpp_parser_stage2"] {
         steps.add_after(&[cpp_parser_stage], "add_slot_wrappers", add_slot_wrappers)?;
     }
+    for cpp_variants_stage in &["cpp_variants", "cpp_variants_stage2"] {
+        steps.add_after(
+            &[cpp_variants_stage],
+            "add_hash_functions",
+            add_hash_functions,
+        )?;
+    }
 
     steps.add_after(
         &["rust_generator"],
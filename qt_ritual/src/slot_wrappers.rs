@@ -1,25 +1,96 @@
 use crate::detect_signal_argument_types::detect_signal_argument_types;
 use itertools::Itertools;
 use log::trace;
-use ritual::cpp_ffi_data::{CppFfiItem, QtSlotWrapper};
-use ritual::cpp_ffi_generator::{ffi_type, FfiNameProvider};
-use ritual::cpp_type::{CppFunctionPointerType, CppPointerLikeTypeKind, CppType, CppTypeRole};
+use ritual::cpp_ffi_data::{CppFfiItem, CppFfiType, QtSlotWrapper};
+use ritual::cpp_ffi_generator::{ffi_type, slot_return_type, FfiNameProvider};
+use ritual::cpp_type::{
+    is_qflags, CppFunctionPointerType, CppPointerLikeTypeKind, CppType, CppTypeRole,
+};
 use ritual::processor::ProcessorData;
 use ritual_common::errors::Result;
 use ritual_common::utils::MapIfOk;
+use std::collections::{HashMap, HashSet};
 use std::iter::once;
 
-/// Generates slot wrappers for all encountered argument types
-/// (excluding types already handled in the dependencies).
+/// Converts a signal argument type to the form used in slot wrappers, so that
+/// signals with equivalent arguments share the same wrapper. Qt normalizes
+/// signatures when connecting, so `QString` and `const QString&` arguments
+/// are compatible. Both of them are passed through FFI as `const QString*`,
+/// so class types are converted to const references. `QFlags` are passed as `int`
+/// in both forms, so they are converted to values.
+///
+/// Other types are kept as is because the reference changes the FFI type
+/// (e.g. `int` is passed as `int`, but `const int&` is passed as `const int*`).
+fn normalized_signal_argument_type(cpp_type: &CppType) -> CppType {
+    match cpp_type {
+        CppType::Class(path) if !is_qflags(path) => CppType::new_reference(true, cpp_type.clone()),
+        CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::Reference,
+            is_const: true,
+            target,
+        } => match &**target {
+            CppType::Class(path) if is_qflags(path) => (**target).clone(),
+            _ => cpp_type.clone(),
+        },
+        _ => cpp_type.clone(),
+    }
+}
+
+fn normalized_signal_argument_types(types: &[CppType]) -> Vec<CppType> {
+    types.iter().map(normalized_signal_argument_type).collect()
+}
+
+/// Cache of FFI types of signal arguments. Many signals share argument types,
+/// so each distinct type is converted once per run.
+#[derive(Default)]
+struct FfiTypeCache {
+    types: HashMap<CppType, CppFfiType>,
+    hits: usize,
+    misses: usize,
+}
+
+impl FfiTypeCache {
+    /// Returns the FFI type of the signal argument type `cpp_type`.
+    /// Failed conversions are not cached.
+    fn ffi_type(&mut self, cpp_type: &CppType) -> Result<CppFfiType> {
+        if let Some(ffi_type) = self.types.get(cpp_type) {
+            self.hits += 1;
+            return Ok(ffi_type.clone());
+        }
+        self.misses += 1;
+        let ffi_type = ffi_type(cpp_type, CppTypeRole::NotReturnType)?;
+        self.types.insert(cpp_type.clone(), ffi_type.clone());
+        Ok(ffi_type)
+    }
+}
+
+/// Returns the signature of the slot wrapper used by `FfiNameProvider`
+/// to create its stable name.
+fn slot_wrapper_signature(arguments: &[CppType], return_type: Option<&CppType>) -> String {
+    format!(
+        "{} slot_wrapper({})",
+        return_type.map_or_else(|| "void".to_string(), CppType::to_cpp_pseudo_code),
+        arguments.iter().map(CppType::to_cpp_pseudo_code).join(", ")
+    )
+}
+
+/// Generates a slot wrapper for `arguments`. The slot returns `return_type`
+/// (or void if it's `None`), and the callback returns the corresponding FFI type.
 fn generate_slot_wrapper(
     arguments: &[CppType],
+    return_type: Option<&CppType>,
     name_provider: &mut FfiNameProvider,
+    ffi_type_cache: &mut FfiTypeCache,
 ) -> Result<QtSlotWrapper> {
-    let ffi_types = arguments.map_if_ok(|t| ffi_type(&t, CppTypeRole::NotReturnType))?;
-    let class_path = name_provider.create_path(&format!(
-        "slot_wrapper_{}",
-        arguments.iter().map(CppType::ascii_caption).join("_")
-    ));
+    let ffi_types = arguments.map_if_ok(|t| ffi_type_cache.ffi_type(t))?;
+    let ffi_return_type = slot_return_type(return_type)?;
+    let class_path = name_provider.create_path(
+        &format!(
+            "slot_wrapper_{}",
+            arguments.iter().map(CppType::ascii_caption).join("_")
+        ),
+        &slot_wrapper_signature(arguments, return_type),
+    );
 
     let void_ptr = CppType::PointerLike {
         is_const: false,
@@ -31,7 +102,7 @@ fn generate_slot_wrapper(
         .collect();
 
     let function_type = CppFunctionPointerType {
-        return_type: Box::new(CppType::Void),
+        return_type: Box::new(ffi_return_type.ffi_type().clone()),
         arguments: func_arguments,
         allows_variadic_arguments: false,
     };
@@ -40,15 +111,46 @@ fn generate_slot_wrapper(
         signal_arguments: arguments.to_vec(),
         class_path: class_path.clone(),
         arguments: ffi_types,
+        return_type: ffi_return_type,
         function_type: function_type.clone(),
     };
     Ok(qt_slot_wrapper)
 }
 
+/// Sorts sets of signal argument types, so that names of the generated slot wrappers
+/// (including the numbers added to resolve name conflicts) are determined by
+/// the argument types only and don't depend on the order of detection.
+fn sorted_signal_argument_types(
+    all_types: impl IntoIterator<Item = Vec<CppType>>,
+) -> Vec<Vec<CppType>> {
+    let mut all_types = all_types
+        .into_iter()
+        .map(|types| {
+            let key = types.iter().map(CppType::to_cpp_pseudo_code).join(", ");
+            (key, types)
+        })
+        .collect_vec();
+    all_types.sort_by(|a, b| a.0.cmp(&b.0));
+    all_types.into_iter().map(|(_, types)| types).collect()
+}
+
+/// Returns distinct normalized sets of signal argument types (see
+/// `normalized_signal_argument_type`) in a stable order.
+fn slot_wrapper_argument_types(
+    all_types: impl IntoIterator<Item = Vec<CppType>>,
+) -> Vec<Vec<CppType>> {
+    let normalized = all_types
+        .into_iter()
+        .map(|types| normalized_signal_argument_types(&types))
+        .collect::<HashSet<_>>();
+    sorted_signal_argument_types(normalized)
+}
+
 pub fn add_slot_wrappers(data: &mut ProcessorData<'_>) -> Result<()> {
-    let all_types = detect_signal_argument_types(data)?;
+    let all_types = slot_wrapper_argument_types(detect_signal_argument_types(data)?);
 
     let mut name_provider = FfiNameProvider::new(data);
+    let mut ffi_type_cache = FfiTypeCache::default();
 
     for arg_types in all_types {
         let arg_types_text = arg_types.iter().map(CppType::to_cpp_pseudo_code).join(", ");
@@ -57,11 +159,14 @@ pub fn add_slot_wrappers(data: &mut ProcessorData<'_>) -> Result<()> {
             .db
             .all_ffi_items()
             .filter_map(|item| item.item.as_slot_wrapper_ref())
-            .any(|item| item.signal_arguments == arg_types);
+            .any(|item| {
+                normalized_signal_argument_types(&item.signal_arguments) == arg_types
+                    && item.return_type.original_type().is_void()
+            });
         if found {
             trace!("slot wrapper already exists: {}", arg_types_text);
         } else {
-            match generate_slot_wrapper(&arg_types, &mut name_provider) {
+            match generate_slot_wrapper(&arg_types, None, &mut name_provider, &mut ffi_type_cache) {
                 Ok(slot_wrapper) => {
                     let id = data
                         .db
@@ -80,5 +185,145 @@ pub fn add_slot_wrappers(data: &mut ProcessorData<'_>) -> Result<()> {
             }
         }
     }
+    trace!(
+        "slot wrapper argument types: {} conversions, {} cache hits",
+        ffi_type_cache.misses,
+        ffi_type_cache.hits
+    );
     Ok(())
 }
+
+#[test]
+fn slot_wrapper_names_are_stable() {
+    use ritual::cpp_data::CppPath;
+    use ritual::cpp_type::CppBuiltInNumericType;
+
+    // `ns::A` and `ns_A` have the same caption, so one of the names gets a number
+    let all_types = vec![
+        vec![CppType::Class(CppPath::from_good_str("ns::A"))],
+        vec![CppType::Class(CppPath::from_good_str("ns_A"))],
+        vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Int)],
+    ];
+    // names are created in the same order as in `add_slot_wrappers`
+    let paths = |all_types: Vec<Vec<CppType>>| {
+        let mut name_provider = FfiNameProvider::testing();
+        let mut cache = FfiTypeCache::default();
+        slot_wrapper_argument_types(all_types)
+            .into_iter()
+            .map(|types| {
+                let wrapper =
+                    generate_slot_wrapper(&types, None, &mut name_provider, &mut cache).unwrap();
+                (
+                    types[0].to_cpp_pseudo_code(),
+                    wrapper.class_path.to_cpp_pseudo_code(),
+                )
+            })
+            .collect::<HashMap<_, _>>()
+    };
+    let reversed = all_types.iter().rev().cloned().collect_vec();
+    let paths1 = paths(all_types);
+    assert_eq!(paths1, paths(reversed));
+    assert_eq!(paths1["const ns::A&"], "_slot_wrapper_ns_A_const_ref");
+    assert_eq!(paths1["const ns_A&"], "_slot_wrapper_ns_A_const_ref1");
+}
+
+#[test]
+fn slot_wrapper_stable_names() {
+    use ritual::cpp_type::CppBuiltInNumericType;
+
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let bool_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Bool);
+    let path = |arguments: &[CppType], return_type: Option<&CppType>| {
+        let mut name_provider =
+            FfiNameProvider::with_stable_suffixes("ctr_test_ffi".into(), HashSet::new());
+        let mut cache = FfiTypeCache::default();
+        generate_slot_wrapper(arguments, return_type, &mut name_provider, &mut cache)
+            .unwrap()
+            .class_path
+    };
+    assert_eq!(
+        slot_wrapper_signature(&[int.clone()], Some(&bool_type)),
+        "bool slot_wrapper(int)"
+    );
+    assert_eq!(path(&[int.clone()], None), path(&[int.clone()], None));
+    assert_ne!(path(&[int.clone()], None), path(&[int], Some(&bool_type)));
+}
+
+#[test]
+fn equivalent_signals_share_slot_wrapper() {
+    use ritual::cpp_data::CppPath;
+    use ritual::cpp_type::CppBuiltInNumericType;
+
+    let qstring = CppType::Class(CppPath::from_good_str("QString"));
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let all_types = vec![
+        vec![qstring.clone(), int.clone()],
+        vec![CppType::new_reference(true, qstring.clone()), int.clone()],
+        vec![int.clone()],
+        vec![CppType::new_reference(true, int.clone())],
+        vec![CppType::new_reference(false, qstring.clone())],
+    ];
+    let types = slot_wrapper_argument_types(all_types);
+    assert_eq!(types.len(), 4);
+    assert!(types.contains(&vec![
+        CppType::new_reference(true, qstring.clone()),
+        int.clone()
+    ]));
+    // references to primitive types are passed through FFI differently
+    assert!(types.contains(&vec![int.clone()]));
+    assert!(types.contains(&vec![CppType::new_reference(true, int)]));
+    // non-const references are not merged
+    assert!(types.contains(&vec![CppType::new_reference(false, qstring)]));
+}
+
+#[test]
+fn slot_wrapper_return_type() {
+    use ritual::cpp_data::CppPath;
+    use ritual::cpp_type::CppBuiltInNumericType;
+
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let mut name_provider = FfiNameProvider::testing();
+    let mut cache = FfiTypeCache::default();
+
+    let wrapper =
+        generate_slot_wrapper(&[int.clone()], None, &mut name_provider, &mut cache).unwrap();
+    assert!(wrapper.return_type.original_type().is_void());
+    assert_eq!(*wrapper.function_type.return_type, CppType::Void);
+
+    let wrapper =
+        generate_slot_wrapper(&[int.clone()], Some(&int), &mut name_provider, &mut cache).unwrap();
+    assert_eq!(wrapper.return_type.original_type(), &int);
+    assert_eq!(*wrapper.function_type.return_type, int);
+
+    // the slot can't take ownership of a value returned through a pointer
+    let qstring = CppType::Class(CppPath::from_good_str("QString"));
+    assert!(generate_slot_wrapper(&[int], Some(&qstring), &mut name_provider, &mut cache).is_err());
+}
+
+#[test]
+fn ffi_type_cache() {
+    use ritual::cpp_data::CppPath;
+    use ritual::cpp_type::CppBuiltInNumericType;
+
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let qstring = CppType::new_reference(true, CppType::Class(CppPath::from_good_str("QString")));
+    let mut name_provider = FfiNameProvider::testing();
+    let mut cache = FfiTypeCache::default();
+    generate_slot_wrapper(&[int.clone()], None, &mut name_provider, &mut cache).unwrap();
+    let wrapper = generate_slot_wrapper(
+        &[qstring.clone(), int.clone()],
+        None,
+        &mut name_provider,
+        &mut cache,
+    )
+    .unwrap();
+    assert_eq!(cache.misses, 2);
+    assert_eq!(cache.hits, 1);
+    assert_eq!(
+        wrapper.arguments,
+        vec![
+            ffi_type(&qstring, CppTypeRole::NotReturnType).unwrap(),
+            ffi_type(&int, CppTypeRole::NotReturnType).unwrap(),
+        ]
+    );
+}
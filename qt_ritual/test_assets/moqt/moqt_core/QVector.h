@@ -43,6 +43,9 @@ public:
         void operator--() {
             m_pos--;
         }
+        int operator-(const Iterator& other) const {
+            return m_pos - other.m_pos;
+        }
 
     private:
         Iterator(T* pos) : m_pos(pos) {}
@@ -70,6 +70,10 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: None,
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }),
     )?;
     data.db.add_cpp_item(
@@ -115,6 +119,10 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: None,
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }),
     )?;
     Ok(())
@@ -194,7 +202,10 @@ fn add_vector_instantiations(data: &mut ProcessorData<'_>) -> Result<()> {
         data.db.add_cpp_item(
             None,
             CppItem::Type(CppTypeDeclaration {
-                kind: CppTypeDeclarationKind::Class,
+                kind: CppTypeDeclarationKind::Class {
+                    is_pod: false,
+                    is_polymorphic: false,
+                },
                 path: CppPath::from_good_str("std").join(CppPathItem {
                     name: "vector".into(),
                     template_arguments: Some(vec![arg.clone(), allocator_type]),
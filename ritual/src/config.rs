@@ -2,14 +2,21 @@
 
 use crate::cpp_checker::PreliminaryTest;
 use crate::cpp_data::{CppItem, CppPath};
+use crate::cpp_ffi_data::DurationPeriod;
+use crate::cpp_function::ReturnValueAllocationPlace;
 use crate::cpp_parser::CppParserOutput;
+use crate::cpp_type::CppType;
+use crate::database::DatabaseClient;
 use crate::processor::{ProcessingSteps, ProcessorData};
 use crate::rust_info::{NameType, RustPathScope};
 use crate::rust_type::RustPath;
 use ritual_common::cpp_build_config::{CppBuildConfig, CppBuildPaths};
 use ritual_common::errors::{bail, Result};
+use ritual_common::file_utils::path_to_str;
 use ritual_common::target::Target;
 use ritual_common::toml;
+use ritual_common::utils::MapIfOk;
+use ritual_common::FeatureMacro;
 use serde_derive::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -21,9 +28,20 @@ pub enum CrateDependencyKind {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CrateDependencySource {
-    CratesIo { version: String },
-    Local { path: PathBuf },
+    CratesIo {
+        version: String,
+    },
+    Local {
+        path: PathBuf,
+    },
     CurrentWorkspace,
+    /// The crate is fetched from the git repository `url` at revision `rev`.
+    /// Can't be used for ritual dependencies because their database
+    /// is not available.
+    Git {
+        url: String,
+        rev: String,
+    },
 }
 
 /// Information about a crate dependency.
@@ -49,6 +67,9 @@ impl CrateDependency {
     }
 }
 
+/// Rust edition of the generated crate if it's not set in `CrateProperties`.
+pub const DEFAULT_EDITION: &str = "2018";
+
 /// Information about the crate being generated.
 /// Most of information in this object will be used in
 /// the output `Cargo.toml`.
@@ -58,6 +79,18 @@ pub struct CrateProperties {
     name: String,
     /// Version of the crate (must be in compliance with cargo requirements)
     version: String,
+    /// Rust edition of the crate (`DEFAULT_EDITION` if not set)
+    edition: Option<String>,
+    /// Crate types of the library (e.g. `cdylib`). Cargo's default is used if empty.
+    crate_types: Vec<String>,
+    /// Authors of the crate
+    authors: Vec<String>,
+    /// License of the crate (an SPDX expression, e.g. `MIT OR Apache-2.0`)
+    license: Option<String>,
+    /// URL of the source repository of the crate
+    repository: Option<String>,
+    /// Short description of the crate
+    description: Option<String>,
     /// Extra properties to be merged with auto generated content of `Cargo.toml`
     custom_fields: toml::value::Table,
     /// Extra dependencies for output `Cargo.toml`
@@ -76,6 +109,12 @@ impl CrateProperties {
         Self {
             name: name.into(),
             version: version.into(),
+            edition: None,
+            crate_types: Vec::new(),
+            authors: Vec::new(),
+            license: None,
+            repository: None,
+            description: None,
             custom_fields: Default::default(),
             dependencies: Vec::new(),
             build_dependencies: Vec::new(),
@@ -96,6 +135,11 @@ impl CrateProperties {
         {
             bail!("cannot use CurrentWorkspace for normal dependencies");
         }
+        if let CrateDependencySource::Git { .. } = source {
+            if kind == CrateDependencyKind::Ritual {
+                bail!("cannot use Git for ritual dependencies");
+            }
+        }
         self.dependencies.push(CrateDependency {
             name: name.into(),
             kind,
@@ -135,6 +179,7 @@ impl CrateProperties {
 
     /// Sets custom fields for output `Cargo.toml`. These fields will
     /// be added to auto-generated fields (or replace them in case of a name conflict).
+    /// Arrays are appended to auto-generated arrays, skipping values that are already present.
     pub fn set_custom_fields(&mut self, value: toml::value::Table) {
         self.custom_fields = value;
     }
@@ -147,6 +192,59 @@ impl CrateProperties {
     pub fn version(&self) -> &str {
         &self.version
     }
+    /// Rust edition of the crate
+    pub fn edition(&self) -> &str {
+        self.edition
+            .as_ref()
+            .map_or(DEFAULT_EDITION, |s| s.as_str())
+    }
+    /// Sets Rust edition of the crate (e.g. `"2021"`).
+    /// The edition specified in custom fields takes precedence over this value.
+    pub fn set_edition(&mut self, edition: impl Into<String>) {
+        self.edition = Some(edition.into());
+    }
+    /// Crate types of the library
+    pub fn crate_types(&self) -> &[String] {
+        &self.crate_types
+    }
+    /// Adds a crate type of the library (e.g. `"rlib"`, `"cdylib"` or `"staticlib"`)
+    /// to the `[lib]` section of `Cargo.toml`. If no crate types are added,
+    /// the section is omitted, so the crate is built as `rlib`.
+    pub fn add_crate_type(&mut self, crate_type: impl Into<String>) {
+        self.crate_types.push(crate_type.into());
+    }
+    /// Authors of the crate
+    pub fn authors(&self) -> &[String] {
+        &self.authors
+    }
+    /// Adds an author of the crate (e.g. `"Name <email>"`).
+    pub fn add_author(&mut self, author: impl Into<String>) {
+        self.authors.push(author.into());
+    }
+    /// License of the crate
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_ref().map(String::as_str)
+    }
+    /// Sets license of the crate (an SPDX expression, e.g. `"MIT OR Apache-2.0"`).
+    pub fn set_license(&mut self, license: impl Into<String>) {
+        self.license = Some(license.into());
+    }
+    /// URL of the source repository of the crate
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_ref().map(String::as_str)
+    }
+    /// Sets URL of the source repository of the crate.
+    pub fn set_repository(&mut self, repository: impl Into<String>) {
+        self.repository = Some(repository.into());
+    }
+    /// Short description of the crate
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_ref().map(String::as_str)
+    }
+    /// Sets short description of the crate.
+    pub fn set_description(&mut self, description: impl Into<String>) {
+        self.description = Some(description.into());
+    }
 
     /// Extra non-`cpp_to_rust`-based dependencies of the crate
     pub fn dependencies(&self) -> &[CrateDependency] {
@@ -176,6 +274,7 @@ pub type RustPathHook =
 pub type AfterCppParserHook =
     dyn Fn(&mut ProcessorData<'_>, &CppParserOutput) -> Result<()> + 'static;
 pub type FfiGeneratorHook = dyn Fn(&CppItem) -> Result<bool> + 'static;
+pub type AllocationPlaceHook = dyn Fn(&CppPath) -> Option<ReturnValueAllocationPlace> + 'static;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerLibraryConfig {
@@ -204,11 +303,14 @@ pub struct Config {
     // see setters documentation for information about these properties
     crate_properties: CrateProperties,
     cpp_lib_version: Option<String>,
+    cpp_lib_name: Option<String>,
     crate_template_path: Option<PathBuf>,
     include_directives: Vec<PathBuf>,
+    system_include_directives: Vec<PathBuf>,
     target_include_paths: Vec<PathBuf>,
     cpp_build_config: CppBuildConfig,
     cpp_build_paths: CppBuildPaths,
+    pkg_config_module: Option<String>,
     cpp_parser_arguments: Vec<String>,
     processing_steps: ProcessingSteps,
     movable_types_hook: Option<Box<dyn Fn(&CppPath) -> Result<MovableTypesHookOutput>>>,
@@ -217,9 +319,72 @@ pub struct Config {
     rust_path_hook: Option<Box<RustPathHook>>,
     after_cpp_parser_hooks: Vec<Box<AfterCppParserHook>>,
     ffi_generator_hook: Option<Box<FfiGeneratorHook>>,
+    allocation_place_hook: Option<Box<AllocationPlaceHook>>,
     cluster_config: Option<ClusterConfig>,
     cpp_checker_tests: Vec<PreliminaryTest>,
     write_dependencies_local_paths: bool,
+    thread_safe_functions: Vec<CppPath>,
+    denied_function_signatures: Vec<String>,
+    callback_panic_policy: CallbackPanicPolicy,
+    fixed_array_arguments: bool,
+    array_length_arguments: bool,
+    wrap_protected_functions: bool,
+    wrap_protected_fields: bool,
+    transitive_casts: bool,
+    unsafe_static_downcasts: bool,
+    heap_return_variants: bool,
+    catch_exceptions: bool,
+    strict_ffi_conversion: bool,
+    cpp_file_count: usize,
+    format_output: bool,
+    rustfmt_path: Option<PathBuf>,
+    cpp_wrapper_lib_output_name: Option<String>,
+    cpp_wrapper_lib_install_dir: String,
+    cpp_wrapper_header_install_dir: String,
+    feature_macros: Vec<(FeatureMacro, Vec<CppPath>)>,
+    transparent_layout_types: Vec<CppPath>,
+    ownership_transfer_arguments: Vec<(CppPath, usize)>,
+    non_relocatable_types: Vec<CppPath>,
+    movable_types: Vec<CppPath>,
+    non_movable_types: Vec<CppPath>,
+    registration_guards: Vec<(CppPath, CppPath)>,
+    root_classes: Vec<CppPath>,
+    allowed_path_prefixes: Vec<CppPath>,
+    duration_types: Vec<(CppPath, DurationPeriod)>,
+    success_flag_functions: Vec<CppPath>,
+    chaining_methods: Vec<CppPath>,
+    callback_arguments: Vec<(CppPath, usize, usize)>,
+    constructor_enum_classes: Vec<CppPath>,
+    utf8_string_constants: Vec<CppPath>,
+    benchmark_functions: Vec<CppPath>,
+    return_type_template_instantiations: Vec<(CppPath, CppType)>,
+}
+
+/// Action performed when a Rust closure called from C++
+/// (e.g. a closure attached to a slot) panics. Unwinding across
+/// the FFI boundary is undefined behavior, so the panic is always caught.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackPanicPolicy {
+    /// Abort the process.
+    Abort,
+    /// Print a message to stderr and return from the callback.
+    /// Callbacks returning a value return `Default::default()`.
+    LogAndSwallow,
+}
+
+impl Default for CallbackPanicPolicy {
+    fn default() -> Self {
+        CallbackPanicPolicy::Abort
+    }
+}
+
+/// A C++ path referenced in `Config` that doesn't resolve
+/// to any item in the C++ database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedConfigPath {
+    /// Name of the `Config` method the path was passed to
+    pub setting: &'static str,
+    pub path: CppPath,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -239,19 +404,58 @@ impl Config {
             cpp_build_paths: Default::default(),
             target_include_paths: Default::default(),
             include_directives: Default::default(),
+            system_include_directives: Default::default(),
             cpp_parser_arguments: Default::default(),
             cpp_build_config: Default::default(),
+            pkg_config_module: Default::default(),
             movable_types_hook: Default::default(),
             processing_steps: Default::default(),
             cpp_lib_version: Default::default(),
+            cpp_lib_name: Default::default(),
             cpp_parser_path_hook: Default::default(),
             rust_path_scope_hook: Default::default(),
             rust_path_hook: Default::default(),
             after_cpp_parser_hooks: Default::default(),
             ffi_generator_hook: Default::default(),
+            allocation_place_hook: Default::default(),
             cluster_config: None,
             cpp_checker_tests: Default::default(),
             write_dependencies_local_paths: true,
+            thread_safe_functions: Default::default(),
+            denied_function_signatures: Vec::new(),
+            callback_panic_policy: Default::default(),
+            fixed_array_arguments: false,
+            array_length_arguments: false,
+            wrap_protected_functions: false,
+            wrap_protected_fields: false,
+            transitive_casts: true,
+            unsafe_static_downcasts: true,
+            heap_return_variants: false,
+            catch_exceptions: false,
+            strict_ffi_conversion: false,
+            cpp_file_count: 1,
+            format_output: true,
+            rustfmt_path: None,
+            cpp_wrapper_lib_output_name: None,
+            cpp_wrapper_lib_install_dir: ".".to_string(),
+            cpp_wrapper_header_install_dir: ".".to_string(),
+            feature_macros: Vec::new(),
+            transparent_layout_types: Vec::new(),
+            ownership_transfer_arguments: Vec::new(),
+            non_relocatable_types: Vec::new(),
+            movable_types: Vec::new(),
+            non_movable_types: Vec::new(),
+            registration_guards: Vec::new(),
+            root_classes: Vec::new(),
+            allowed_path_prefixes: Vec::new(),
+            duration_types: Vec::new(),
+            success_flag_functions: Vec::new(),
+            chaining_methods: Vec::new(),
+            callback_arguments: Vec::new(),
+            constructor_enum_classes: Vec::new(),
+            utf8_string_constants: Vec::new(),
+            benchmark_functions: Vec::new(),
+            return_type_template_instantiations: Vec::new(),
         }
     }
 
@@ -320,12 +524,22 @@ impl Config {
     }
 
     /// Adds an include directive. Each directive will be added
-    /// as `#include <path>` to the input file for the C++ parser.
+    /// as `#include "path"` to the input file for the C++ parser,
+    /// the C++ checker and the C++ wrapper library.
     /// File name only paths or relative paths should be used in this method.
     pub fn add_include_directive<P: Into<PathBuf>>(&mut self, path: P) {
         self.include_directives.push(path.into());
     }
 
+    /// Same as `Config::add_include_directive`, but the directive is added
+    /// as `#include <path>`. This should be used for system and framework headers,
+    /// so that they are not shadowed by local files.
+    pub fn add_system_include_directive<P: Into<PathBuf>>(&mut self, path: P) {
+        let path = path.into();
+        self.include_directives.push(path.clone());
+        self.system_include_directives.push(path);
+    }
+
     /// Sets `CppBuildConfig` value that will be passed to the build script
     /// of the generated crate.
     pub fn set_cpp_build_config(&mut self, cpp_build_config: CppBuildConfig) {
@@ -346,6 +560,15 @@ impl Config {
         self.cpp_lib_version.as_ref().map(String::as_str)
     }
 
+    /// Sets the name of the C++ library displayed in the generated README.
+    pub fn set_cpp_lib_name<S: Into<String>>(&mut self, name: S) {
+        self.cpp_lib_name = Some(name.into());
+    }
+
+    pub fn cpp_lib_name(&self) -> Option<&str> {
+        self.cpp_lib_name.as_ref().map(String::as_str)
+    }
+
     pub fn processing_steps(&self) -> &ProcessingSteps {
         &self.processing_steps
     }
@@ -380,16 +603,47 @@ impl Config {
         &self.target_include_paths
     }
 
-    /// Returns values added by `Config::add_include_directive`.
+    /// Returns values added by `Config::add_include_directive` and
+    /// `Config::add_system_include_directive`.
     pub fn include_directives(&self) -> &[PathBuf] {
         &self.include_directives
     }
 
+    /// Returns C++ code containing all include directives, one per line.
+    pub fn include_directives_code(&self) -> Result<String> {
+        let lines = self
+            .include_directives
+            .iter()
+            .map_if_ok(|path| -> Result<_> {
+                let path_str = path_to_str(path)?;
+                Ok(if self.system_include_directives.contains(path) {
+                    format!("#include <{}>", path_str)
+                } else {
+                    format!("#include \"{}\"", path_str)
+                })
+            })?;
+        Ok(lines.join("\n"))
+    }
+
     /// Returns current `CppBuildConfig` value.
     pub fn cpp_build_config(&self) -> &CppBuildConfig {
         &self.cpp_build_config
     }
 
+    /// Makes the generated build script locate the C++ library using
+    /// the pkg-config module `name`. The include and library paths reported
+    /// by `pkg-config` are added to the build paths at build time.
+    ///
+    /// This setting has no effect if the crate template contains `build.rs`.
+    pub fn set_pkg_config_module(&mut self, name: impl Into<String>) {
+        self.pkg_config_module = Some(name.into());
+    }
+
+    /// Returns the value set by `Config::set_pkg_config_module`.
+    pub fn pkg_config_module(&self) -> Option<&str> {
+        self.pkg_config_module.as_ref().map(|s| s.as_str())
+    }
+
     pub fn set_movable_types_hook(
         &mut self,
         hook: impl Fn(&CppPath) -> Result<MovableTypesHookOutput> + 'static,
@@ -462,6 +716,26 @@ impl Config {
         self.ffi_generator_hook.as_ref().map(|b| &**b)
     }
 
+    /// Sets a hook that overrides the allocation place of class types
+    /// in the FFI generator. If the hook returns `Some(Stack)` or `Some(Heap)`
+    /// for a class path, values of the class are returned through an output buffer
+    /// or allocated on the heap, respectively, regardless of the movable types
+    /// detection. This also affects the allocation place of the destructor,
+    /// so construction and destruction of the class stay consistent.
+    /// `None` and `Some(NotApplicable)` keep the default allocation place.
+    /// `Some(Stack)` is ignored with a warning for types that can't be relocated
+    /// (see `Config::add_non_relocatable_type`).
+    pub fn set_allocation_place_hook(
+        &mut self,
+        hook: impl Fn(&CppPath) -> Option<ReturnValueAllocationPlace> + 'static,
+    ) {
+        self.allocation_place_hook = Some(Box::new(hook));
+    }
+
+    pub fn allocation_place_hook(&self) -> Option<&AllocationPlaceHook> {
+        self.allocation_place_hook.as_ref().map(|b| &**b)
+    }
+
     pub fn set_cluster_config(&mut self, cluster_config: ClusterConfig) {
         self.cluster_config = Some(cluster_config);
     }
@@ -485,6 +759,677 @@ impl Config {
     pub fn write_dependencies_local_paths(&self) -> bool {
         self.write_dependencies_local_paths
     }
+
+    /// Marks C++ function with `path` as safe to call from any thread,
+    /// even if its class is otherwise bound to a single thread.
+    /// All overloads of the function are affected. The annotation
+    /// is available to later processing steps through
+    /// `CppFfiFunction::is_thread_safe`.
+    pub fn add_thread_safe_function(&mut self, path: CppPath) {
+        self.thread_safe_functions.push(path);
+    }
+
+    /// Returns values added by `Config::add_thread_safe_function`.
+    pub fn thread_safe_functions(&self) -> &[CppPath] {
+        &self.thread_safe_functions
+    }
+
+    /// Excludes the C++ function overload with `signature` from the FFI generator.
+    /// The signature must be in the form returned by
+    /// `CppFunction::normalized_signature`, e.g. `QString::arg(int, int, QChar) const`
+    /// (whitespace is ignored). Other overloads of the function are not affected.
+    pub fn add_denied_function_signature(&mut self, signature: impl Into<String>) {
+        self.denied_function_signatures.push(signature.into());
+    }
+
+    /// Returns values added by `Config::add_denied_function_signature`.
+    pub fn denied_function_signatures(&self) -> &[String] {
+        &self.denied_function_signatures
+    }
+
+    /// Sets the action performed when a Rust closure called from C++ panics.
+    /// The default is `CallbackPanicPolicy::Abort`.
+    pub fn set_callback_panic_policy(&mut self, policy: CallbackPanicPolicy) {
+        self.callback_panic_policy = policy;
+    }
+
+    pub fn callback_panic_policy(&self) -> CallbackPanicPolicy {
+        self.callback_panic_policy
+    }
+
+    /// Enables or disables recognition of the size of fixed-size array arguments
+    /// (e.g. `float out[16]`). If enabled, such arguments are presented as
+    /// `&[T; N]` or `&mut [T; N]` in the Rust API instead of raw pointers.
+    /// This takes precedence over `Config::set_array_length_arguments`.
+    /// Disabled by default.
+    pub fn set_fixed_array_arguments(&mut self, value: bool) {
+        self.fixed_array_arguments = value;
+    }
+
+    pub fn fixed_array_arguments(&self) -> bool {
+        self.fixed_array_arguments
+    }
+
+    /// Enables or disables passing the length of fixed-size array arguments
+    /// (e.g. `float out[16]`) through an additional FFI argument. If enabled,
+    /// such arguments are presented as slices in the Rust API, and the C++
+    /// wrapper fails if the slice is shorter than the declared size: it throws
+    /// `std::length_error` if exceptions are caught (see `Config::set_catch_exceptions`)
+    /// and aborts otherwise. This changes the signatures of the FFI functions.
+    /// Disabled by default.
+    pub fn set_array_length_arguments(&mut self, value: bool) {
+        self.array_length_arguments = value;
+    }
+
+    pub fn array_length_arguments(&self) -> bool {
+        self.array_length_arguments
+    }
+
+    /// Enables or disables wrapping of protected member functions.
+    /// If enabled, the FFI wrapper of a protected function calls it through
+    /// a generated C++ subclass that makes the function public with
+    /// a `using` declaration. Protected constructors of non-abstract classes
+    /// are wrapped by constructing the subclass, which has a public constructor
+    /// forwarding to the protected one, so the dynamic type of such objects is
+    /// the subclass. Protected constructors of abstract classes, protected destructors
+    /// and fields are still skipped. Disabled by default.
+    pub fn set_wrap_protected_functions(&mut self, value: bool) {
+        self.wrap_protected_functions = value;
+    }
+
+    pub fn wrap_protected_functions(&self) -> bool {
+        self.wrap_protected_functions
+    }
+
+    /// Enables or disables generation of accessors for protected fields.
+    /// If enabled, the accessors use the same subclass mechanism as
+    /// wrappers of protected functions (see `Config::set_wrap_protected_functions`).
+    /// Disabled by default.
+    pub fn set_wrap_protected_fields(&mut self, value: bool) {
+        self.wrap_protected_fields = value;
+    }
+
+    pub fn wrap_protected_fields(&self) -> bool {
+        self.wrap_protected_fields
+    }
+
+    /// Enables or disables generation of casts between a class and
+    /// its indirect bases. Static casts to an indirect base are chained through
+    /// each intermediate base class (see `CppCast::base_chain`), so the pointer
+    /// is adjusted for every base subobject along the path, and it's unambiguous
+    /// even if the indirect base appears in the hierarchy multiple times.
+    /// `dynamic_cast` wrappers cast to the indirect base in one step.
+    /// If disabled, casts are only generated for direct bases,
+    /// which reduces the number of FFI functions in deep class hierarchies.
+    /// Enabled by default.
+    pub fn set_transitive_casts(&mut self, value: bool) {
+        self.transitive_casts = value;
+    }
+
+    pub fn transitive_casts(&self) -> bool {
+        self.transitive_casts
+    }
+
+    /// Enables or disables generation of unsafe `static_cast` downcasts
+    /// (from a base class to a derived class). If disabled, downcasting is
+    /// only possible with `dynamic_cast` wrappers. Upcasts are always generated.
+    /// Enabled by default.
+    pub fn set_unsafe_static_downcasts(&mut self, value: bool) {
+        self.unsafe_static_downcasts = value;
+    }
+
+    pub fn unsafe_static_downcasts(&self) -> bool {
+        self.unsafe_static_downcasts
+    }
+
+    /// Enables or disables generation of heap-allocating variants of functions
+    /// returning movable types by value. If enabled, such functions get
+    /// an additional wrapper returning `CppBox` (with the `_boxed` suffix
+    /// in Rust), and movable types get a `CppDeletable` implementation.
+    /// This avoids copying large objects returned from C++. Disabled by default.
+    pub fn set_heap_return_variants(&mut self, value: bool) {
+        self.heap_return_variants = value;
+    }
+
+    pub fn heap_return_variants(&self) -> bool {
+        self.heap_return_variants
+    }
+
+    /// Enables or disables catching of C++ exceptions in FFI wrappers.
+    /// If enabled, wrappers of functions that are not `noexcept` catch all exceptions
+    /// and write their messages to an additional buffer argument, and the Rust
+    /// wrappers return `Result<T, cpp_core::CppException>`. Exceptions that
+    /// reach Rust code cause undefined behavior, so this should be enabled for
+    /// libraries that report errors with exceptions. Disabled by default.
+    pub fn set_catch_exceptions(&mut self, value: bool) {
+        self.catch_exceptions = value;
+    }
+
+    pub fn catch_exceptions(&self) -> bool {
+        self.catch_exceptions
+    }
+
+    /// Enables or disables failing the FFI generator step if any C++ items
+    /// can't be converted to FFI items. If enabled, the step reports all
+    /// such items and the reasons in the error. Otherwise, these items
+    /// are skipped and only their count is logged. Disabled by default.
+    pub fn set_strict_ffi_conversion(&mut self, value: bool) {
+        self.strict_ffi_conversion = value;
+    }
+
+    pub fn strict_ffi_conversion(&self) -> bool {
+        self.strict_ffi_conversion
+    }
+
+    /// Sets the number of C++ source files (`file1.cpp`, ..., `fileN.cpp`)
+    /// the FFI wrappers of the C++ wrapper library are split into.
+    /// The files are compiled in parallel, which reduces build time and
+    /// memory usage of the build for large libraries. The default value is 1.
+    ///
+    /// Panics if `count` is 0.
+    pub fn set_cpp_file_count(&mut self, count: usize) {
+        assert!(count > 0, "C++ file count must be positive");
+        self.cpp_file_count = count;
+    }
+
+    pub fn cpp_file_count(&self) -> usize {
+        self.cpp_file_count
+    }
+
+    /// Enables or disables formatting of the generated Rust code with `rustfmt`.
+    /// Disabling formatting makes generation faster and removes the dependency
+    /// on `rustfmt`. Enabled by default.
+    pub fn set_format_output(&mut self, value: bool) {
+        self.format_output = value;
+    }
+
+    pub fn format_output(&self) -> bool {
+        self.format_output
+    }
+
+    /// Sets the path to the `rustfmt` binary used for formatting the generated code.
+    /// By default, `rustfmt` is looked up in `PATH`. The edition of the crate
+    /// (see `CrateProperties::set_edition`) is used for formatting.
+    pub fn set_rustfmt_path(&mut self, path: impl Into<PathBuf>) {
+        self.rustfmt_path = Some(path.into());
+    }
+
+    /// Returns value set by `Config::set_rustfmt_path`.
+    pub fn rustfmt_path(&self) -> Option<&PathBuf> {
+        self.rustfmt_path.as_ref()
+    }
+
+    /// Sets the file name (without prefix and extension) of the compiled
+    /// C++ wrapper library. By default, the name of the CMake target
+    /// (`<crate name>_c`) is used.
+    pub fn set_cpp_wrapper_lib_output_name(&mut self, name: impl Into<String>) {
+        self.cpp_wrapper_lib_output_name = Some(name.into());
+    }
+
+    /// Returns value set by `Config::set_cpp_wrapper_lib_output_name`.
+    pub fn cpp_wrapper_lib_output_name(&self) -> Option<&str> {
+        self.cpp_wrapper_lib_output_name
+            .as_ref()
+            .map(|s| s.as_str())
+    }
+
+    /// Sets the directory (relative to the install prefix) the `install` target
+    /// of the C++ wrapper library puts the compiled library to.
+    /// The build script installs the library to a directory in `OUT_DIR`,
+    /// so this only affects the layout of that directory and manual installs
+    /// of the wrapper library. The default value is `.`.
+    pub fn set_cpp_wrapper_lib_install_dir(&mut self, dir: impl Into<String>) {
+        self.cpp_wrapper_lib_install_dir = dir.into();
+    }
+
+    pub fn cpp_wrapper_lib_install_dir(&self) -> &str {
+        &self.cpp_wrapper_lib_install_dir
+    }
+
+    /// Sets the directory (relative to the install prefix) the `install` target
+    /// of the C++ wrapper library puts the headers to. The default value is `.`.
+    pub fn set_cpp_wrapper_header_install_dir(&mut self, dir: impl Into<String>) {
+        self.cpp_wrapper_header_install_dir = dir.into();
+    }
+
+    pub fn cpp_wrapper_header_install_dir(&self) -> &str {
+        &self.cpp_wrapper_header_install_dir
+    }
+
+    /// Adds a C++ feature macro mapped to a cargo feature of the crate.
+    /// C++ items with `paths` (including members of classes and namespaces
+    /// listed in `paths`) are only available when the cargo feature is enabled.
+    /// This also applies to all items using these types, e.g. methods of other
+    /// classes taking them as arguments, casts and field accessors.
+    /// The build script defines the C++ macro when building the C++ wrapper library
+    /// with the feature enabled. For macros created with `FeatureMacro::negated`
+    /// (e.g. `QT_NO_SSL`), the C++ wrappers are guarded with `#ifndef`, and
+    /// the macro is defined when the feature is disabled.
+    pub fn add_feature_macro(&mut self, feature: FeatureMacro, paths: Vec<CppPath>) {
+        self.feature_macros.push((feature, paths));
+    }
+
+    /// Returns values added by `Config::add_feature_macro`.
+    pub fn feature_macros(&self) -> &[(FeatureMacro, Vec<CppPath>)] {
+        &self.feature_macros
+    }
+
+    /// Requests transparent layout for the C++ class with `path`.
+    /// If the class is a POD type and all of its fields have built-in numeric types,
+    /// the Rust wrapper is generated as a `#[repr(C)]` struct with the same fields,
+    /// so that the fields can be accessed directly without FFI calls.
+    /// The C++ wrapper library contains `static_assert`s verifying that
+    /// the layouts match. Other types keep the opaque representation.
+    pub fn add_transparent_layout_type(&mut self, path: CppPath) {
+        self.transparent_layout_types.push(path);
+    }
+
+    /// Returns values added by `Config::add_transparent_layout_type`.
+    pub fn transparent_layout_types(&self) -> &[CppPath] {
+        &self.transparent_layout_types
+    }
+
+    /// Marks the argument with `argument_index` of the C++ function with `path`
+    /// as taking ownership of the passed object (e.g. the parent deletes the child
+    /// passed to `addChild(Widget*)`). All overloads of the function are affected.
+    /// The argument must be a non-const pointer to a deletable class.
+    /// The Rust wrapper accepts `CppBox<T>` for such argument and releases the ownership
+    /// without deleting the object, so that it's not deleted twice.
+    pub fn add_ownership_transfer_argument(&mut self, path: CppPath, argument_index: usize) {
+        self.ownership_transfer_arguments
+            .push((path, argument_index));
+    }
+
+    /// Returns values added by `Config::add_ownership_transfer_argument`.
+    pub fn ownership_transfer_arguments(&self) -> &[(CppPath, usize)] {
+        &self.ownership_transfer_arguments
+    }
+
+    /// Marks the C++ class with `path` as unsafe to relocate by copying its bytes
+    /// (e.g. because it contains pointers into its own storage). Such types and
+    /// types containing them are always allocated on the heap, even if
+    /// they are considered movable. Classes with virtual bases are detected
+    /// automatically and don't need to be added here.
+    pub fn add_non_relocatable_type(&mut self, path: CppPath) {
+        self.non_relocatable_types.push(path);
+    }
+
+    /// Returns values added by `Config::add_non_relocatable_type`.
+    pub fn non_relocatable_types(&self) -> &[CppPath] {
+        &self.non_relocatable_types
+    }
+
+    /// Marks the C++ class with `path` as movable, so that its values are
+    /// allocated on the stack. Use this if the type is movable but wasn't
+    /// detected as such. Types marked with `Config::add_non_relocatable_type`
+    /// or `Config::add_non_movable_type` are still allocated on the heap.
+    pub fn add_movable_type(&mut self, path: CppPath) {
+        self.movable_types.push(path);
+    }
+
+    /// Returns values added by `Config::add_movable_type`.
+    pub fn movable_types(&self) -> &[CppPath] {
+        &self.movable_types
+    }
+
+    /// Marks the C++ class with `path` as not movable, so that its values are
+    /// always allocated on the heap, even if it's detected as movable.
+    /// Unlike `Config::add_non_relocatable_type`, this doesn't affect
+    /// types containing this type.
+    pub fn add_non_movable_type(&mut self, path: CppPath) {
+        self.non_movable_types.push(path);
+    }
+
+    /// Returns values added by `Config::add_non_movable_type`.
+    pub fn non_movable_types(&self) -> &[CppPath] {
+        &self.non_movable_types
+    }
+
+    /// Pairs the C++ registration function with `register_path` (e.g. `addObserver`)
+    /// with the unregistration function with `unregister_path` (e.g. `removeObserver`).
+    /// The registration function must return a token of a primitive type (e.g. an integer ID),
+    /// and the unregistration function must accept this token as its only argument.
+    /// If the registration function is a method, the unregistration function must be
+    /// a method of the same class.
+    ///
+    /// In addition to the regular wrapper, a `_guarded` variant of the registration
+    /// function is generated. It returns a `cpp_core::Guard` that calls
+    /// the unregistration function when dropped.
+    pub fn add_registration_guard(&mut self, register_path: CppPath, unregister_path: CppPath) {
+        self.registration_guards
+            .push((register_path, unregister_path));
+    }
+
+    /// Returns values added by `Config::add_registration_guard`.
+    pub fn registration_guards(&self) -> &[(CppPath, CppPath)] {
+        &self.registration_guards
+    }
+
+    /// Adds the C++ class with `path` to the set of root classes.
+    /// If any root classes are added, FFI wrappers are only generated for
+    /// the root classes and the types transitively referenced by them
+    /// (through method signatures, fields and base classes), including
+    /// instantiations of class templates. Members of these types and casts
+    /// between them are wrapped, but free functions and all other types are not.
+    /// The parsed C++ data is not affected.
+    pub fn add_root_class(&mut self, path: CppPath) {
+        self.root_classes.push(path);
+    }
+
+    /// Returns values added by `Config::add_root_class`.
+    pub fn root_classes(&self) -> &[CppPath] {
+        &self.root_classes
+    }
+
+    /// Adds `prefix` to the allow-list of the FFI generator. If any prefixes
+    /// are added, FFI wrappers are only generated for items whose path starts
+    /// with one of the prefixes (e.g. a class and its members or all items
+    /// of a namespace). Casts and base specifiers are only wrapped if both
+    /// classes are allowed. Unlike `Config::add_root_class`, this doesn't
+    /// affect the parsed C++ data, so excluded types used in signatures
+    /// of allowed items remain available as opaque types. Such types are
+    /// reported in a warning.
+    pub fn add_allowed_path_prefix(&mut self, prefix: CppPath) {
+        self.allowed_path_prefixes.push(prefix);
+    }
+
+    /// Returns values added by `Config::add_allowed_path_prefix`.
+    pub fn allowed_path_prefixes(&self) -> &[CppPath] {
+        &self.allowed_path_prefixes
+    }
+
+    /// Marks the C++ class with `path` as a duration type with the specified tick `period`,
+    /// e.g. `std::chrono::milliseconds` with period `1/1000`. Values of this type
+    /// (passed by value or by const reference) are passed to FFI as a tick count
+    /// and exposed as `std::time::Duration` in the Rust API. `path` must be
+    /// a concrete instantiation constructible from its tick count (`long long`)
+    /// and providing the `count()` method and the `period` type (`std::ratio`).
+    /// The C++ wrappers check that `period` matches the specified value at compile time.
+    /// Other duration types are wrapped as usual.
+    pub fn add_duration_type(&mut self, path: CppPath, period: DurationPeriod) {
+        self.duration_types.push((path, period));
+    }
+
+    /// Returns values added by `Config::add_duration_type`.
+    pub fn duration_types(&self) -> &[(CppPath, DurationPeriod)] {
+        &self.duration_types
+    }
+
+    /// Marks the C++ function with `path` as returning `bool` to indicate
+    /// success (`true`) or failure (`false`). All overloads of the function
+    /// returning `bool` are affected. The Rust wrappers of such functions return
+    /// `Result<(), ()>` instead of `bool`, so that the failure can't be ignored silently.
+    pub fn add_success_flag_function(&mut self, path: CppPath) {
+        self.success_flag_functions.push(path);
+    }
+
+    /// Returns values added by `Config::add_success_flag_function`.
+    pub fn success_flag_functions(&self) -> &[CppPath] {
+        &self.success_flag_functions
+    }
+
+    /// Marks the C++ method with `path` as returning `*this` to allow method chaining
+    /// (e.g. `T& T::setFoo(int)`). All non-const overloads of the method returning
+    /// a reference to its own class are affected. The Rust wrappers of such methods
+    /// return `&mut self` instead of an unbound reference.
+    ///
+    /// Methods returning a reference to a different object of the same class
+    /// (e.g. `Node& Node::next()`) must not be marked.
+    pub fn add_chaining_method(&mut self, path: CppPath) {
+        self.chaining_methods.push(path);
+    }
+
+    /// Returns values added by `Config::add_chaining_method`.
+    pub fn chaining_methods(&self) -> &[CppPath] {
+        &self.chaining_methods
+    }
+
+    /// Marks the arguments with `callback_index` and `user_data_index` of the C++ function
+    /// with `path` as a callback and the user data pointer passed to it
+    /// (e.g. `register(void (*cb)(int, void*), void* user_data)`). All overloads
+    /// of the function are affected. The callback must return `void` and
+    /// accept the user data pointer (`void*`) as its last argument.
+    ///
+    /// The Rust wrapper accepts a closure in place of both arguments. The closure is
+    /// passed to C++ as the user data, and a generated `extern "C"` function calling it
+    /// is passed as the callback. The closure is never freed, as the wrapper can't know
+    /// when the C++ library stops using it.
+    pub fn add_callback_argument(
+        &mut self,
+        path: CppPath,
+        callback_index: usize,
+        user_data_index: usize,
+    ) {
+        self.callback_arguments
+            .push((path, callback_index, user_data_index));
+    }
+
+    /// Returns values added by `Config::add_callback_argument`.
+    pub fn callback_arguments(&self) -> &[(CppPath, usize, usize)] {
+        &self.callback_arguments
+    }
+
+    /// Enables generation of a single `new` function accepting an enum of
+    /// constructor arguments for the C++ class with `path`. The enum
+    /// (e.g. `QPointNewArgs` for `QPoint`) has a variant for each overloaded
+    /// constructor, and the function calls the constructor matching the passed variant.
+    /// The separate Rust wrappers of the constructors are still generated.
+    ///
+    /// Constructors accepting references or closures and constructors not available
+    /// in all environments are not included in the enum.
+    pub fn add_constructor_enum_class(&mut self, path: CppPath) {
+        self.constructor_enum_classes.push(path);
+    }
+
+    /// Returns values added by `Config::add_constructor_enum_class`.
+    pub fn constructor_enum_classes(&self) -> &[CppPath] {
+        &self.constructor_enum_classes
+    }
+
+    /// Marks the static `const char* const` class field with `path` as always pointing to
+    /// a valid UTF-8 string. The Rust getter of such a field returns `Option<&'static str>`
+    /// instead of `Option<&'static CStr>`.
+    ///
+    /// Getters of all static `const char* const` fields return values with `'static`
+    /// lifetime, as these fields are assumed to point to strings with static storage
+    /// duration. `None` is returned if the field contains a null pointer.
+    pub fn add_utf8_string_constant(&mut self, path: CppPath) {
+        self.utf8_string_constants.push(path);
+    }
+
+    /// Returns values added by `Config::add_utf8_string_constant`.
+    pub fn utf8_string_constants(&self) -> &[CppPath] {
+        &self.utf8_string_constants
+    }
+
+    /// Adds a benchmark of the Rust wrappers of the C++ function with `path`
+    /// to the generated crate. If any benchmarks are added, the crate gets
+    /// a `ritual_benchmarks` bench target that only builds when the `ritual_benchmarks`
+    /// feature is enabled (`cargo bench --features ritual_benchmarks`).
+    ///
+    /// The benchmarks call the wrappers with default argument values, so only functions
+    /// with primitive, `QFlags` and `Duration` arguments are supported. The object
+    /// for a method call is constructed with the class's default constructor.
+    pub fn add_benchmark_function(&mut self, path: CppPath) {
+        self.benchmark_functions.push(path);
+    }
+
+    /// Returns values added by `Config::add_benchmark_function`.
+    pub fn benchmark_functions(&self) -> &[CppPath] {
+        &self.benchmark_functions
+    }
+
+    /// Requests an instantiation of the function template with `path` that is templated
+    /// only on its return type (e.g. `template<class T> T get()`). Such templates can't
+    /// be instantiated automatically because their template argument can't be deduced
+    /// from the arguments. `return_type` is used as the explicit template argument,
+    /// and the name of the generated Rust wrapper is based on it (e.g. `get_f64`).
+    ///
+    /// `path` shouldn't contain template arguments of the function.
+    pub fn add_return_type_template_instantiation(&mut self, path: CppPath, return_type: CppType) {
+        self.return_type_template_instantiations
+            .push((path, return_type));
+    }
+
+    /// Returns values added by `Config::add_return_type_template_instantiation`.
+    pub fn return_type_template_instantiations(&self) -> &[(CppPath, CppType)] {
+        &self.return_type_template_instantiations
+    }
+
+    /// Checks that all C++ paths referenced in the config resolve to C++ items
+    /// of the current database or its dependencies and returns
+    /// the paths that don't. `db` should contain the output of the C++ parser.
+    /// Hooks are arbitrary functions, so they are not checked.
+    pub fn unresolved_paths(&self, db: &DatabaseClient) -> Vec<UnresolvedConfigPath> {
+        let mut unresolved = Vec::new();
+        let mut check = |setting: &'static str,
+                         paths: &mut dyn Iterator<Item = &CppPath>,
+                         is_resolved_by: fn(&CppItem, &CppPath) -> bool| {
+            check_paths(db, setting, paths, is_resolved_by, &mut unresolved)
+        };
+        check(
+            "add_thread_safe_function",
+            &mut self.thread_safe_functions.iter(),
+            is_function,
+        );
+        check(
+            "add_ownership_transfer_argument",
+            &mut self
+                .ownership_transfer_arguments
+                .iter()
+                .map(|(path, _)| path),
+            is_function,
+        );
+        check(
+            "add_success_flag_function",
+            &mut self.success_flag_functions.iter(),
+            is_function,
+        );
+        check(
+            "add_chaining_method",
+            &mut self.chaining_methods.iter(),
+            is_function,
+        );
+        check(
+            "add_callback_argument",
+            &mut self.callback_arguments.iter().map(|(path, _, _)| path),
+            is_function,
+        );
+        check(
+            "add_registration_guard",
+            &mut self
+                .registration_guards
+                .iter()
+                .flat_map(|(register, unregister)| vec![register, unregister]),
+            is_function,
+        );
+        check(
+            "add_transparent_layout_type",
+            &mut self.transparent_layout_types.iter(),
+            is_type,
+        );
+        check(
+            "add_non_relocatable_type",
+            &mut self.non_relocatable_types.iter(),
+            is_type,
+        );
+        check("add_movable_type", &mut self.movable_types.iter(), is_type);
+        check(
+            "add_non_movable_type",
+            &mut self.non_movable_types.iter(),
+            is_type,
+        );
+        check("add_root_class", &mut self.root_classes.iter(), is_type);
+        check(
+            "add_allowed_path_prefix",
+            &mut self.allowed_path_prefixes.iter(),
+            has_prefix,
+        );
+        check(
+            "add_duration_type",
+            &mut self.duration_types.iter().map(|(path, _)| path),
+            is_type,
+        );
+        check(
+            "add_constructor_enum_class",
+            &mut self.constructor_enum_classes.iter(),
+            is_type,
+        );
+        check(
+            "add_utf8_string_constant",
+            &mut self.utf8_string_constants.iter(),
+            is_field,
+        );
+        check(
+            "add_benchmark_function",
+            &mut self.benchmark_functions.iter(),
+            is_function,
+        );
+        check(
+            "add_return_type_template_instantiation",
+            &mut self
+                .return_type_template_instantiations
+                .iter()
+                .map(|(path, _)| path),
+            is_function_template,
+        );
+        check(
+            "add_feature_macro",
+            &mut self.feature_macros.iter().flat_map(|(_, paths)| paths),
+            has_prefix,
+        );
+        unresolved
+    }
+}
+
+/// Adds each of `paths` for which `is_resolved_by` returns false for all items
+/// of `db` to `unresolved`.
+fn check_paths<'a>(
+    db: &DatabaseClient,
+    setting: &'static str,
+    paths: &mut dyn Iterator<Item = &'a CppPath>,
+    is_resolved_by: fn(&CppItem, &CppPath) -> bool,
+    unresolved: &mut Vec<UnresolvedConfigPath>,
+) {
+    for path in paths {
+        if !db
+            .all_cpp_items()
+            .any(|item| is_resolved_by(item.item, path))
+        {
+            unresolved.push(UnresolvedConfigPath {
+                setting,
+                path: path.clone(),
+            });
+        }
+    }
+}
+
+fn is_function(item: &CppItem, path: &CppPath) -> bool {
+    item.as_function_ref()
+        .map_or(false, |function| &function.path == path)
+}
+
+/// Returns true if `item` is a function with `path`, ignoring template arguments
+/// of the function.
+fn is_function_template(item: &CppItem, path: &CppPath) -> bool {
+    item.as_function_ref().map_or(false, |function| {
+        function.path.parent_parts().ok() == path.parent_parts().ok()
+            && function.path.last().name == path.last().name
+    })
+}
+
+fn is_type(item: &CppItem, path: &CppPath) -> bool {
+    item.as_type_ref()
+        .map_or(false, |type1| &type1.path == path)
+}
+
+fn is_field(item: &CppItem, path: &CppPath) -> bool {
+    item.as_field_ref()
+        .map_or(false, |field| &field.path == path)
+}
+
+fn has_prefix(item: &CppItem, prefix: &CppPath) -> bool {
+    item.path().map_or(false, |path| path.starts_with(prefix))
 }
 
 #[derive(Default)]
@@ -26,19 +26,41 @@ fn create_cast_method(cast: CppCast, from: &CppType, to: &CppType) -> Result<Cpp
         }],
         allows_variadic_arguments: false,
         declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_deleted: false,
         cast: Some(cast),
     };
     Ok(CppItem::Function(function))
 }
 
+/// Returns false if `class_path` is known to be a non-polymorphic class,
+/// so `dynamic_cast` can't be applied to it.
+fn is_polymorphic(class_path: &CppPath, data: &ProcessorData<'_>) -> bool {
+    data.db
+        .all_cpp_items()
+        .filter_map(|i| i.item.as_type_ref())
+        .find(|t| &t.path == class_path)
+        .map_or(true, |t| t.kind.is_polymorphic())
+}
+
 /// Performs a portion of `generate_casts` operation.
 /// Adds casts between `target_type` and `base_type` and calls
 /// `generate_casts_one` recursively to add casts between `target_type`
-/// and base types of `base_type`.
+/// and base types of `base_type` (unless disabled with
+/// `Config::set_transitive_casts`).
+///
+/// Casts to indirect bases have no `direct_base_index`. The base index
+/// doesn't affect the generated C++ code; it's only used to select the base
+/// for `Deref` implementations. `base_chain` contains the intermediate bases
+/// between `target_type` and `base_type`, and the generated C++ code casts
+/// through each of them.
 fn generate_casts_one(
     target_type: &CppPath,
     base_type: &CppPath,
     direct_base_index: Option<usize>,
+    base_chain: &[CppPath],
     data: &ProcessorData<'_>,
 ) -> Result<Vec<CppItem>> {
     let target_ptr_type = CppType::PointerLike {
@@ -52,34 +74,46 @@ fn generate_casts_one(
         target: Box::new(CppType::Class(base_type.clone())),
     };
     let mut new_methods = Vec::new();
-    new_methods.push(create_cast_method(
-        CppCast::Static {
-            is_unsafe: true,
-            base_index: direct_base_index,
-        },
-        &base_ptr_type,
-        &target_ptr_type,
-    )?);
+    if data.config.unsafe_static_downcasts() {
+        new_methods.push(create_cast_method(
+            CppCast::Static {
+                is_unsafe: true,
+                base_index: direct_base_index,
+                base_chain: base_chain.to_vec(),
+            },
+            &base_ptr_type,
+            &target_ptr_type,
+        )?);
+    }
     new_methods.push(create_cast_method(
         CppCast::Static {
             is_unsafe: false,
             base_index: direct_base_index,
+            base_chain: base_chain.to_vec(),
         },
         &target_ptr_type,
         &base_ptr_type,
     )?);
-    new_methods.push(create_cast_method(
-        CppCast::Dynamic,
-        &base_ptr_type,
-        &target_ptr_type,
-    )?);
+    if is_polymorphic(base_type, data) {
+        new_methods.push(create_cast_method(
+            CppCast::Dynamic,
+            &base_ptr_type,
+            &target_ptr_type,
+        )?);
+    }
 
+    if !data.config.transitive_casts() {
+        return Ok(new_methods);
+    }
+    let mut next_base_chain = base_chain.to_vec();
+    next_base_chain.push(base_type.clone());
     for item in data.db.all_cpp_items().filter_map(|i| i.item.as_base_ref()) {
         if &item.derived_class_type == base_type {
             new_methods.extend(generate_casts_one(
                 target_type,
                 &item.base_class_type,
                 None,
+                &next_base_chain,
                 data,
             )?);
         }
@@ -95,6 +129,7 @@ fn generate_casts(base: &CppBaseSpecifier, data: &ProcessorData<'_>) -> Result<V
         &base.derived_class_type,
         &base.base_class_type,
         Some(base.base_index),
+        &[],
         data,
     )
 }
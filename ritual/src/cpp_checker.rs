@@ -15,11 +15,9 @@ use ritual_common::cpp_lib_builder::{
     BuildType, CMakeConfigData, CppLibBuilder, CppLibBuilderOutput,
 };
 use ritual_common::errors::{bail, err_msg, Result};
-use ritual_common::file_utils::{
-    create_dir_all, create_file, os_str_to_str, path_to_str, remove_dir_all,
-};
-use ritual_common::target::{current_target, LibraryTarget};
-use ritual_common::utils::{MapIfOk, ProgressBar};
+use ritual_common::file_utils::{create_dir_all, create_file, os_str_to_str, remove_dir_all};
+use ritual_common::target::{current_target, CppStdLib, LibraryTarget};
+use ritual_common::utils::ProgressBar;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::{hash_map::Entry, HashMap};
 use std::io::Write;
@@ -160,6 +158,23 @@ impl CppCheckerInstance {
         Ok(())
     }
 
+    /// Detects the C++ standard library implementation used by the compiler.
+    pub fn detect_cpp_std_lib(&mut self) -> Result<Option<CppStdLib>> {
+        for &cpp_std_lib in CppStdLib::all() {
+            let snippet = Snippet::new_global(
+                format!(
+                    "#ifndef {}\n#error \"not detected\"\n#endif",
+                    cpp_std_lib.detection_macro()
+                ),
+                false,
+            );
+            if self.check_snippets(iter::once(&snippet))?.is_success() {
+                return Ok(Some(cpp_std_lib));
+            }
+        }
+        Ok(None)
+    }
+
     pub fn binary_check<T>(
         &mut self,
         snippets: &mut [SnippetTask<T>],
@@ -264,7 +279,7 @@ impl PreliminaryTest {
 #[derive(Debug, Clone)]
 pub struct LocalCppChecker {
     parent_path: PathBuf,
-    include_directives: Vec<PathBuf>,
+    include_directives_code: String,
     crate_name: String,
     cpp_build_config: CppBuildConfigData,
     cpp_build_paths: CppBuildPaths,
@@ -278,7 +293,7 @@ impl LocalCppChecker {
 
         Ok(LocalCppChecker {
             parent_path: parent_path.into(),
-            include_directives: config.include_directives().to_vec(),
+            include_directives_code: config.include_directives_code()?,
             crate_name: config.crate_properties().name().to_string(),
             cpp_build_paths: {
                 let mut data = config.cpp_build_paths().clone();
@@ -309,11 +324,7 @@ impl LocalCppChecker {
         write!(
             utils_file,
             include_str!("../templates/cpp_checker/utils.h"),
-            include_directives_code = self
-                .include_directives
-                .iter()
-                .map_if_ok(|d| -> Result<_> { Ok(format!("#include \"{}\"", path_to_str(d)?)) })?
-                .join("\n")
+            include_directives_code = self.include_directives_code
         )?;
 
         let cmake_config = CMakeConfigData {
@@ -321,6 +332,7 @@ impl LocalCppChecker {
             cpp_build_paths: &self.cpp_build_paths,
             library_type: None,
             cpp_library_version: None,
+            cpp_defines: Vec::new(),
         };
 
         let builder = CppLibBuilder {
@@ -419,10 +431,11 @@ fn builtin_tests() -> Vec<PreliminaryTest> {
 }
 
 impl CppChecker<'_, '_> {
-    fn env(&self) -> LibraryTarget {
+    fn env(&self, cpp_std_lib: Option<CppStdLib>) -> LibraryTarget {
         LibraryTarget {
             target: current_target(),
             cpp_library_version: self.data.config.cpp_lib_version().map(ToString::to_string),
+            cpp_std_lib,
         }
     }
 
@@ -449,6 +462,7 @@ impl CppChecker<'_, '_> {
                     .map(move |lib| LibraryTarget {
                         target: worker.target.clone(),
                         cpp_library_version: lib.lib_version.clone(),
+                        cpp_std_lib: None,
                     })
             })
             .collect_vec();
@@ -475,7 +489,16 @@ impl CppChecker<'_, '_> {
             &self.data.config,
         )?;
 
-        let env = self.env();
+        let mut instance = instance_provider.get("tests")?;
+        let cpp_std_lib = instance.detect_cpp_std_lib()?;
+        if let Some(cpp_std_lib) = cpp_std_lib {
+            info!(
+                "Detected C++ standard library: {}",
+                cpp_std_lib.short_text()
+            );
+        }
+
+        let env = self.env(cpp_std_lib);
 
         self.data.db.add_environment(env.clone());
 
@@ -484,7 +507,6 @@ impl CppChecker<'_, '_> {
             return Ok(());
         }
 
-        let mut instance = instance_provider.get("tests")?;
         instance.check_preliminary_tests()?;
 
         let progress_bar = ProgressBar::new(snippets.len() as u64, "Checking items");
@@ -619,6 +641,15 @@ fn type_paths(type1: &CppType) -> Vec<&CppPath> {
             .chain(once(&*function.return_type))
             .flat_map(|type1| type_paths(type1))
             .collect(),
+        CppType::MemberFunctionPointer(function) => once(&function.class_path)
+            .chain(
+                function
+                    .arguments
+                    .iter()
+                    .chain(once(&*function.return_type))
+                    .flat_map(|type1| type_paths(type1)),
+            )
+            .collect(),
         CppType::PointerLike { target, .. } => type_paths(target),
     }
 }
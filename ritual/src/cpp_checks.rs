@@ -1,5 +1,7 @@
+use crate::cpp_data::{CppItem, CppPath};
 use itertools::Itertools;
 use ritual_common::target::{Arch, Endian, Env, Family, LibraryTarget, PointerWidth, OS};
+use ritual_common::FeatureMacro;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -112,9 +114,64 @@ pub enum Condition {
     Env(Env),
     PointerWidth(PointerWidth),
     Endian(Endian),
+    Feature(FeatureMacro),
     And(Vec<Condition>),
     Or(Vec<Condition>),
     Not(Box<Condition>),
     True,
     False,
 }
+
+impl Condition {
+    pub fn and(self, other: Condition) -> Condition {
+        match (self, other) {
+            (Condition::True, other) | (other, Condition::True) => other,
+            (Condition::False, _) | (_, Condition::False) => Condition::False,
+            (Condition::And(mut conditions), other) => {
+                conditions.push(other);
+                Condition::And(conditions)
+            }
+            (first, other) => Condition::And(vec![first, other]),
+        }
+    }
+}
+
+/// Returns condition of availability of the C++ item with `path`
+/// based on feature macros configured with `Config::add_feature_macro`.
+pub fn feature_condition(
+    path: &CppPath,
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+) -> Condition {
+    paths_feature_condition(&[path.clone()], feature_macros)
+}
+
+/// Returns condition of availability of `item` based on feature macros
+/// configured with `Config::add_feature_macro`. Unlike `feature_condition`,
+/// this also takes into account all types used by the item, so that e.g.
+/// a method taking a guarded class or a cast to it is guarded as well.
+pub fn item_feature_condition(
+    item: &CppItem,
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+) -> Condition {
+    let mut paths = item.path().into_iter().cloned().collect_vec();
+    for type1 in item.all_involved_types() {
+        type1.collect_paths(&mut paths);
+    }
+    paths_feature_condition(&paths, feature_macros)
+}
+
+fn paths_feature_condition(
+    paths: &[CppPath],
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+) -> Condition {
+    feature_macros
+        .iter()
+        .filter(|(_, prefixes)| {
+            paths
+                .iter()
+                .any(|path| prefixes.iter().any(|prefix| path.starts_with(prefix)))
+        })
+        .fold(Condition::True, |condition, (feature, _)| {
+            condition.and(Condition::Feature(feature.clone()))
+        })
+}
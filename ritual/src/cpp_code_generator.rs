@@ -1,44 +1,616 @@
-use crate::cpp_checks::Condition;
+use crate::cpp_checks::{item_feature_condition, Condition};
+use crate::cpp_data::{CppItem, CppPath, CppVisibility};
 use crate::cpp_ffi_data::{
-    CppFfiArgumentMeaning, CppFfiFunctionKind, CppFfiType, CppFieldAccessorType,
+    CppCast, CppFfiArgumentMeaning, CppFfiFunctionKind, CppFfiType, CppFieldAccessorType,
     CppToFfiTypeConversion, QtSlotWrapper,
 };
-use crate::cpp_ffi_data::{CppFfiFunction, CppFfiItem};
-use crate::cpp_function::ReturnValueAllocationPlace;
+use crate::cpp_ffi_data::{CppFfiFunction, CppFfiFunctionArgument, CppFfiItem};
+use crate::cpp_function::{CppFunction, ReturnValueAllocationPlace};
 use crate::cpp_type::CppPointerLikeTypeKind;
-use crate::cpp_type::CppType;
+use crate::cpp_type::{
+    CppBuiltInNumericType, CppFunctionPointerType, CppSpecificNumericType,
+    CppSpecificNumericTypeKind, CppType,
+};
 use crate::database::{DatabaseClient, DbItem};
-use crate::rust_info::{RustItem, RustStructKind};
+use crate::rust_info::{RustItem, RustStructField, RustStructKind, RustWrapperTypeKind};
 use itertools::Itertools;
+use log::debug;
 use ritual_common::cpp_lib_builder::version_to_number;
 use ritual_common::errors::{bail, err_msg, format_err, Result};
-use ritual_common::file_utils::{create_file, os_str_to_str, path_to_str};
+use ritual_common::file_utils::{create_file, os_str_to_str};
 use ritual_common::utils::MapIfOk;
-use std::collections::HashSet;
+use ritual_common::FeatureMacro;
+use std::collections::{BTreeSet, HashSet};
 use std::io::Write;
 use std::iter::once;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 struct Generator<'a>(&'a DatabaseClient);
 
-impl Generator<'_> {
-    /// Generates function name, return type and arguments list
-    /// as it appears in both function declaration and implementation.
-    fn function_signature(&self, method: &CppFfiFunction) -> Result<String> {
-        let mut arg_texts = Vec::new();
-        for arg in &method.arguments {
-            arg_texts.push(arg.to_cpp_code()?);
+/// Version of the generator embedded in both the C++ wrapper library and the Rust crate.
+pub const GENERATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Returns name of the FFI function that returns `GENERATOR_VERSION`
+/// the C++ wrapper library was generated with.
+pub fn generator_version_function_name(crate_name: &str) -> String {
+    format!("{}_ritual_generator_version", crate_name)
+}
+
+/// Generates `static_assert`s verifying that the layout of a C++ class
+/// matches the layout of a `#[repr(C)]` struct with `fields`.
+/// A C struct with the same fields is declared for comparison
+/// because `#[repr(C)]` Rust structs have the same layout as C structs.
+pub fn transparent_layout_checks(cpp_path: &CppPath, fields: &[RustStructField]) -> Result<String> {
+    let class_code = cpp_path.to_cpp_code()?;
+    let mirror_name = format!("ritual_layout_{}", cpp_path.ascii_caption());
+    let mut code = format!("struct {} {{\n", mirror_name);
+    for field in fields {
+        code += &format!(
+            "  {} {};\n",
+            field.cpp_type.to_cpp_code(None)?,
+            field.cpp_name
+        );
+    }
+    code += "};\n";
+    for function in &["sizeof", "alignof"] {
+        code += &format!(
+            "static_assert({func}({mirror}) == {func}({class}), \
+             \"{func}({class}) doesn't match the generated Rust struct\");\n",
+            func = function,
+            mirror = mirror_name,
+            class = class_code
+        );
+    }
+    for field in fields {
+        code += &format!(
+            "static_assert(offsetof({mirror}, {field}) == offsetof({class}, {field}), \
+             \"offset of {class}::{field} doesn't match the generated Rust struct\");\n",
+            mirror = mirror_name,
+            class = class_code,
+            field = field.cpp_name
+        );
+    }
+    Ok(code)
+}
+
+/// Adds pointer-sized integer types (like `size_t`) used in `cpp_type` to `output`.
+fn collect_pointer_sized_integers(cpp_type: &CppType, output: &mut Vec<CppType>) {
+    match cpp_type {
+        CppType::PointerSizedInteger { .. } => {
+            if !output.contains(cpp_type) {
+                output.push(cpp_type.clone());
+            }
+        }
+        CppType::PointerLike { target, .. } => collect_pointer_sized_integers(target, output),
+        _ => {}
+    }
+}
+
+/// Generates C++ code that prints a Rust compile-time check verifying that
+/// the size of the pointer-sized integer type `cpp_type` is equal to the size of
+/// `usize` (or `isize` for signed types) on the current target. The FFI functions use
+/// `usize` and `isize` for these types, so their width must not be assumed.
+pub fn pointer_sized_integer_size_check(cpp_type: &CppType) -> Result<String> {
+    let rust_type = if let CppType::PointerSizedInteger { is_signed, .. } = cpp_type {
+        if *is_signed {
+            "isize"
+        } else {
+            "usize"
+        }
+    } else {
+        bail!("not a pointer-sized integer type: {:?}", cpp_type);
+    };
+    Ok(format!(
+        "printf(\"const _: [(); %zu] = [(); ::std::mem::size_of::<{}>()];\\n\", sizeof({}));",
+        rust_type,
+        cpp_type.to_cpp_code(None)?
+    ))
+}
+
+/// Returns true if `item` is a protected member function or field.
+fn is_protected_member(item: &CppItem) -> bool {
+    match item {
+        CppItem::Function(function) => function.is_protected(),
+        CppItem::ClassField(field) => field.visibility == CppVisibility::Protected,
+        _ => false,
+    }
+}
+
+/// Returns name of the subclass generated for accessing the protected member
+/// wrapped by the FFI function `method`.
+fn protected_shim_name(method: &CppFfiFunction) -> String {
+    format!("ritual_shim_{}", method.path.last().name)
+}
+
+/// Generates a subclass of the class of the protected `member`
+/// (a function, a constructor or a field) that makes the member accessible to
+/// the FFI wrapper `method`.
+///
+/// Constructors inherited with `using Base::Base;` keep their protected access,
+/// so for a protected constructor the subclass gets a public constructor
+/// forwarding its arguments to the base class. The FFI wrapper then constructs
+/// the subclass instead of the class itself. The subclass has no own data,
+/// so its objects can be used in place of objects of the base class, but their
+/// dynamic type (e.g. reported by `typeid`) is the subclass. The subclass
+/// of an abstract class would be abstract too, so protected constructors
+/// of abstract classes are not wrapped (see `cpp_ffi_generator::abstract_classes`).
+pub fn protected_shim(method: &CppFfiFunction, member: &CppItem) -> Result<String> {
+    let member_path = member
+        .path()
+        .ok_or_else(|| err_msg("protected member expected to have a path"))?;
+    let class_path = member_path.parent()?.to_cpp_code()?;
+    let shim_name = protected_shim_name(method);
+    let is_constructor = member
+        .as_function_ref()
+        .map_or(false, |f| f.is_constructor());
+    if is_constructor {
+        Ok(format!(
+            "struct {shim} : public {class} {{\n  \
+             template<typename... Args>\n  \
+             {shim}(Args&&... args) : {class}(std::forward<Args>(args)...) {{}}\n\
+             }};\n\
+             static_assert(sizeof({shim}) == sizeof({class}), \"unexpected shim size\");\n",
+            shim = shim_name,
+            class = class_path
+        ))
+    } else {
+        Ok(format!(
+            "struct {} : public {} {{\n  using {}::{};\n}};\n",
+            shim_name,
+            class_path,
+            class_path,
+            member_path.last().to_cpp_code()?
+        ))
+    }
+}
+
+/// Generates code for the value of `argument` passed to the original C++ method.
+/// See `arguments_values` for the meaning of `is_constructor`.
+fn argument_value(argument: &CppFfiFunctionArgument, is_constructor: bool) -> Result<String> {
+    let mut result = argument.name.clone();
+    match argument.argument_type.conversion() {
+        CppToFfiTypeConversion::ValueToPointer { is_ffi_const } => {
+            result = if is_constructor && !is_ffi_const {
+                format!("std::move(*{})", result)
+            } else {
+                format!("*{}", result)
+            };
+        }
+        CppToFfiTypeConversion::ReferenceToPointer => result = format!("*{}", result),
+        CppToFfiTypeConversion::RValueReferenceToPointer => {
+            result = format!("std::move(*{})", result)
+        }
+        CppToFfiTypeConversion::NoChange | CppToFfiTypeConversion::ImplicitCast { .. } => {}
+        CppToFfiTypeConversion::QFlagsToInt | CppToFfiTypeConversion::DurationToTicks { .. } => {
+            let type_text = if let CppType::PointerLike {
+                kind,
+                is_const,
+                target,
+            } = argument.argument_type.original_type()
+            {
+                if *kind == CppPointerLikeTypeKind::Reference && *is_const {
+                    target.to_cpp_code(None)?
+                } else {
+                    bail!(
+                        "Unsupported original type for {:?} conversion",
+                        argument.argument_type.conversion()
+                    );
+                }
+            } else {
+                argument.argument_type.original_type().to_cpp_code(None)?
+            };
+            result = format!("{}({})", type_text, result);
+        }
+        CppToFfiTypeConversion::OrderingToInt => {
+            bail!("OrderingToInt is not supported in argument position");
+        }
+        CppToFfiTypeConversion::OptionalToValue => {
+            bail!("OptionalToValue is not supported in argument position");
+        }
+        CppToFfiTypeConversion::MemberFunctionPointerToStruct => {
+            result = format!(
+                "ritual_to_member_function_pointer<{}>({})",
+                argument
+                    .argument_type
+                    .original_type()
+                    .to_cpp_code(Some(""))?,
+                result
+            );
+        }
+        // the list is constructed by `with_initializer_lists`
+        CppToFfiTypeConversion::InitializerListToPointer => result = format!("{}_list", result),
+    }
+    Ok(result)
+}
+
+/// Maximal number of elements of an `std::initializer_list` argument.
+/// Must match `RITUAL_MAX_INITIALIZER_LIST_SIZE` in the global header.
+pub const MAX_INITIALIZER_LIST_SIZE: usize = 16;
+
+/// Wraps `expression` calling the original C++ method in calls of
+/// `ritual_with_initializer_list` that construct the `std::initializer_list`
+/// arguments of `method` from the passed elements. The lists are passed
+/// to `expression` as parameters of lambda functions.
+pub fn with_initializer_lists(method: &CppFfiFunction, expression: String) -> Result<String> {
+    let mut result = expression;
+    for arg in method.arguments.iter().rev() {
+        if arg.argument_type.conversion() != &CppToFfiTypeConversion::InitializerListToPointer {
+            continue;
         }
-        let name_with_args = format!("{}({})", method.path.to_cpp_code()?, arg_texts.join(", "));
-        let return_type = method.return_type.ffi_type();
-        let r = if let CppType::FunctionPointer(..) = return_type {
-            return_type.to_cpp_code(Some(&name_with_args))?
+        let index = if let CppFfiArgumentMeaning::Argument(index) = arg.meaning {
+            index
         } else {
-            format!("{} {}", return_type.to_cpp_code(None)?, name_with_args)
+            bail!(
+                "unexpected meaning of initializer list argument: {:?}",
+                arg.meaning
+            );
         };
-        Ok(r)
+        let length_arg = method
+            .arguments
+            .iter()
+            .find(|x| x.meaning == CppFfiArgumentMeaning::ArrayLength(index))
+            .ok_or_else(|| err_msg("initializer list length argument not found"))?;
+        result = format!(
+            "ritual_with_initializer_list({name}, {len}, [&](std::initializer_list<{t}> \
+             {name}_list) {{ return {expr}; }})",
+            name = arg.name,
+            len = length_arg.name,
+            t = arg
+                .argument_type
+                .ffi_type()
+                .pointer_like_to_target()?
+                .to_cpp_code(None)?,
+            expr = result
+        );
     }
+    Ok(result)
+}
+
+/// Generates code that converts `expression` returned by the callback of a slot wrapper
+/// (of type `return_type.ffi_type`) to the return type of the slot
+/// (`return_type.original_type`).
+pub fn convert_slot_return_value(return_type: &CppFfiType, expression: String) -> Result<String> {
+    Ok(match return_type.conversion() {
+        CppToFfiTypeConversion::NoChange => expression,
+        CppToFfiTypeConversion::QFlagsToInt => format!(
+            "{}({})",
+            return_type.original_type().to_cpp_code(None)?,
+            expression
+        ),
+        conversion => bail!("{:?} is not supported for slot return values", conversion),
+    })
+}
 
+/// Generates code for values passed to the original C++ method.
+///
+/// If `is_constructor` is true, arguments passed by value through a non-const
+/// pointer are owned by the wrapper, so they are wrapped in `std::move`
+/// to allow the move constructor of the argument type to be selected.
+/// See `owned_argument_guards`.
+pub fn arguments_values(method: &CppFfiFunction, is_constructor: bool) -> Result<String> {
+    let r = method
+        .arguments
+        .iter()
+        .filter(|arg| arg.meaning.is_argument())
+        .map_if_ok(|argument| argument_value(argument, is_constructor))?;
+    Ok(r.join(", "))
+}
+
+/// Generates statements that take ownership of the arguments of `method`
+/// that are moved into the original C++ method (see `arguments_values`),
+/// including the source object of a move constructor.
+/// The Rust caller gives up ownership of such arguments (they are passed
+/// as `CppBox`), so the moved-from objects are deleted when the wrapper returns,
+/// even if an exception is thrown.
+pub fn owned_argument_guards(method: &CppFfiFunction, is_constructor: bool) -> Result<String> {
+    let mut result = String::new();
+    if !is_constructor {
+        return Ok(result);
+    }
+    for arg in &method.arguments {
+        if !arg.meaning.is_argument() {
+            continue;
+        }
+        let is_owned = match arg.argument_type.conversion() {
+            CppToFfiTypeConversion::ValueToPointer { is_ffi_const } => !is_ffi_const,
+            CppToFfiTypeConversion::RValueReferenceToPointer => true,
+            _ => false,
+        };
+        if is_owned {
+            result += &format!(
+                "std::unique_ptr<{t}> {name}_owner({name});\n  ",
+                t = arg
+                    .argument_type
+                    .ffi_type()
+                    .pointer_like_to_target()?
+                    .to_cpp_code(None)?,
+                name = arg.name
+            );
+        }
+    }
+    Ok(result)
+}
+
+/// Wraps `body` of an FFI function in a `try` block that writes the message
+/// of any caught exception to the `exception_arg` buffer.
+/// A value-initialized value is returned if an exception was caught.
+pub fn catch_exceptions(body: &str, exception_arg: &str, is_void: bool) -> String {
+    format!(
+        "try {{\n    {body}  }} catch (const std::exception& e) {{\n    \
+         ritual_write_exception({arg}, e.what());\n  \
+         }} catch (...) {{\n    \
+         ritual_write_exception({arg}, \"unknown C++ exception\");\n  \
+         }}\n{ret}",
+        body = body,
+        arg = exception_arg,
+        ret = if is_void { "" } else { "  return {};\n" },
+    )
+}
+
+/// Wraps `argument` of a cast wrapper in `static_cast`s to each of
+/// the intermediate bases of `cast`. Casting through the declared base
+/// classes one step at a time makes the compiler apply the correct offset
+/// of each base subobject and avoids ambiguous conversions when
+/// the target base is reachable through multiple inheritance paths.
+/// `from_type` is the type of the argument.
+pub fn cast_argument(cast: &CppCast, from_type: &CppType, argument: String) -> Result<String> {
+    let is_const = if let CppType::PointerLike { is_const, .. } = from_type {
+        *is_const
+    } else {
+        false
+    };
+    let mut chain = cast.base_chain().iter().collect_vec();
+    if cast.is_unsafe_static_cast() {
+        // downcasts start from the base class
+        chain.reverse();
+    }
+    let mut result = argument;
+    for base in chain {
+        let base_type = CppType::new_pointer(is_const, CppType::Class(base.clone()));
+        result = format!("static_cast<{}>({})", base_type.to_cpp_code(None)?, result);
+    }
+    Ok(result)
+}
+
+/// Generates `static_assert`s verifying that the periods of duration types used by
+/// the wrapper `method` match the periods specified in `Config::add_duration_type`.
+pub fn duration_period_checks(method: &CppFfiFunction) -> Result<String> {
+    let mut result = String::new();
+    let types = once(&method.return_type).chain(method.arguments.iter().map(|a| &a.argument_type));
+    for type1 in types {
+        let period = if let CppToFfiTypeConversion::DurationToTicks { period } = type1.conversion()
+        {
+            period
+        } else {
+            continue;
+        };
+        let duration_type = match type1.original_type() {
+            CppType::PointerLike { target, .. } => target,
+            other => other,
+        };
+        let duration_type_code = duration_type.to_cpp_code(None)?;
+        result += &format!(
+            "static_assert(std::ratio_equal<{t}::period, std::ratio<{n}, {d}>>::value, \
+             \"unexpected period of {t}\");\n  ",
+            t = duration_type_code,
+            n = period.numerator,
+            d = period.denominator,
+        );
+    }
+    Ok(result)
+}
+
+/// Generates statements that check that the arrays passed to the wrapper
+/// `method` of `cpp_function` are not shorter than the sizes declared in
+/// the C++ function (see `CppFfiArgumentMeaning::ArrayLength`) and that
+/// the elements of `std::initializer_list` arguments fit into
+/// `RITUAL_MAX_INITIALIZER_LIST_SIZE`. If the wrapper
+/// catches exceptions, `std::length_error` is thrown for an invalid length,
+/// so the error is reported to the caller. Otherwise the process is aborted.
+pub fn array_length_checks(method: &CppFfiFunction, cpp_function: &CppFunction) -> Result<String> {
+    let catches_exceptions = method
+        .arguments
+        .iter()
+        .any(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionOutput);
+    let mut result = String::new();
+    for arg in &method.arguments {
+        let index = if let CppFfiArgumentMeaning::ArrayLength(index) = arg.meaning {
+            index
+        } else {
+            continue;
+        };
+        let name = &cpp_function.arguments[index].name;
+        let (condition, message) = if cpp_function.arguments[index]
+            .argument_type
+            .is_initializer_list()
+        {
+            (
+                format!("{} > RITUAL_MAX_INITIALIZER_LIST_SIZE", arg.name),
+                format!("initializer list argument {} is too long", name),
+            )
+        } else {
+            let size = cpp_function
+                .fixed_array_argument_size(index)
+                .ok_or_else(|| {
+                    err_msg("array length argument for an argument that is not an array")
+                })?;
+            (
+                format!("{} < {}", arg.name, size),
+                format!("array argument {} is too short", name),
+            )
+        };
+        let failure = if catches_exceptions {
+            format!("throw std::length_error(\"{}\")", message)
+        } else {
+            "std::abort()".to_string()
+        };
+        result += &format!("if ({}) {};\n  ", condition, failure);
+    }
+    Ok(result)
+}
+
+/// Returns C code representing `cpp_type` in the C header of the wrapper library.
+///
+/// C++ classes are only allowed behind pointers. They are replaced with opaque structs
+/// named after `CppType::ascii_caption`, and the struct names are added to `opaque_structs`.
+/// Enums are passed as `int`, like in the Rust enum wrappers.
+/// `function_pointer_inner_text` has the same meaning as in `CppType::to_cpp_code`.
+pub fn c_type_code(
+    cpp_type: &CppType,
+    function_pointer_inner_text: Option<&str>,
+    opaque_structs: &mut BTreeSet<String>,
+) -> Result<String> {
+    match cpp_type {
+        CppType::Void => Ok("void".to_string()),
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Char16) => Ok("uint_least16_t".to_string()),
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Char32) => Ok("uint_least32_t".to_string()),
+        CppType::BuiltInNumeric(t) => Ok(t.to_cpp_code().to_string()),
+        CppType::SpecificNumeric(CppSpecificNumericType { bits, kind, .. }) => match kind {
+            CppSpecificNumericTypeKind::Integer { is_signed } => Ok(format!(
+                "{}int{}_t",
+                if *is_signed { "" } else { "u" },
+                bits
+            )),
+            CppSpecificNumericTypeKind::FloatingPoint => match *bits {
+                32 => Ok("float".to_string()),
+                64 => Ok("double".to_string()),
+                _ => bail!("unsupported floating point type size: {}", bits),
+            },
+        },
+        CppType::PointerSizedInteger { is_signed, .. } => Ok(if *is_signed {
+            "intptr_t".to_string()
+        } else {
+            "uintptr_t".to_string()
+        }),
+        CppType::Enum { .. } => Ok("int".to_string()),
+        CppType::Class(path) => bail!(
+            "class type {} can't be passed by value in C code",
+            path.to_cpp_pseudo_code()
+        ),
+        CppType::TemplateParameter { .. } => {
+            bail!("template parameters are not allowed in C code");
+        }
+        CppType::FunctionPointer(CppFunctionPointerType {
+            return_type,
+            arguments,
+            allows_variadic_arguments,
+        }) => {
+            if *allows_variadic_arguments {
+                bail!("function pointers with variadic arguments are not supported");
+            }
+            let mut arg_texts = Vec::new();
+            for arg in arguments {
+                arg_texts.push(c_type_code(arg, None, opaque_structs)?);
+            }
+            let function_pointer_inner_text = function_pointer_inner_text
+                .ok_or_else(|| err_msg("function_pointer_inner_text argument is missing"))?;
+            Ok(format!(
+                "{} (*{})({})",
+                c_type_code(return_type, None, opaque_structs)?,
+                function_pointer_inner_text,
+                arg_texts.join(", ")
+            ))
+        }
+        CppType::MemberFunctionPointer(..) => {
+            bail!("pointers to member functions are not allowed in C code");
+        }
+        CppType::PointerLike {
+            kind,
+            is_const,
+            target,
+        } => {
+            if *kind != CppPointerLikeTypeKind::Pointer {
+                bail!("references are not allowed in C code");
+            }
+            let target_code = if target.is_class() {
+                let name = target.ascii_caption();
+                opaque_structs.insert(name.clone());
+                format!("struct {}", name)
+            } else {
+                c_type_code(target, function_pointer_inner_text, opaque_structs)?
+            };
+            Ok(format!(
+                "{}{}*",
+                if *is_const { "const " } else { "" },
+                target_code
+            ))
+        }
+    }
+}
+
+/// Generates function name, return type and arguments list of the declaration
+/// of `method` in the C header of the wrapper library. Unlike `function_signature`,
+/// the result is valid C code: it uses the types generated by `c_type_code` and
+/// omits `noexcept` and function attributes. Struct names of the used C++ classes
+/// are added to `opaque_structs`.
+pub fn c_function_signature(
+    method: &CppFfiFunction,
+    opaque_structs: &mut BTreeSet<String>,
+) -> Result<String> {
+    let mut arg_texts = Vec::new();
+    for arg in &method.arguments {
+        let arg_type = arg.argument_type.ffi_type();
+        if let CppType::FunctionPointer(..) = arg_type {
+            arg_texts.push(c_type_code(arg_type, Some(&arg.name), opaque_structs)?);
+        } else {
+            arg_texts.push(format!(
+                "{} {}",
+                c_type_code(arg_type, None, opaque_structs)?,
+                arg.name
+            ));
+        }
+    }
+    let name = match method.calling_convention {
+        Some(calling_convention) => format!(
+            "{} {}",
+            calling_convention.to_cpp_code(),
+            method.path.to_cpp_code()?
+        ),
+        None => method.path.to_cpp_code()?,
+    };
+    let name_with_args = format!("{}({})", name, arg_texts.join(", "));
+    let return_type = method.return_type.ffi_type();
+    if let CppType::FunctionPointer(..) = return_type {
+        c_type_code(return_type, Some(&name_with_args), opaque_structs)
+    } else {
+        Ok(format!(
+            "{} {}",
+            c_type_code(return_type, None, opaque_structs)?,
+            name_with_args
+        ))
+    }
+}
+
+/// Generates function name, return type and arguments list
+/// as it appears in both function declaration and implementation.
+pub fn function_signature(method: &CppFfiFunction) -> Result<String> {
+    let mut arg_texts = Vec::new();
+    for arg in &method.arguments {
+        arg_texts.push(arg.to_cpp_code()?);
+    }
+    let name = match method.calling_convention {
+        Some(calling_convention) => format!(
+            "{} {}",
+            calling_convention.to_cpp_code(),
+            method.path.to_cpp_code()?
+        ),
+        None => method.path.to_cpp_code()?,
+    };
+    let noexcept = if method.is_noexcept { " noexcept" } else { "" };
+    let name_with_args = format!("{}({}){}", name, arg_texts.join(", "), noexcept);
+    let return_type = method.return_type.ffi_type();
+    let r = if let CppType::FunctionPointer(..) = return_type {
+        return_type.to_cpp_code(Some(&name_with_args))?
+    } else {
+        format!("{} {}", return_type.to_cpp_code(None)?, name_with_args)
+    };
+    match method.purity {
+        Some(purity) => Ok(format!("{} {}", purity.to_cpp_code(), r)),
+        None => Ok(r),
+    }
+}
+
+impl Generator<'_> {
     /// Generates code for a Qt slot wrapper
     fn qt_slot_wrapper(&self, wrapper: &QtSlotWrapper) -> Result<String> {
         let func_type = CppType::FunctionPointer(wrapper.function_type.clone());
@@ -63,13 +635,28 @@ impl Generator<'_> {
                     .map_if_ok(|(num, t)| self.convert_type_to_ffi(t, format!("arg{}", num)))?,
             )
             .join(", ");
+        let return_type = wrapper.return_type.original_type();
+        let slot_body = if return_type.is_void() {
+            format!(
+                "if (m_func) {{\n            m_func({});\n        }}",
+                func_args
+            )
+        } else {
+            // the default value is returned if the callback is not set
+            format!(
+                "if (m_func) {{\n            return {};\n        }}\n        return {}();",
+                convert_slot_return_value(&wrapper.return_type, format!("m_func({})", func_args))?,
+                return_type.to_cpp_code(None)?
+            )
+        };
         Ok(format!(
             include_str!("../templates/c_lib/qt_slot_wrapper.h"),
             class_name = wrapper.class_path.to_cpp_code()?,
             func_arg = func_type.to_cpp_code(Some("func"))?,
             func_field = func_type.to_cpp_code(Some("m_func"))?,
+            return_type = return_type.to_cpp_code(None)?,
             method_args = method_args,
-            func_args = func_args
+            slot_body = slot_body
         ))
     }
 
@@ -86,7 +673,25 @@ impl Generator<'_> {
                 expression
             ),
             CppToFfiTypeConversion::ReferenceToPointer => format!("&{}", expression),
+            CppToFfiTypeConversion::RValueReferenceToPointer => {
+                bail!("RValueReferenceToPointer is only supported for arguments of FFI functions");
+            }
             CppToFfiTypeConversion::QFlagsToInt => format!("int({})", expression),
+            CppToFfiTypeConversion::DurationToTicks { .. } => {
+                format!("static_cast<long long>(({}).count())", expression)
+            }
+            CppToFfiTypeConversion::OrderingToInt => {
+                format!("ritual_ordering_to_int({})", expression)
+            }
+            CppToFfiTypeConversion::OptionalToValue => {
+                bail!("OptionalToValue is only supported for return values of FFI functions");
+            }
+            CppToFfiTypeConversion::MemberFunctionPointerToStruct => {
+                format!("ritual_from_member_function_pointer({})", expression)
+            }
+            CppToFfiTypeConversion::InitializerListToPointer => {
+                bail!("InitializerListToPointer is only supported for arguments of FFI functions");
+            }
         })
     }
 
@@ -135,9 +740,32 @@ impl Generator<'_> {
             CppToFfiTypeConversion::ReferenceToPointer => {
                 result = format!("&{}", result);
             }
+            CppToFfiTypeConversion::RValueReferenceToPointer => {
+                bail!("RValueReferenceToPointer is not supported in return type position");
+            }
             CppToFfiTypeConversion::QFlagsToInt => {
                 result = format!("int({})", result);
             }
+            CppToFfiTypeConversion::DurationToTicks { .. } => {
+                result = format!("static_cast<long long>(({}).count())", result);
+            }
+            CppToFfiTypeConversion::OrderingToInt => {
+                result = format!("ritual_ordering_to_int({})", result);
+            }
+            CppToFfiTypeConversion::OptionalToValue => {
+                let arg = method
+                    .arguments
+                    .iter()
+                    .find(|x| x.meaning == CppFfiArgumentMeaning::HasValue)
+                    .ok_or_else(|| err_msg("has_value argument not found"))?;
+                result = format!("ritual_optional_value({}, {})", result, arg.name);
+            }
+            CppToFfiTypeConversion::MemberFunctionPointerToStruct => {
+                result = format!("ritual_from_member_function_pointer({})", result);
+            }
+            CppToFfiTypeConversion::InitializerListToPointer => {
+                bail!("InitializerListToPointer is not supported in return type position");
+            }
         }
 
         if method.allocation_place == ReturnValueAllocationPlace::Stack && !is_constructor {
@@ -153,42 +781,6 @@ impl Generator<'_> {
         Ok(result)
     }
 
-    /// Generates code for values passed to the original C++ method.
-    fn arguments_values(&self, method: &CppFfiFunction) -> Result<String> {
-        let r = method
-            .arguments
-            .iter()
-            .filter(|arg| arg.meaning.is_argument())
-            .map_if_ok(|argument| -> Result<_> {
-                let mut result = argument.name.clone();
-                match argument.argument_type.conversion() {
-                    CppToFfiTypeConversion::ValueToPointer { .. }
-                    | CppToFfiTypeConversion::ReferenceToPointer => result = format!("*{}", result),
-                    CppToFfiTypeConversion::NoChange
-                    | CppToFfiTypeConversion::ImplicitCast { .. } => {}
-                    CppToFfiTypeConversion::QFlagsToInt => {
-                        let type_text = if let CppType::PointerLike {
-                            kind,
-                            is_const,
-                            target,
-                        } = argument.argument_type.original_type()
-                        {
-                            if *kind == CppPointerLikeTypeKind::Reference && *is_const {
-                                target.to_cpp_code(None)?
-                            } else {
-                                bail!("Unsupported original type for QFlagsToUInt conversion");
-                            }
-                        } else {
-                            argument.argument_type.original_type().to_cpp_code(None)?
-                        };
-                        result = format!("{}({})", type_text, result);
-                    }
-                }
-                Ok(result)
-            })?;
-        Ok(r.join(", "))
-    }
-
     /// Generates code for the value returned by the FFI method.
     #[allow(clippy::collapsible_if)]
     fn returned_expression(&self, item: DbItem<&CppFfiFunction>) -> Result<String> {
@@ -218,6 +810,11 @@ impl Generator<'_> {
                 .as_function_ref()
                 .filter(|m| m.is_constructor())
             {
+                let constructed_type = if cpp_function.is_protected() {
+                    protected_shim_name(method)
+                } else {
+                    cpp_function.class_path()?.to_cpp_code()?
+                };
                 match method.allocation_place {
                     ReturnValueAllocationPlace::Stack => {
                         if let Some(arg) = method
@@ -225,18 +822,12 @@ impl Generator<'_> {
                             .iter()
                             .find(|x| x.meaning == CppFfiArgumentMeaning::ReturnValue)
                         {
-                            format!(
-                                "new({}) {}",
-                                arg.name,
-                                cpp_function.class_path()?.to_cpp_code()?
-                            )
+                            format!("new({}) {}", arg.name, constructed_type)
                         } else {
                             bail!("return value argument not found\n{:?}", method);
                         }
                     }
-                    ReturnValueAllocationPlace::Heap => {
-                        format!("new {}", cpp_function.class_path()?.to_cpp_code()?)
-                    }
+                    ReturnValueAllocationPlace::Heap => format!("new {}", constructed_type),
                     ReturnValueAllocationPlace::NotApplicable => {
                         bail!("NotApplicable in constructor");
                     }
@@ -246,32 +837,82 @@ impl Generator<'_> {
                     err_msg("cpp item (function or field) expected to have a path")
                 })?;
 
+                let is_protected = is_protected_member(&cpp_item.item);
+
                 if let Some(arg) = method
                     .arguments
                     .iter()
                     .find(|x| x.meaning == CppFfiArgumentMeaning::This)
                 {
-                    format!("{}->{}", arg.name, path.last().to_cpp_code()?)
+                    if is_protected {
+                        let is_const = if let CppType::PointerLike { is_const, .. } =
+                            arg.argument_type.ffi_type()
+                        {
+                            *is_const
+                        } else {
+                            bail!("this argument is not a pointer");
+                        };
+                        format!(
+                            "static_cast<{}{}*>({})->{}",
+                            if is_const { "const " } else { "" },
+                            protected_shim_name(method),
+                            arg.name,
+                            path.last().to_cpp_code()?
+                        )
+                    } else {
+                        format!("{}->{}", arg.name, path.last().to_cpp_code()?)
+                    }
+                } else if is_protected {
+                    format!(
+                        "{}::{}",
+                        protected_shim_name(method),
+                        path.last().to_cpp_code()?
+                    )
                 } else {
                     path.to_cpp_code()?
                 }
             };
             if let CppFfiFunctionKind::FieldAccessor { accessor_type, .. } = &method.kind {
-                if accessor_type == &CppFieldAccessorType::Setter {
-                    format!(
+                match accessor_type {
+                    CppFieldAccessorType::Setter => format!(
                         "{} = {}",
                         result_without_args,
-                        self.arguments_values(method)?
-                    )
-                } else {
-                    result_without_args
+                        arguments_values(method, false)?
+                    ),
+                    CppFieldAccessorType::ArrayElementSetter => {
+                        let arguments = method
+                            .arguments
+                            .iter()
+                            .filter(|arg| arg.meaning.is_argument())
+                            .collect_vec();
+                        if arguments.len() != 2 {
+                            bail!("array element setter must have index and value arguments");
+                        }
+                        format!(
+                            "{}[{}] = {}",
+                            result_without_args,
+                            argument_value(arguments[0], false)?,
+                            argument_value(arguments[1], false)?
+                        )
+                    }
+                    // array fields decay to the pointer to the first element
+                    CppFieldAccessorType::CopyGetter
+                    | CppFieldAccessorType::ConstRefGetter
+                    | CppFieldAccessorType::MutRefGetter
+                    | CppFieldAccessorType::ArrayPointerGetter => result_without_args,
                 }
             } else {
-                format!(
-                    "{}({})",
-                    result_without_args,
-                    self.arguments_values(method)?
-                )
+                let cpp_function = cpp_item.item.as_function_ref();
+                let is_constructor = cpp_function.map_or(false, |f| f.is_constructor());
+                let mut arguments = arguments_values(method, is_constructor)?;
+                if let Some(cpp_function) = cpp_function {
+                    if let (Some(cast), Some(arg)) =
+                        (&cpp_function.cast, cpp_function.arguments.first())
+                    {
+                        arguments = cast_argument(cast, &arg.argument_type, arguments)?;
+                    }
+                }
+                format!("{}({})", result_without_args, arguments)
             }
         };
         self.convert_return_type(item, result)
@@ -301,23 +942,56 @@ impl Generator<'_> {
                 panic!("Error: no this argument found\n{:?}", method);
             }
         } else {
-            Ok(format!(
-                "{}{};\n",
+            let checks = match cpp_item.item.as_function_ref() {
+                Some(cpp_function) => array_length_checks(method, cpp_function)?,
+                None => String::new(),
+            };
+            let is_constructor = cpp_item
+                .item
+                .as_function_ref()
+                .map_or(false, |f| f.is_constructor());
+            let body = format!(
+                "{}{}{}{}{};\n",
+                duration_period_checks(method)?,
+                owned_argument_guards(method, is_constructor)?,
+                checks,
                 if method.return_type.ffi_type().is_void() {
                     ""
                 } else {
                     "return "
                 },
-                self.returned_expression(item)?
-            ))
+                with_initializer_lists(method, self.returned_expression(item)?)?
+            );
+            match method
+                .arguments
+                .iter()
+                .find(|x| x.meaning == CppFfiArgumentMeaning::ExceptionOutput)
+            {
+                Some(arg) => Ok(catch_exceptions(
+                    &body,
+                    &arg.name,
+                    method.return_type.ffi_type().is_void(),
+                )),
+                None => Ok(body),
+            }
         }
     }
 
     /// Generates implementation of the FFI method for the source file.
     fn function_implementation(&self, method: DbItem<&CppFfiFunction>) -> Result<String> {
+        let cpp_item = self
+            .0
+            .source_cpp_item(&method.id)?
+            .ok_or_else(|| format_err!("failed to find original cpp item for {:?}", method))?;
+        let shim = if is_protected_member(&cpp_item.item) {
+            protected_shim(method.item, &cpp_item.item)?
+        } else {
+            String::new()
+        };
         Ok(format!(
-            "RITUAL_EXPORT {} {{\n  {}}}\n\n",
-            self.function_signature(method.item)?,
+            "{}RITUAL_EXPORT {} {{\n  {}}}\n\n",
+            shim,
+            function_signature(method.item)?,
             self.source_body(method)?
         ))
     }
@@ -334,6 +1008,13 @@ impl Generator<'_> {
             Condition::Env(_) => unimplemented!(),
             Condition::PointerWidth(_) => unimplemented!(),
             Condition::Endian(_) => unimplemented!(),
+            Condition::Feature(feature) => {
+                if feature.is_negated {
+                    format!("!defined({})", feature.cpp_macro)
+                } else {
+                    format!("defined({})", feature.cpp_macro)
+                }
+            }
             Condition::And(conditions) => conditions
                 .iter()
                 .map(|c| format!("({})", self.condition_expression(c)))
@@ -359,11 +1040,12 @@ impl Generator<'_> {
         )
     }
 
-    /// Generates a source file with the specified FFI methods.
-    fn generate_cpp_file(&self, file_path: &Path, global_header_name: &str) -> Result<()> {
-        let mut cpp_file = create_file(file_path)?;
-        writeln!(cpp_file, "#include \"{}\"", global_header_name)?;
-
+    /// Returns FFI items that should be compiled into the C++ wrapper library.
+    /// Qt slot wrappers are always included, and FFI functions are included
+    /// only if they are used by the generated Rust code.
+    /// Items are sorted by name to make the output independent of
+    /// the order of items in the database.
+    fn used_ffi_items(&self) -> Vec<DbItem<&CppFfiItem>> {
         let used_ffi_functions = self
             .0
             .rust_items()
@@ -372,7 +1054,7 @@ impl Generator<'_> {
             .map(|item| item.path.last())
             .collect::<HashSet<&str>>();
 
-        let ffi_items = self
+        let mut ffi_items = self
             .0
             .ffi_items()
             .filter(|item| {
@@ -380,40 +1062,183 @@ impl Generator<'_> {
                     || used_ffi_functions.contains(item.item.path().last().name.as_str())
             })
             .collect_vec();
+        ffi_items.sort_by_cached_key(|item| item.item.path().to_cpp_pseudo_code());
+        ffi_items
+    }
 
-        let mut any_slot_wrappers = false;
-        for ffi_item in &ffi_items {
-            if let CppFfiItem::QtSlotWrapper(qt_slot_wrapper) = &ffi_item.item {
-                let checks = self.0.cpp_checks(&ffi_item.id)?;
-                if !checks.any_success() {
-                    continue;
-                }
-                any_slot_wrappers = true;
-                let condition = checks.condition(self.0.environments());
-                let code = self.qt_slot_wrapper(qt_slot_wrapper)?;
-                write!(cpp_file, "{}", self.wrap_with_condition(&code, &condition))?;
+    /// Returns the preprocessor condition for an FFI function, or `None`
+    /// if the function is not available in any of the checked environments.
+    fn ffi_function_condition(
+        &self,
+        ffi_item: &DbItem<&CppFfiItem>,
+        feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+    ) -> Result<Option<Condition>> {
+        let checks = self.0.cpp_checks(&ffi_item.id)?;
+        if !checks.any_success() {
+            return Ok(None);
+        }
+        let mut condition = checks.condition(self.0.environments());
+        if let Some(cpp_item) = self.0.original_cpp_item(&ffi_item.id)? {
+            condition = condition.and(item_feature_condition(cpp_item.item, feature_macros));
+        }
+        Ok(Some(condition))
+    }
+
+    /// Generates a header file declaring all FFI functions of the library.
+    /// The header doesn't include any C++ headers, so it can be used
+    /// to call the wrapper library from C or C++ code without going through Rust.
+    /// C++ classes are declared as opaque structs, and functions that can't
+    /// be expressed in C (see `c_function_signature`) are omitted.
+    fn generate_c_header(
+        &self,
+        file_path: &Path,
+        feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+    ) -> Result<()> {
+        let mut opaque_structs = BTreeSet::new();
+        let mut declarations = Vec::new();
+        for ffi_item in &self.used_ffi_items() {
+            if let Some(item) = ffi_item.item.as_function_ref() {
+                let condition = match self.ffi_function_condition(ffi_item, feature_macros)? {
+                    Some(condition) => condition,
+                    None => continue,
+                };
+                let signature = match c_function_signature(item, &mut opaque_structs) {
+                    Ok(signature) => signature,
+                    Err(err) => {
+                        debug!(
+                            "omitting {} from C header: {}",
+                            item.path.to_cpp_pseudo_code(),
+                            err
+                        );
+                        continue;
+                    }
+                };
+                let code = format!("{};", signature);
+                declarations.push(self.wrap_with_condition(&code, &condition));
             }
         }
 
-        writeln!(cpp_file, "extern \"C\" {{")?;
+        let include_guard = format!(
+            "{}_H",
+            os_str_to_str(
+                file_path
+                    .file_stem()
+                    .ok_or_else(|| err_msg("failed to get file stem"))?,
+            )?
+            .to_uppercase()
+        );
+        let mut header_file = create_file(file_path)?;
+        writeln!(header_file, "#ifndef {}", include_guard)?;
+        writeln!(header_file, "#define {}", include_guard)?;
+        writeln!(header_file)?;
+        writeln!(header_file, "#include <stdbool.h>")?;
+        writeln!(header_file, "#include <stddef.h>")?;
+        writeln!(header_file, "#include <stdint.h>")?;
+        writeln!(header_file)?;
+        writeln!(header_file, "#ifdef __cplusplus")?;
+        writeln!(header_file, "extern \"C\" {{")?;
+        writeln!(header_file, "#endif")?;
+        writeln!(header_file)?;
+        for name in &opaque_structs {
+            writeln!(header_file, "struct {};", name)?;
+        }
+        writeln!(header_file)?;
+        writeln!(
+            header_file,
+            "const char* {}();",
+            generator_version_function_name(&self.0.crate_name())
+        )?;
+        for declaration in &declarations {
+            writeln!(header_file, "{}", declaration)?;
+        }
+        writeln!(header_file, "#ifdef __cplusplus")?;
+        writeln!(header_file, "}} // extern \"C\"")?;
+        writeln!(header_file, "#endif")?;
+        writeln!(header_file)?;
+        writeln!(header_file, "#endif // {}", include_guard)?;
+        Ok(())
+    }
+
+    /// Generates `file_count` C++ source files of the wrapper library in `dir`.
+    /// FFI functions (sorted by their C++ paths) are split with `partition`,
+    /// so the partitioning only depends on the set of functions.
+    /// The first file also contains the layout checks, Qt slot wrappers
+    /// and the generator version function.
+    /// Each item is written to exactly one file and FFI function names are
+    /// unique within the crate, so the files can be compiled and linked together.
+    fn generate_cpp_files(
+        &self,
+        dir: &Path,
+        file_count: usize,
+        global_header_name: &str,
+        feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+    ) -> Result<()> {
+        let ffi_items = self.used_ffi_items();
+
+        let mut functions = Vec::new();
         for ffi_item in &ffi_items {
             if let Some(item) = ffi_item.clone().filter_map(|item| item.as_function_ref()) {
-                let checks = self.0.cpp_checks(&ffi_item.id)?;
-                if !checks.any_success() {
-                    continue;
-                }
-                let condition = checks.condition(self.0.environments());
+                let condition = match self.ffi_function_condition(ffi_item, feature_macros)? {
+                    Some(condition) => condition,
+                    None => continue,
+                };
                 let code = self.function_implementation(item)?;
-                writeln!(cpp_file, "{}", self.wrap_with_condition(&code, &condition))?;
+                functions.push(self.wrap_with_condition(&code, &condition));
             }
         }
-        writeln!(cpp_file, "}} // extern \"C\"")?;
+        let chunks = partition(&functions, file_count);
+
+        for (index, file_name) in cpp_file_names(file_count).iter().enumerate() {
+            let mut cpp_file = create_file(dir.join(file_name))?;
+            writeln!(cpp_file, "#include \"{}\"", global_header_name)?;
 
-        if any_slot_wrappers && !self.0.crate_name().starts_with("moqt_") {
-            let stem = file_path
-                .file_stem()
-                .ok_or_else(|| err_msg("failed to get file stem"))?;
-            writeln!(cpp_file, "#include \"{}.moc\"", os_str_to_str(stem)?)?;
+            let mut any_slot_wrappers = false;
+            if index == 0 {
+                for item in self.0.rust_items() {
+                    if let RustItem::Struct(data) = &item.item {
+                        if let RustStructKind::WrapperType(
+                            RustWrapperTypeKind::TransparentClassWrapper { cpp_path, fields },
+                        ) = &data.kind
+                        {
+                            writeln!(cpp_file, "{}", transparent_layout_checks(cpp_path, fields)?)?;
+                        }
+                    }
+                }
+
+                for ffi_item in &ffi_items {
+                    if let CppFfiItem::QtSlotWrapper(qt_slot_wrapper) = &ffi_item.item {
+                        let checks = self.0.cpp_checks(&ffi_item.id)?;
+                        if !checks.any_success() {
+                            continue;
+                        }
+                        any_slot_wrappers = true;
+                        let condition = checks.condition(self.0.environments());
+                        let code = self.qt_slot_wrapper(qt_slot_wrapper)?;
+                        write!(cpp_file, "{}", self.wrap_with_condition(&code, &condition))?;
+                    }
+                }
+            }
+
+            writeln!(cpp_file, "extern \"C\" {{")?;
+            if index == 0 {
+                writeln!(
+                    cpp_file,
+                    "RITUAL_EXPORT const char* {}() {{ return \"{}\"; }}",
+                    generator_version_function_name(&self.0.crate_name()),
+                    GENERATOR_VERSION
+                )?;
+            }
+            for code in chunks[index] {
+                writeln!(cpp_file, "{}", code)?;
+            }
+            writeln!(cpp_file, "}} // extern \"C\"")?;
+
+            if any_slot_wrappers && !self.0.crate_name().starts_with("moqt_") {
+                let stem = Path::new(file_name)
+                    .file_stem()
+                    .ok_or_else(|| err_msg("failed to get file stem"))?;
+                writeln!(cpp_file, "#include \"{}.moc\"", os_str_to_str(stem)?)?;
+            }
         }
         Ok(())
     }
@@ -423,12 +1248,10 @@ impl Generator<'_> {
     /// to the standard output.
     fn generate_cpp_type_size_requester(
         &self,
-        include_directives: &[PathBuf],
+        include_directives_code: &str,
         mut output: impl Write,
     ) -> Result<()> {
-        for dir in include_directives {
-            writeln!(output, "#include <{}>", path_to_str(dir)?)?;
-        }
+        writeln!(output, "{}", include_directives_code)?;
         writeln!(output, "#include <stdio.h>\n\nint main() {{")?;
 
         let rust_items = self.0.rust_items().map(|i| i.item);
@@ -453,6 +1276,33 @@ impl Generator<'_> {
             }
         }
 
+        let mut pointer_sized_types = Vec::new();
+        for ffi_item in self.0.ffi_items() {
+            let function = if let Some(function) = ffi_item.item.as_function_ref() {
+                function
+            } else {
+                continue;
+            };
+            if !self
+                .0
+                .cpp_checks(&ffi_item.id)?
+                .all_success(self.0.environments())
+            {
+                continue;
+            }
+            let types = function
+                .arguments
+                .iter()
+                .map(|arg| &arg.argument_type)
+                .chain(once(&function.return_type));
+            for ffi_type in types {
+                collect_pointer_sized_integers(ffi_type.original_type(), &mut pointer_sized_types);
+            }
+        }
+        for cpp_type in &pointer_sized_types {
+            writeln!(output, "{}", pointer_sized_integer_size_check(cpp_type)?)?;
+        }
+
         writeln!(output, "}}")?;
         Ok(())
     }
@@ -469,18 +1319,49 @@ pub fn qt_slot_wrapper(db: &DatabaseClient, wrapper: &QtSlotWrapper) -> Result<S
     Generator(db).qt_slot_wrapper(wrapper)
 }
 
-pub fn generate_cpp_file(
+/// Splits `items` into `count` (at least 1) contiguous chunks
+/// whose lengths differ by at most 1.
+pub fn partition<T>(items: &[T], count: usize) -> Vec<&[T]> {
+    let count = count.max(1);
+    let mut chunks = Vec::with_capacity(count);
+    let mut start = 0;
+    for index in 0..count {
+        let len = items.len() / count + if index < items.len() % count { 1 } else { 0 };
+        chunks.push(&items[start..start + len]);
+        start += len;
+    }
+    chunks
+}
+
+/// Returns names of C++ source files generated by `generate_cpp_files`.
+pub fn cpp_file_names(file_count: usize) -> Vec<String> {
+    (1..=file_count.max(1))
+        .map(|index| format!("file{}.cpp", index))
+        .collect()
+}
+
+pub fn generate_cpp_files(
     db: &DatabaseClient,
-    file_path: &Path,
+    dir: &Path,
+    file_count: usize,
     global_header_name: &str,
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+) -> Result<()> {
+    Generator(db).generate_cpp_files(dir, file_count, global_header_name, feature_macros)
+}
+
+pub fn generate_c_header(
+    db: &DatabaseClient,
+    file_path: &Path,
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
 ) -> Result<()> {
-    Generator(db).generate_cpp_file(file_path, global_header_name)
+    Generator(db).generate_c_header(file_path, feature_macros)
 }
 
 pub fn generate_cpp_type_size_requester(
     db: &DatabaseClient,
-    include_directives: &[PathBuf],
+    include_directives_code: &str,
     output: impl Write,
 ) -> Result<()> {
-    Generator(db).generate_cpp_type_size_requester(include_directives, output)
+    Generator(db).generate_cpp_type_size_requester(include_directives_code, output)
 }
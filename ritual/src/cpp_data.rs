@@ -57,6 +57,13 @@ pub struct CppClassField {
     /// Visibility
     pub visibility: CppVisibility,
     pub is_static: bool,
+    /// True if the field is declared `const`
+    #[serde(default)]
+    pub is_const: bool,
+    /// Extent of the field if it's declared as a fixed-size array
+    /// (e.g. `int values[4]`). `field_type` is the element type
+    /// for such fields.
+    pub array_size: Option<usize>,
 }
 
 impl CppClassField {
@@ -65,6 +72,8 @@ impl CppClassField {
             && self.field_type == other.field_type
             && self.visibility == other.visibility
             && self.is_static == other.is_static
+            && self.is_const == other.is_const
+            && self.array_size == other.array_size
     }
 
     pub fn short_text(&self) -> String {
@@ -73,11 +82,17 @@ impl CppClassField {
             CppVisibility::Protected => "protected ",
             CppVisibility::Private => "private ",
         };
+        let array_text = match self.array_size {
+            Some(size) => format!("[{}]", size),
+            None => String::new(),
+        };
         format!(
-            "{}{} {}",
+            "{}{}{} {}{}",
             visibility_text,
+            if self.is_const { "const " } else { "" },
             self.field_type.to_cpp_pseudo_code(),
             self.path.to_cpp_pseudo_code(),
+            array_text,
         )
     }
 }
@@ -195,6 +210,17 @@ impl CppPath {
         }
     }
 
+    /// Returns true if `prefix` is equal to this path or to one of its parents.
+    /// Template arguments are ignored, so `QList` is a prefix of `QList<int>::append`.
+    pub fn starts_with(&self, prefix: &CppPath) -> bool {
+        prefix.items.len() <= self.items.len()
+            && prefix
+                .items
+                .iter()
+                .zip(&self.items)
+                .all(|(a, b)| a.name == b.name)
+    }
+
     pub fn parent_parts(&self) -> Result<&[CppPathItem]> {
         if self.items.len() > 1 {
             Ok(&self.items[..self.items.len() - 1])
@@ -369,7 +395,16 @@ impl fmt::Debug for CppPathItem {
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Hash)]
 pub enum CppTypeDeclarationKind {
     Enum,
-    Class,
+    Class {
+        /// True if the class is a POD type (trivial and standard-layout),
+        /// so its memory layout can be reproduced with a `#[repr(C)]` Rust struct.
+        #[serde(default)]
+        is_pod: bool,
+        /// True if the class declares or inherits a virtual function,
+        /// so `dynamic_cast` can be applied to it.
+        #[serde(default)]
+        is_polymorphic: bool,
+    },
 }
 
 /// Information about a C++ type declaration
@@ -395,6 +430,14 @@ impl CppTypeDeclarationKind {
         }
     }
 
+    /// Checks if the type is a polymorphic class type.
+    pub fn is_polymorphic(&self) -> bool {
+        match self {
+            CppTypeDeclarationKind::Class { is_polymorphic, .. } => *is_polymorphic,
+            _ => false,
+        }
+    }
+
     pub fn is_enum(&self) -> bool {
         match self {
             CppTypeDeclarationKind::Enum => true,
@@ -0,0 +1,152 @@
+use crate::cpp_data::{CppEnumValue, CppPath};
+use crate::database::DatabaseClient;
+use std::collections::HashMap;
+
+/// Kind of a C++ enum determined by its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CppEnumKind {
+    /// Values are distinct bits (and, optionally, zero and
+    /// combinations of these bits), so the enum can be used as a set of flags.
+    Flags,
+    /// Distinct values form a contiguous range of integers.
+    Sequential,
+    /// Other enums.
+    Other,
+}
+
+/// A value of a C++ enum grouped with other values of the same enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CppEnumGroupValue {
+    pub value: CppEnumValue,
+    /// Index of the first value of the enum with the same numeric value,
+    /// if this value is an alias of it.
+    pub alias_of: Option<usize>,
+}
+
+/// All values of a C++ enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CppEnumGroup {
+    pub enum_path: CppPath,
+    /// Values in declaration order.
+    pub values: Vec<CppEnumGroupValue>,
+    pub kind: CppEnumKind,
+}
+
+impl CppEnumGroup {
+    /// Returns values that are not aliases of previous values.
+    pub fn unique_values(&self) -> impl Iterator<Item = &CppEnumValue> {
+        self.values
+            .iter()
+            .filter(|value| value.alias_of.is_none())
+            .map(|value| &value.value)
+    }
+
+    /// Returns pairs of aliases and the values they are aliases of.
+    pub fn aliases(&self) -> impl Iterator<Item = (&CppEnumValue, &CppEnumValue)> {
+        self.values.iter().filter_map(move |value| {
+            value
+                .alias_of
+                .map(|index| (&value.value, &self.values[index].value))
+        })
+    }
+}
+
+/// Returns index of the first value in `previous` with the same numeric value
+/// as `value`, if `value` is an alias of it.
+fn alias_index<'a>(
+    previous: impl IntoIterator<Item = &'a CppEnumValue>,
+    value: &CppEnumValue,
+) -> Option<usize> {
+    previous
+        .into_iter()
+        .position(|other| other.value == value.value)
+}
+
+/// Returns pairs of aliases and their canonical values for `values` of one enum.
+/// A value is an alias if an earlier value has the same numeric value.
+/// The canonical value is the first value with that numeric value.
+pub fn enum_value_aliases(values: &[CppEnumValue]) -> Vec<(&CppEnumValue, &CppEnumValue)> {
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| {
+            alias_index(&values[..index], value).map(|canonical| (value, &values[canonical]))
+        })
+        .collect()
+}
+
+/// Returns true if distinct `values` form a contiguous range of integers.
+fn is_sequential(values: &[i64]) -> bool {
+    let min = values.iter().min().cloned().unwrap_or(0);
+    values
+        .iter()
+        .all(|&value| (value.wrapping_sub(min) as u64) < values.len() as u64)
+}
+
+/// Determines kind of the enum with distinct `values`.
+/// Enums with at least 3 sequential values (e.g. `0, 1, 2, 3`) are considered
+/// sequential even if they could be used as flags.
+fn enum_kind(values: &[i64]) -> CppEnumKind {
+    let sequential = is_sequential(values);
+    if sequential && values.len() >= 3 {
+        return CppEnumKind::Sequential;
+    }
+    let bits = values
+        .iter()
+        .filter(|&&value| value > 0 && value & (value - 1) == 0)
+        .fold(0, |bits, value| bits | value);
+    if bits.count_ones() >= 2 && values.iter().all(|&value| value >= 0 && value & !bits == 0) {
+        CppEnumKind::Flags
+    } else if sequential {
+        CppEnumKind::Sequential
+    } else {
+        CppEnumKind::Other
+    }
+}
+
+/// Groups `values` by their enums. Enums are returned in the order of their
+/// first values, and values of each enum keep their order in `values`.
+pub fn group_enum_values<'a>(
+    values: impl IntoIterator<Item = &'a CppEnumValue>,
+) -> Vec<CppEnumGroup> {
+    let mut groups: Vec<CppEnumGroup> = Vec::new();
+    let mut indexes = HashMap::new();
+    for value in values {
+        let enum_path = match value.path.parent() {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        let index = *indexes.entry(enum_path.clone()).or_insert_with(|| {
+            groups.push(CppEnumGroup {
+                enum_path,
+                values: Vec::new(),
+                kind: CppEnumKind::Other,
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[index];
+        let alias_of = alias_index(group.values.iter().map(|other| &other.value), value);
+        group.values.push(CppEnumGroupValue {
+            value: value.clone(),
+            alias_of,
+        });
+    }
+
+    for group in &mut groups {
+        let values = group
+            .unique_values()
+            .map(|value| value.value)
+            .collect::<Vec<_>>();
+        group.kind = enum_kind(&values);
+    }
+    groups
+}
+
+/// Groups all C++ enum values in the database by their enums.
+/// See `group_enum_values`.
+pub fn enum_groups(db: &DatabaseClient) -> Vec<CppEnumGroup> {
+    group_enum_values(
+        db.cpp_items()
+            .filter_map(|item| item.item.as_enum_value_ref()),
+    )
+}
@@ -1,10 +1,12 @@
 use crate::cpp_code_generator;
 use crate::cpp_data::CppPath;
-use crate::cpp_function::ReturnValueAllocationPlace;
-use crate::cpp_type::{CppBuiltInNumericType, CppFunctionPointerType, CppType};
+use crate::cpp_function::{CppCallingConvention, CppFunctionPurity, ReturnValueAllocationPlace};
+use crate::cpp_type::{
+    CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind, CppType,
+};
 use crate::database::DatabaseClient;
 use itertools::Itertools;
-use ritual_common::errors::{bail, Result};
+use ritual_common::errors::{bail, err_msg, Result};
 use serde_derive::{Deserialize, Serialize};
 
 /// Variation of a field accessor method
@@ -18,6 +20,11 @@ pub enum CppFieldAccessorType {
     MutRefGetter,
     /// Copies value from its argument to the field
     Setter,
+    /// Returns pointer to the first element of the array field
+    ArrayPointerGetter,
+    /// Copies value from its second argument to the element of the array field
+    /// at the index specified by its first argument
+    ArrayElementSetter,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -29,6 +36,13 @@ pub enum CppCast {
         /// Contains index of the base (e.g. 0 for the first base; always
         /// 0 if the class only has one base).
         base_index: Option<usize>,
+
+        /// Intermediate base classes between the derived class and the base class,
+        /// starting with the direct base of the derived class. Empty if the base
+        /// is a direct base. The generated C++ code casts through each of these
+        /// classes, so the compiler adjusts the pointer for every base subobject.
+        #[serde(default)]
+        base_chain: Vec<CppPath>,
     },
     Dynamic,
     QObject,
@@ -55,6 +69,12 @@ impl CppCast {
             _ => false,
         }
     }
+    pub fn base_chain(&self) -> &[CppPath] {
+        match self {
+            CppCast::Static { base_chain, .. } => base_chain,
+            _ => &[],
+        }
+    }
 }
 
 /// Information about real nature of a C++ FFI method.
@@ -83,11 +103,63 @@ pub enum CppToFfiTypeConversion {
     /// C++ argument is a reference (like QPoint&)
     /// and FFI argument is a pointer (like QPoint*)
     ReferenceToPointer,
+    /// C++ argument is an rvalue reference (like QPoint&&)
+    /// and FFI argument is a pointer (like QPoint*).
+    /// The pointed object is passed to the C++ function with `std::move`.
+    RValueReferenceToPointer,
     /// C++ argument is QFlags<T>
     /// and FFI argument is uint
     QFlagsToInt,
+    /// C++ return value is a result of a three-way comparison
+    /// (like `std::strong_ordering`) and FFI return value is
+    /// an int (-1, 0 or 1)
+    OrderingToInt,
     /// Implicit conversion is used.
     ImplicitCast { ffi_type: CppType },
+    /// C++ return value is `std::optional<T>` and FFI return value is `T`.
+    /// Presence of the value is reported through the `HasValue` argument.
+    /// Only numbers, enums and pointers are supported as `T`. Functions returning
+    /// `std::optional` of other types (e.g. classes) are not wrapped.
+    OptionalToValue,
+    /// C++ argument is a duration type (like `std::chrono::milliseconds`)
+    /// and FFI argument is its tick count (`long long`)
+    DurationToTicks { period: DurationPeriod },
+    /// C++ argument is a pointer to member function (like `void (QObject::*)()`)
+    /// and FFI argument is an opaque struct containing its bytes
+    /// (`ritual_member_function_pointer`)
+    MemberFunctionPointerToStruct,
+    /// C++ argument is `std::initializer_list<T>` (or a const reference to it)
+    /// and FFI argument is `const T*` pointing to its elements.
+    /// The number of elements is passed in the paired `ArrayLength` argument.
+    InitializerListToPointer,
+}
+
+/// Period of a tick of a C++ duration type in seconds,
+/// expressed as a ratio (like `std::ratio`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct DurationPeriod {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl DurationPeriod {
+    /// Creates a period of `numerator / denominator` seconds.
+    /// Returns an error if any of the values is zero.
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self> {
+        if numerator == 0 || denominator == 0 {
+            bail!("invalid duration period: {}/{}", numerator, denominator);
+        }
+        Ok(DurationPeriod {
+            numerator,
+            denominator,
+        })
+    }
+}
+
+/// Returns path of the struct used to pass pointers to member functions through FFI.
+/// It's declared in the global header of the C++ wrapper library.
+pub fn member_function_pointer_struct_path() -> CppPath {
+    CppPath::from_good_str("ritual_member_function_pointer")
 }
 
 /// Information that indicates how an FFI function argument
@@ -104,6 +176,25 @@ pub enum CppFfiArgumentMeaning {
     /// This argument receives pointer to the buffer where
     /// the return value should be transferred to using placement new.
     ReturnValue,
+    /// This argument receives pointer to a flag that indicates whether
+    /// the optional return value is present (see `OptionalToValue`).
+    HasValue,
+    /// Same as `Argument`, but the argument is a callback function paired with
+    /// a `CallbackUserData` argument (see `Config::add_callback_argument`).
+    Callback(usize),
+    /// Same as `Argument`, but the argument is the user data pointer
+    /// passed to the paired `Callback` argument.
+    CallbackUserData(usize),
+    /// This argument receives the number of elements of the array passed
+    /// in the `Argument` with the same index. The original C++ function
+    /// declares that argument as a fixed-size array (e.g. `float out[16]`)
+    /// or as `std::initializer_list`, so the length is not passed to it.
+    ArrayLength(usize),
+    /// This argument receives pointer to the buffer where the message
+    /// of an exception thrown by the original C++ function is written
+    /// (see `Config::set_catch_exceptions`). The buffer is left unchanged
+    /// if no exception was thrown.
+    ExceptionOutput,
 }
 
 impl CppFfiArgumentMeaning {
@@ -111,7 +202,9 @@ impl CppFfiArgumentMeaning {
     /// C++ method's argument
     pub fn is_argument(&self) -> bool {
         match *self {
-            CppFfiArgumentMeaning::Argument(..) => true,
+            CppFfiArgumentMeaning::Argument(..)
+            | CppFfiArgumentMeaning::Callback(..)
+            | CppFfiArgumentMeaning::CallbackUserData(..) => true,
             _ => false,
         }
     }
@@ -162,6 +255,30 @@ pub struct CppFfiFunction {
     pub path: CppPath,
 
     pub kind: CppFfiFunctionKind,
+
+    /// True if the original C++ function was marked as callable
+    /// from any thread using `Config::add_thread_safe_function`.
+    #[serde(default)]
+    pub is_thread_safe: bool,
+
+    /// Non-default calling convention of the original C++ function.
+    /// The FFI function is declared with the same calling convention.
+    pub calling_convention: Option<CppCallingConvention>,
+
+    /// `pure` or `const` attribute propagated from the original C++ function.
+    /// None if the attribute is absent or can't be applied to the FFI function.
+    pub purity: Option<CppFunctionPurity>,
+
+    /// True if the FFI function uses heap allocation even though the type
+    /// is movable. Such functions are generated in addition to the regular
+    /// stack-allocating ones if enabled with `Config::set_heap_return_variants`.
+    #[serde(default)]
+    pub is_heap_variant: bool,
+
+    /// True if the original C++ function is `noexcept` and the FFI function doesn't
+    /// call anything else that can throw, so it can be declared `noexcept` as well.
+    #[serde(default)]
+    pub is_noexcept: bool,
 }
 
 impl CppFfiFunction {
@@ -227,6 +344,14 @@ impl CppFfiType {
                 original_type,
                 conversion,
             }),
+            CppToFfiTypeConversion::RValueReferenceToPointer => {
+                let target = original_type.pointer_like_to_target()?;
+                Ok(CppFfiType {
+                    ffi_type: CppType::new_pointer(false, target.clone()),
+                    original_type,
+                    conversion,
+                })
+            }
             CppToFfiTypeConversion::ReferenceToPointer => {
                 let target = original_type.pointer_like_to_target()?;
                 let is_const = original_type.pointer_like_is_const()?;
@@ -236,16 +361,55 @@ impl CppFfiType {
                     conversion,
                 })
             }
-            CppToFfiTypeConversion::QFlagsToInt => Ok(CppFfiType {
-                ffi_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+            CppToFfiTypeConversion::QFlagsToInt | CppToFfiTypeConversion::OrderingToInt => {
+                Ok(CppFfiType {
+                    ffi_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+                    original_type,
+                    conversion,
+                })
+            }
+            CppToFfiTypeConversion::OptionalToValue => {
+                let value_type = original_type
+                    .optional_value_type()
+                    .ok_or_else(|| err_msg("std::optional type expected"))?
+                    .clone();
+                Ok(CppFfiType {
+                    ffi_type: value_type,
+                    original_type,
+                    conversion,
+                })
+            }
+            CppToFfiTypeConversion::DurationToTicks { .. } => Ok(CppFfiType {
+                ffi_type: CppType::BuiltInNumeric(CppBuiltInNumericType::LongLong),
                 original_type,
                 conversion,
             }),
+            CppToFfiTypeConversion::MemberFunctionPointerToStruct => {
+                if !original_type.is_member_function_pointer() {
+                    bail!("member function pointer type expected");
+                }
+                Ok(CppFfiType {
+                    ffi_type: CppType::Class(member_function_pointer_struct_path()),
+                    original_type,
+                    conversion,
+                })
+            }
             CppToFfiTypeConversion::ImplicitCast { ffi_type } => Ok(CppFfiType {
                 ffi_type,
                 original_type,
                 conversion,
             }),
+            CppToFfiTypeConversion::InitializerListToPointer => {
+                let element_type = original_type
+                    .initializer_list_element_type()
+                    .ok_or_else(|| err_msg("std::initializer_list type expected"))?
+                    .clone();
+                Ok(CppFfiType {
+                    ffi_type: CppType::new_pointer(true, element_type),
+                    original_type,
+                    conversion,
+                })
+            }
         }
     }
 
@@ -262,6 +426,50 @@ impl CppFfiType {
         &self.original_type
     }
 
+    /// Returns path of the enum `QFlags<T>` is instantiated with if the type
+    /// has `QFlagsToInt` conversion. The original type must be `QFlags<T>`
+    /// or `const QFlags<T>&`.
+    pub fn qflags_enum_path(&self) -> Result<&CppPath> {
+        if self.conversion != CppToFfiTypeConversion::QFlagsToInt {
+            bail!("expected QFlagsToInt conversion: {:?}", self);
+        }
+        let qflags_type = match &self.original_type {
+            CppType::PointerLike {
+                kind,
+                is_const,
+                target,
+            } => {
+                if kind != &CppPointerLikeTypeKind::Reference {
+                    bail!("unsupported indirection for QFlagsToUInt: {:?}", self);
+                }
+                if !*is_const {
+                    bail!("unsupported is_const for QFlagsToUInt: {:?}", self);
+                }
+                &**target
+            }
+            a => a,
+        };
+        let enum_type = if let CppType::Class(path) = qflags_type {
+            let template_arguments = path
+                .last()
+                .template_arguments
+                .as_ref()
+                .ok_or_else(|| err_msg("expected template arguments for QFlags"))?;
+            if template_arguments.len() != 1 {
+                bail!("QFlags type must have exactly 1 template argument");
+            }
+            &template_arguments[0]
+        } else {
+            bail!("invalid original type for QFlagsToUInt: {:?}", self);
+        };
+
+        if let CppType::Enum { path } = enum_type {
+            Ok(path)
+        } else {
+            bail!("invalid QFlags argument type: {:?}", enum_type);
+        }
+    }
+
     pub fn ffi_type(&self) -> &CppType {
         &self.ffi_type
     }
@@ -280,6 +488,10 @@ pub struct QtSlotWrapper {
     pub class_path: CppPath,
     /// Arguments of the slot.
     pub arguments: Vec<CppFfiType>,
+    /// Return type of the slot. The callback returns the FFI type
+    /// and the slot converts it back to the original type.
+    #[serde(default = "CppFfiType::void")]
+    pub return_type: CppFfiType,
     /// The function pointer type accepted by this wrapper
     pub function_type: CppFunctionPointerType,
 }
@@ -350,6 +562,7 @@ impl CppFfiItem {
             CppFfiItem::QtSlotWrapper(wrapper) => {
                 if let CppFfiItem::QtSlotWrapper(other_wrapper) = other {
                     wrapper.signal_arguments == other_wrapper.signal_arguments
+                        && wrapper.return_type == other_wrapper.return_type
                 } else {
                     false
                 }
@@ -1,29 +1,65 @@
+use crate::config::{AllocationPlaceHook, Config};
+use crate::cpp_data::CppBaseSpecifier;
 use crate::cpp_data::CppClassField;
 use crate::cpp_data::CppItem;
 use crate::cpp_data::CppPath;
 use crate::cpp_data::CppPathItem;
 use crate::cpp_data::CppVisibility;
 use crate::cpp_ffi_data::CppFfiType;
+use crate::cpp_ffi_data::DurationPeriod;
 use crate::cpp_ffi_data::{CppFfiArgumentMeaning, CppToFfiTypeConversion};
 use crate::cpp_ffi_data::{CppFfiFunction, CppFfiFunctionKind, CppFieldAccessorType};
 use crate::cpp_ffi_data::{CppFfiFunctionArgument, CppFfiItem};
 use crate::cpp_function::ReturnValueAllocationPlace;
-use crate::cpp_function::{CppFunction, CppFunctionArgument, CppFunctionKind};
+use crate::cpp_function::{CppFunction, CppFunctionArgument, CppFunctionKind, CppFunctionPurity};
+use crate::cpp_operator::CppOperator;
+use crate::cpp_type::CppBuiltInNumericType;
 use crate::cpp_type::CppPointerLikeTypeKind;
 use crate::cpp_type::CppType;
 use crate::cpp_type::CppTypeRole;
 use crate::cpp_type::{is_qflags, CppFunctionPointerType};
+use crate::database::DatabaseClient;
 use crate::processor::ProcessorData;
 use itertools::Itertools;
-use log::{debug, trace};
-use ritual_common::errors::{bail, Result, ResultExt};
-use std::collections::HashSet;
+use log::{debug, info, trace, warn};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use ritual_common::errors::{bail, err_msg, format_err, Result, ResultExt};
+use ritual_common::file_utils::content_hash;
+use ritual_common::utils::MapIfOk;
+use std::collections::{HashMap, HashSet};
+use std::iter::once;
 
 /// Converts this C++ type to its adaptation for FFI interface,
 /// removing all features not supported by C ABI
 /// (e.g. references and passing objects by value).
-#[allow(clippy::collapsible_if)]
+/// Same as `to_ffi_type(original_type, role, None)`.
 pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType> {
+    to_ffi_type(original_type, role, None)
+}
+
+/// Converts `original_type` to the type used in the FFI wrappers in the position
+/// specified by `role`. This is the conversion used by the FFI generator,
+/// so custom processing steps should use it to obtain matching types.
+///
+/// Pointers are passed unchanged, and references are converted to pointers
+/// with the same constness (`const T&` becomes `const T*`).
+/// `QFlags<T>` and `const QFlags<T>&` are converted to `int`.
+///
+/// Classes passed by value are converted to pointers. The constness of
+/// such pointers depends on `role`:
+///
+/// - for `CppTypeRole::NotReturnType` (arguments), the pointer is const,
+///   because the wrapper only reads the object to pass its copy to the function;
+/// - for `CppTypeRole::ReturnType`, the pointer is not const, because it points
+///   to the returned object owned by the caller (or the output buffer).
+///
+/// If `is_value_pointer_const` is `Some`, it overrides this rule.
+#[allow(clippy::collapsible_if)]
+pub fn to_ffi_type(
+    original_type: &CppType,
+    role: CppTypeRole,
+    is_value_pointer_const: Option<bool>,
+) -> Result<CppFfiType> {
     let inner = || -> Result<CppFfiType> {
         if original_type.is_or_contains_template_parameter() {
             bail!("template parameters cannot be expressed in FFI");
@@ -41,7 +77,7 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
                 all_types.push(return_type.as_ref());
                 for arg in all_types {
                     match *arg {
-                        CppType::FunctionPointer(..) => {
+                        CppType::FunctionPointer(..) | CppType::MemberFunctionPointer(..) => {
                             bail!(
                                 "function pointers containing nested function pointers are \
                                  not supported"
@@ -61,12 +97,16 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
                 }
                 CppToFfiTypeConversion::NoChange
             }
+            CppType::MemberFunctionPointer(..) => {
+                CppToFfiTypeConversion::MemberFunctionPointerToStruct
+            }
             CppType::Class(path) => {
                 if is_qflags(&path) {
                     CppToFfiTypeConversion::QFlagsToInt
                 } else {
                     CppToFfiTypeConversion::ValueToPointer {
-                        is_ffi_const: role != CppTypeRole::ReturnType,
+                        is_ffi_const: is_value_pointer_const
+                            .unwrap_or(role != CppTypeRole::ReturnType),
                     }
                 }
             }
@@ -98,9 +138,40 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
     Ok(inner().with_context(|_| format!("Can't express type to FFI: {:?}", original_type))?)
 }
 
+/// Converts the return type of a Qt slot wrapper to its FFI type returned by the callback.
+/// `None` means void. Only types that the slot can convert back without taking
+/// ownership of a value (i.e. types without conversion and `QFlags`) are supported.
+pub fn slot_return_type(return_type: Option<&CppType>) -> Result<CppFfiType> {
+    let return_type = if let Some(return_type) = return_type {
+        ffi_type(return_type, CppTypeRole::ReturnType)?
+    } else {
+        return Ok(CppFfiType::void());
+    };
+    match return_type.conversion() {
+        CppToFfiTypeConversion::NoChange | CppToFfiTypeConversion::QFlagsToInt => Ok(return_type),
+        conversion => bail!("{:?} is not supported for slot return values", conversion),
+    }
+}
+
+/// Returns a short hash of `key` that only depends on the contents of `key`
+/// and is the same across runs, platforms and compiler versions
+/// (unlike `std::collections::hash_map::DefaultHasher`).
+fn stable_hash(key: &str) -> String {
+    let hash = content_hash(key.as_bytes());
+    hash[hash.len() - 8..].to_string()
+}
+
 pub struct FfiNameProvider {
     names: HashSet<String>,
     prefix: String,
+    /// If true, names are suffixed with a hash of the C++ signature.
+    /// Otherwise, name collisions are resolved with an incrementing number,
+    /// which makes names depend on the processing order.
+    use_stable_suffixes: bool,
+    /// Signatures of the first field accessor and the first real function
+    /// that used each name, for detecting collisions between them.
+    accessor_names: HashMap<String, String>,
+    function_names: HashMap<String, String>,
 }
 
 impl FfiNameProvider {
@@ -112,103 +183,1109 @@ impl FfiNameProvider {
             .map(|f| f.item.path().to_cpp_code().unwrap())
             .collect();
 
-        FfiNameProvider { prefix, names }
+        FfiNameProvider::with_stable_suffixes(prefix, names)
+    }
+
+    /// Creates a provider that generates names starting with `prefix`
+    /// and avoids `names` that are already taken.
+    pub fn with_stable_suffixes(prefix: String, names: HashSet<String>) -> Self {
+        FfiNameProvider {
+            names,
+            prefix,
+            use_stable_suffixes: true,
+            accessor_names: HashMap::new(),
+            function_names: HashMap::new(),
+        }
     }
 
     pub fn testing() -> Self {
         FfiNameProvider {
             names: HashSet::new(),
             prefix: String::new(),
+            use_stable_suffixes: false,
+            accessor_names: HashMap::new(),
+            function_names: HashMap::new(),
         }
     }
 
-    pub fn create_path(&mut self, name: &str) -> CppPath {
-        let mut num: Option<u32> = None;
-        let full_name = loop {
-            let full_name = format!(
-                "{}_{}{}",
+    /// Returns the name `create_path` would assign for `name` and `signature`.
+    fn next_name(&self, name: &str, signature: &str) -> String {
+        let base_name = if self.use_stable_suffixes {
+            format!(
+                "{}_{}_{}",
                 self.prefix,
                 name,
+                stable_hash(&format!("{}\n{}", name, signature))
+            )
+        } else {
+            format!("{}_{}", self.prefix, name)
+        };
+        let mut num: Option<u32> = None;
+        loop {
+            let full_name = format!(
+                "{}{}",
+                base_name,
                 num.map_or(String::new(), |num| num.to_string())
             );
             if !self.names.contains(&full_name) {
-                break full_name;
+                return full_name;
             }
             num = Some(num.map_or(1, |num| num + 1));
-        };
+        }
+    }
+
+    /// Creates a unique FFI function path based on `name`. `signature` should
+    /// identify the wrapped C++ item (e.g. the text of the C++ function signature).
+    /// Unless the provider was created with `FfiNameProvider::testing`,
+    /// the path contains a hash of `name` and `signature`, so it stays the same
+    /// when the crate is regenerated with an unrelated change.
+    pub fn create_path(&mut self, name: &str, signature: &str) -> CppPath {
+        let full_name = self.next_name(name, signature);
         let item = CppPathItem::from_good_str(&full_name);
         self.names.insert(full_name);
         CppPath::from_item(item)
     }
+
+    /// Records that `name` is used by a field accessor (if `is_field_accessor` is true)
+    /// or a real C++ function identified by `signature`. If `name` is registered
+    /// for this kind for the first time and was already used by an item
+    /// of the other kind, the names are still made unique by `create_path`,
+    /// but the Rust names of these items are likely to collide as well,
+    /// so a warning is logged and the signature of the other item is returned.
+    /// Each collision is reported only once.
+    pub fn register_name_kind(
+        &mut self,
+        name: &str,
+        signature: &str,
+        is_field_accessor: bool,
+    ) -> Option<String> {
+        let (own_names, other_names) = if is_field_accessor {
+            (&mut self.accessor_names, &self.function_names)
+        } else {
+            (&mut self.function_names, &self.accessor_names)
+        };
+        if own_names.contains_key(name) {
+            return None;
+        }
+        own_names.insert(name.to_string(), signature.to_string());
+        let other = other_names.get(name)?;
+        warn!(
+            "FFI name {:?} is used by both {} and {}; consider renaming one of them",
+            name, signature, other
+        );
+        Some(other.clone())
+    }
+
+    /// Returns the path a subsequent `create_path` call with the same arguments
+    /// would return, without reserving it.
+    pub fn peek_path(&self, name: &str, signature: &str) -> CppPath {
+        CppPath::from_item(CppPathItem::from_good_str(&self.next_name(name, signature)))
+    }
+
+    /// Registers the kind of the requested name (see `register_name_kind`)
+    /// and creates a unique path for it.
+    pub fn assign_name(&mut self, request: &FfiNameRequest) -> CppPath {
+        self.register_name_kind(
+            &request.caption,
+            &request.signature,
+            request.is_field_accessor,
+        );
+        self.create_path(&request.caption, &request.signature)
+    }
+}
+
+/// Name of an FFI function requested by the generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiNameRequest {
+    caption: String,
+    signature: String,
+    is_field_accessor: bool,
+}
+
+/// FFI items generated for a C++ item before their names are assigned.
+/// Item generation doesn't depend on other items, so it can run in parallel,
+/// but names depend on previously assigned names, so they are assigned
+/// separately in a sequential pass.
+#[derive(Debug)]
+pub struct UnnamedFfiItems {
+    /// Names requested by the generator, in order. A name is reserved for each request,
+    /// even if the generation failed after requesting it.
+    names: Vec<FfiNameRequest>,
+    /// Generated FFI functions (one for each name request) with temporary paths.
+    items: Result<Vec<CppFfiItem>>,
+}
+
+impl UnnamedFfiItems {
+    /// Assigns the requested names and returns the generated items.
+    pub fn assign_names(self, name_provider: &mut FfiNameProvider) -> Result<Vec<CppFfiItem>> {
+        let paths = self
+            .names
+            .iter()
+            .map(|name| name_provider.assign_name(name))
+            .collect_vec();
+        let mut items = self.items?;
+        for (item, path) in items.iter_mut().zip(paths) {
+            if let CppFfiItem::Function(function) = item {
+                function.path = path;
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// Settings of the FFI generator taken from `Config`. Unlike `Config`,
+/// these settings can be shared between threads.
+pub struct FfiGeneratorOptions<'a> {
+    pub movable_types: &'a [CppPath],
+    pub heap_return_variants: bool,
+    pub thread_safe_functions: &'a [CppPath],
+    pub duration_types: &'a [(CppPath, DurationPeriod)],
+    pub callback_arguments: &'a [(CppPath, usize, usize)],
+    pub catch_exceptions: bool,
+    pub array_length_arguments: bool,
+}
+
+impl<'a> FfiGeneratorOptions<'a> {
+    pub fn new(config: &'a Config, movable_types: &'a [CppPath]) -> Self {
+        FfiGeneratorOptions {
+            movable_types,
+            heap_return_variants: config.heap_return_variants(),
+            thread_safe_functions: config.thread_safe_functions(),
+            duration_types: config.duration_types(),
+            callback_arguments: config.callback_arguments(),
+            catch_exceptions: config.catch_exceptions(),
+            // array references don't need the length
+            array_length_arguments: config.array_length_arguments()
+                && !config.fixed_array_arguments(),
+        }
+    }
+}
+
+/// Generates FFI items for `item` without assigning their names.
+/// Returns `None` if no FFI items are generated for this kind of items.
+fn generate_unnamed_ffi_items(
+    item: &CppItem,
+    options: &FfiGeneratorOptions<'_>,
+) -> Option<UnnamedFfiItems> {
+    let mut names = Vec::new();
+    let items = match item {
+        CppItem::Function(method) => {
+            let is_thread_safe = options
+                .thread_safe_functions
+                .iter()
+                .any(|path| path == &method.path);
+            generate_ffi_methods_for_method(
+                method,
+                options.movable_types,
+                &mut names,
+                options.heap_return_variants,
+            )
+            .and_then(|v| {
+                v.into_iter().map_if_ok(|mut item| -> Result<_> {
+                    if let CppFfiItem::Function(function) = &mut item {
+                        function.is_thread_safe = is_thread_safe;
+                        apply_duration_types(function, method, options.duration_types)?;
+                        apply_callback_arguments(function, method, options.callback_arguments)?;
+                        apply_initializer_list_arguments(function)?;
+                        if options.array_length_arguments {
+                            add_array_length_arguments(function, method)?;
+                        }
+                        if options.catch_exceptions {
+                            add_exception_output(function, method)?;
+                        }
+                    }
+                    Ok(item)
+                })
+            })
+        }
+        CppItem::ClassField(field) => {
+            generate_unnamed_field_accessors(field, options.movable_types, &mut names)
+        }
+        CppItem::ClassBase(_)
+        | CppItem::Type(_)
+        | CppItem::EnumValue(_)
+        | CppItem::Namespace(_) => {
+            // no FFI methods for these items
+            return None;
+        }
+    };
+    Some(UnnamedFfiItems { names, items })
+}
+
+/// Generates FFI items for `items`. The items are generated in parallel
+/// if `parallel` is true, but the names are always assigned in the order of `items`,
+/// so the result doesn't depend on `parallel`. Returns generated items
+/// (or the error) for each of `items`, or `None` if no FFI items are generated
+/// for this kind of items.
+pub fn generate_ffi_items(
+    items: &[&CppItem],
+    options: &FfiGeneratorOptions<'_>,
+    name_provider: &mut FfiNameProvider,
+    parallel: bool,
+) -> Vec<Option<Result<Vec<CppFfiItem>>>> {
+    let unnamed = if parallel {
+        items
+            .par_iter()
+            .map(|item| generate_unnamed_ffi_items(item, options))
+            .collect::<Vec<_>>()
+    } else {
+        items
+            .iter()
+            .map(|item| generate_unnamed_ffi_items(item, options))
+            .collect()
+    };
+    unnamed
+        .into_iter()
+        .map(|unnamed| unnamed.map(|unnamed| unnamed.assign_names(name_provider)))
+        .collect()
 }
 
 /// Runs the FFI generator
 pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
-    // movable types are not supported for now
-    let movable_types = Vec::new();
+    let failures = run_with_report(data)?;
+    if failures.is_empty() {
+        return Ok(());
+    }
+    if data.config.strict_ffi_conversion() {
+        bail!(
+            "{} C++ items could not be converted to FFI items:\n{}",
+            failures.len(),
+            failures
+                .iter()
+                .map(|(path, error)| format!("{}: {}", path.to_cpp_pseudo_code(), error))
+                .join("\n")
+        );
+    }
+    info!(
+        "{} C++ items could not be converted to FFI items",
+        failures.len()
+    );
+    Ok(())
+}
+
+/// Returns paths of `items` for which `results` of `generate_ffi_items`
+/// contain an error, along with the errors. For base specifiers, the path
+/// of the derived class is reported.
+pub fn conversion_failures(
+    items: &[&CppItem],
+    results: &[Option<Result<Vec<CppFfiItem>>>],
+) -> Vec<(CppPath, String)> {
+    items
+        .iter()
+        .zip(results)
+        .filter_map(|(item, result)| match result {
+            Some(Err(error)) => {
+                let path = match item {
+                    CppItem::ClassBase(base) => &base.derived_class_type,
+                    other => other.path().expect("only base specifiers don't have paths"),
+                };
+                Some((path.clone(), error.iter_chain().join(": ")))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Same as `run`, but also returns paths of C++ items that could not be converted
+/// to FFI items, along with the errors. Items skipped because of `check_preconditions`
+/// or the FFI generator hook are not reported. See `conversion_failures`.
+pub fn run_with_report(data: &mut ProcessorData<'_>) -> Result<Vec<(CppPath, String)>> {
+    let movable_types = stack_allocated_types(&data.db, data.config);
 
     let mut name_provider = FfiNameProvider::new(data);
 
-    let all_cpp_item_ids = data.db.cpp_item_ids().collect_vec();
+    let all_cpp_items = data.db.cpp_items().map(|item| item.item).collect_vec();
+    let abstract_classes = abstract_classes(&all_cpp_items);
+    let reachable = if data.config.root_classes().is_empty() {
+        None
+    } else {
+        let reachable = reachable_types(&all_cpp_items, data.config.root_classes());
+        debug!(
+            "{} types are reachable from the root classes",
+            reachable.len()
+        );
+        Some(reachable)
+    };
 
-    for cpp_item_id in all_cpp_item_ids {
-        let item = data.db.cpp_item(&cpp_item_id)?;
-        if let Err(err) = check_preconditions(&item.item) {
+    let allowed_prefixes = data.config.allowed_path_prefixes();
+    let mut items = Vec::new();
+    for item in data.db.cpp_items() {
+        if !is_allowed_item(&item.item, allowed_prefixes) {
+            trace!("skipping {} (not in the allow-list)", item.item);
+            continue;
+        }
+        if let Some(reachable) = &reachable {
+            if !is_reachable_item(&item.item, reachable) {
+                trace!(
+                    "skipping {} (not reachable from the root classes)",
+                    item.item
+                );
+                continue;
+            }
+        }
+        if let Err(err) = check_preconditions(&item.item, data.config) {
             trace!("skipping {}: {}", item.item, err);
             continue;
         }
+        if let Some(function) = item.item.as_function_ref() {
+            let is_abstract_class = function
+                .class_path()
+                .map_or(false, |path| abstract_classes.contains(&path));
+            if function.is_constructor() && function.is_protected() && is_abstract_class {
+                // the shim subclass of an abstract class is also abstract,
+                // so it can't be constructed
+                warn!(
+                    "protected constructor of abstract class is not supported: {}",
+                    function.short_text()
+                );
+                continue;
+            }
+        }
         if let Some(hook) = data.config.ffi_generator_hook() {
             if !hook(&item.item)? {
                 trace!("skipping {} (by hook)", item.item);
                 continue;
             }
         }
-        let result = match &item.item {
-            CppItem::Function(method) => {
-                generate_ffi_methods_for_method(method, &movable_types, &mut name_provider)
-                    .map(|v| v.into_iter().collect_vec())
+        items.push(item);
+    }
+
+    let options = FfiGeneratorOptions::new(data.config, &movable_types);
+    let cpp_items = items.iter().map(|item| item.item).collect_vec();
+    for path in excluded_dependencies(&cpp_items, allowed_prefixes) {
+        warn!(
+            "{} is used by allowed items, but its members are not wrapped \
+             because it's not in the allow-list",
+            path.to_cpp_pseudo_code()
+        );
+    }
+    let results = generate_ffi_items(&cpp_items, &options, &mut name_provider, true);
+    let failures = conversion_failures(&cpp_items, &results);
+
+    let mut new_items = Vec::new();
+    let mut wrapped_functions = Vec::new();
+    for (item, result) in items.iter().zip(results) {
+        if let (Some(Ok(_)), Some(function)) = (&result, item.item.as_function_ref()) {
+            wrapped_functions.push(function);
+        }
+        match result {
+            None => {}
+            Some(Err(error)) => {
+                debug!("failed to add FFI item: {}: {}", item.item, error);
+            }
+            Some(Ok(r)) => {
+                new_items.push((item.id.clone(), r));
+            }
+        }
+    }
+    let bases = all_cpp_items
+        .iter()
+        .filter_map(|item| item.as_base_ref())
+        .collect_vec();
+    for path in destructible_types_without_constructors(&wrapped_functions, &bases) {
+        warn!(
+            "{} has a wrapped destructor, but no wrapped constructor or function returning it",
+            path.to_cpp_pseudo_code()
+        );
+    }
+    for (source_id, r) in new_items {
+        for new_item in r {
+            data.db.add_ffi_item(Some(source_id.clone()), new_item)?;
+        }
+    }
+    Ok(failures)
+}
+
+/// Returns the class `cpp_type` refers to, directly or through a pointer or reference.
+fn referenced_class(cpp_type: &CppType) -> Option<&CppPath> {
+    match cpp_type {
+        CppType::PointerLike { target, .. } => referenced_class(target),
+        CppType::Class(path) => Some(path),
+        _ => None,
+    }
+}
+
+fn is_allowed_path(path: &CppPath, prefixes: &[CppPath]) -> bool {
+    prefixes.is_empty() || prefixes.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Returns true if FFI wrappers for `item` should be generated according to
+/// the allow-list `prefixes` (see `Config::add_allowed_path_prefix`).
+/// All items are allowed if `prefixes` is empty.
+pub fn is_allowed_item(item: &CppItem, prefixes: &[CppPath]) -> bool {
+    if prefixes.is_empty() {
+        return true;
+    }
+    match item {
+        CppItem::ClassBase(base) => {
+            is_allowed_path(&base.derived_class_type, prefixes)
+                && is_allowed_path(&base.base_class_type, prefixes)
+        }
+        CppItem::Function(function) if function.cast.is_some() => once(&function.return_type)
+            .chain(function.arguments.iter().map(|arg| &arg.argument_type))
+            .filter_map(referenced_class)
+            .all(|path| is_allowed_path(path, prefixes)),
+        other => other
+            .path()
+            .map_or(true, |path| is_allowed_path(path, prefixes)),
+    }
+}
+
+/// Returns classes used in signatures of `items` that are excluded by
+/// the allow-list `prefixes`, so their members don't have FFI wrappers.
+pub fn excluded_dependencies(items: &[&CppItem], prefixes: &[CppPath]) -> Vec<CppPath> {
+    if prefixes.is_empty() {
+        return Vec::new();
+    }
+    items
+        .iter()
+        .flat_map(|item| item.all_involved_types())
+        .filter_map(|cpp_type| referenced_class(&cpp_type).cloned())
+        .filter(|path| !is_allowed_path(path, prefixes))
+        .unique()
+        .collect()
+}
+
+/// Returns paths of `roots` and all types transitively referenced by them
+/// through non-private methods, fields and base classes of `items`.
+pub fn reachable_types(items: &[&CppItem], roots: &[CppPath]) -> HashSet<CppPath> {
+    let mut references = HashMap::<CppPath, Vec<CppPath>>::new();
+    for item in items {
+        match item {
+            CppItem::Function(function) => {
+                let is_visible = function
+                    .member
+                    .as_ref()
+                    .map_or(false, |member| member.visibility != CppVisibility::Private);
+                if let (true, Ok(class_path)) = (is_visible, function.class_path()) {
+                    let output = references.entry(class_path).or_insert_with(Vec::new);
+                    for type1 in function.all_involved_types() {
+                        type1.collect_paths(output);
+                    }
+                }
             }
             CppItem::ClassField(field) => {
-                generate_field_accessors(field, &movable_types, &mut name_provider)
-                    .map(|v| v.into_iter().collect_vec())
-            }
-            CppItem::ClassBase(_)
-            | CppItem::Type(_)
-            | CppItem::EnumValue(_)
-            | CppItem::Namespace(_) => {
-                // no FFI methods for these items
+                if let (true, Ok(class_path)) = (
+                    field.visibility != CppVisibility::Private,
+                    field.path.parent(),
+                ) {
+                    let output = references.entry(class_path).or_insert_with(Vec::new);
+                    field.field_type.collect_paths(output);
+                }
+            }
+            CppItem::ClassBase(base) => {
+                let output = references
+                    .entry(base.derived_class_type.clone())
+                    .or_insert_with(Vec::new);
+                CppType::Class(base.base_class_type.clone()).collect_paths(output);
+            }
+            CppItem::Namespace(_) | CppItem::Type(_) | CppItem::EnumValue(_) => {}
+        }
+    }
+
+    let mut result = HashSet::new();
+    let mut queue = roots.to_vec();
+    while let Some(path) = queue.pop() {
+        if !result.insert(path.clone()) {
+            continue;
+        }
+        let mut referenced = Vec::new();
+        CppType::Class(path.clone()).collect_paths(&mut referenced);
+        if let Some(paths) = references.get(&path) {
+            referenced.extend(paths.iter().cloned());
+        }
+        queue.extend(referenced.into_iter().filter(|p| !result.contains(p)));
+    }
+    result
+}
+
+/// Returns true if FFI wrappers for `item` should be generated when only
+/// the `reachable` types are wrapped (see `Config::add_root_class`).
+/// Casts are kept if all classes they refer to are reachable.
+pub fn is_reachable_item(item: &CppItem, reachable: &HashSet<CppPath>) -> bool {
+    match item {
+        CppItem::Namespace(namespace) => reachable
+            .iter()
+            .any(|path| path.items().starts_with(namespace.path.items())),
+        CppItem::Type(type1) => reachable.contains(&type1.path),
+        CppItem::EnumValue(value) => value
+            .path
+            .parent()
+            .map_or(false, |parent| reachable.contains(&parent)),
+        CppItem::Function(function) if function.cast.is_some() => once(&function.return_type)
+            .chain(function.arguments.iter().map(|arg| &arg.argument_type))
+            .filter_map(referenced_class)
+            .all(|path| reachable.contains(path)),
+        CppItem::Function(function) => function
+            .class_path()
+            .map_or(false, |parent| reachable.contains(&parent)),
+        CppItem::ClassField(field) => field
+            .path
+            .parent()
+            .map_or(false, |parent| reachable.contains(&parent)),
+        CppItem::ClassBase(base) => reachable.contains(&base.derived_class_type),
+    }
+}
+
+/// Returns classes in `items` that are abstract, i.e. have pure virtual
+/// functions that are not overridden. A pure virtual function of a base class
+/// is considered overridden if a class between it and the derived class
+/// declares a function with the same name.
+pub fn abstract_classes(items: &[&CppItem]) -> HashSet<CppPath> {
+    #[derive(Default)]
+    struct ClassInfo {
+        pure_virtual: HashSet<String>,
+        declared: HashSet<String>,
+        bases: Vec<CppPath>,
+    }
+
+    fn unresolved_functions(
+        class: &CppPath,
+        classes: &HashMap<CppPath, ClassInfo>,
+        cache: &mut HashMap<CppPath, HashSet<String>>,
+    ) -> HashSet<String> {
+        if let Some(names) = cache.get(class) {
+            return names.clone();
+        }
+        // protects from infinite recursion on invalid input
+        cache.insert(class.clone(), HashSet::new());
+        let mut result = HashSet::new();
+        if let Some(info) = classes.get(class) {
+            result.extend(info.pure_virtual.iter().cloned());
+            for base in &info.bases {
+                for name in unresolved_functions(base, classes, cache) {
+                    if !info.declared.contains(&name) {
+                        result.insert(name);
+                    }
+                }
+            }
+        }
+        cache.insert(class.clone(), result.clone());
+        result
+    }
+
+    let mut classes = HashMap::<CppPath, ClassInfo>::new();
+    for item in items {
+        match item {
+            CppItem::Function(function) => {
+                let membership = match &function.member {
+                    Some(membership) => membership,
+                    None => continue,
+                };
+                let class_path = match function.class_path() {
+                    Ok(path) => path,
+                    Err(_) => continue,
+                };
+                let info = classes.entry(class_path).or_default();
+                let name = function.path.last().name.clone();
+                if membership.is_pure_virtual {
+                    info.pure_virtual.insert(name);
+                } else {
+                    info.declared.insert(name);
+                }
+            }
+            CppItem::ClassBase(base) => {
+                classes
+                    .entry(base.derived_class_type.clone())
+                    .or_default()
+                    .bases
+                    .push(base.base_class_type.clone());
+            }
+            _ => {}
+        }
+    }
+
+    let mut cache = HashMap::new();
+    classes
+        .keys()
+        .filter(|class| !unresolved_functions(class, &classes, &mut cache).is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Returns classes that have a destructor in `functions`, but no constructor
+/// or function returning the class (by value, pointer or reference), so objects
+/// of these classes can't be created through the wrappers. Casts are not
+/// considered a way to create an object, but a class is considered constructible
+/// if a class publicly derived from it (directly or indirectly, according to `bases`)
+/// is constructible.
+pub fn destructible_types_without_constructors(
+    functions: &[&CppFunction],
+    bases: &[&CppBaseSpecifier],
+) -> Vec<CppPath> {
+    let mut constructible = HashSet::new();
+    for function in functions {
+        if function.cast.is_some() || function.is_destructor() {
+            continue;
+        }
+        if function.is_constructor() {
+            if let Ok(class_path) = function.class_path() {
+                constructible.insert(class_path);
+            }
+            continue;
+        }
+        let return_type = match &function.return_type {
+            CppType::PointerLike { target, .. } => &**target,
+            other => other,
+        };
+        if let CppType::Class(path) = return_type {
+            constructible.insert(path.clone());
+        }
+    }
+    loop {
+        let new_bases = bases
+            .iter()
+            .filter(|base| {
+                base.visibility == CppVisibility::Public
+                    && constructible.contains(&base.derived_class_type)
+                    && !constructible.contains(&base.base_class_type)
+            })
+            .map(|base| base.base_class_type.clone())
+            .collect_vec();
+        if new_bases.is_empty() {
+            break;
+        }
+        constructible.extend(new_bases);
+    }
+    functions
+        .iter()
+        .filter(|function| function.is_destructor())
+        .filter_map(|function| function.class_path().ok())
+        .filter(|path| !constructible.contains(path))
+        .unique()
+        .collect()
+}
+
+/// Adds an argument receiving the number of elements after each pointer argument
+/// of `function` declared as a fixed-size array in `method` (e.g. `float out[16]`).
+/// The array decays to a pointer, so the length is passed separately to allow
+/// the Rust API to accept a slice. The C++ wrapper checks that the slice
+/// is not shorter than the declared size. See `Config::set_array_length_arguments`.
+pub fn add_array_length_arguments(
+    function: &mut CppFfiFunction,
+    method: &CppFunction,
+) -> Result<()> {
+    for (index, arg) in method.arguments.iter().enumerate() {
+        let is_pointer = match &arg.argument_type {
+            CppType::PointerLike { kind, .. } => *kind == CppPointerLikeTypeKind::Pointer,
+            _ => false,
+        };
+        if !is_pointer || method.fixed_array_argument_size(index).is_none() {
+            continue;
+        }
+        let position = function
+            .arguments
+            .iter()
+            .position(|arg| arg.meaning == CppFfiArgumentMeaning::Argument(index))
+            .ok_or_else(|| err_msg("array argument not found"))?;
+        insert_array_length_argument(function, position, index)?;
+    }
+    Ok(())
+}
+
+/// Inserts an `ArrayLength(index)` argument after the argument at `position`
+/// of `function`.
+fn insert_array_length_argument(
+    function: &mut CppFfiFunction,
+    position: usize,
+    index: usize,
+) -> Result<()> {
+    let length_arg = CppFfiFunctionArgument {
+        name: format!("{}_len", function.arguments[position].name),
+        argument_type: CppFfiType::new(
+            CppType::PointerSizedInteger {
+                path: CppPath::from_good_str("size_t"),
+                is_signed: false,
+            },
+            CppToFfiTypeConversion::NoChange,
+        )?,
+        meaning: CppFfiArgumentMeaning::ArrayLength(index),
+    };
+    function.arguments.insert(position + 1, length_arg);
+    Ok(())
+}
+
+/// Replaces `std::initializer_list<T>` arguments of `function` with a pointer
+/// to the elements followed by their number, so the Rust API can accept a slice.
+/// The C++ wrapper constructs the list from the elements
+/// (see `ritual_with_initializer_list` in the global header), which limits
+/// its size to `RITUAL_MAX_INITIALIZER_LIST_SIZE`. The Rust wrapper panics if
+/// the slice is longer. Only element types that are
+/// passed to FFI unchanged (numbers, enums and pointers) are supported.
+pub fn apply_initializer_list_arguments(function: &mut CppFfiFunction) -> Result<()> {
+    let mut position = 0;
+    while position < function.arguments.len() {
+        let arg = &function.arguments[position];
+        let index = match arg.meaning {
+            CppFfiArgumentMeaning::Argument(index) => index,
+            _ => {
+                position += 1;
                 continue;
             }
         };
+        let original_type = arg.argument_type.original_type().clone();
+        let element_type = match original_type.initializer_list_element_type() {
+            Some(element_type) => element_type,
+            None => {
+                position += 1;
+                continue;
+            }
+        };
+        if let CppType::PointerLike { is_const, .. } = &original_type {
+            if !is_const {
+                bail!("non-const references to std::initializer_list are not supported");
+            }
+        }
+        if ffi_type(element_type, CppTypeRole::NotReturnType)?.conversion()
+            != &CppToFfiTypeConversion::NoChange
+        {
+            bail!(
+                "std::initializer_list of {} is not supported",
+                element_type.to_cpp_pseudo_code()
+            );
+        }
+        function.arguments[position].argument_type = CppFfiType::new(
+            original_type,
+            CppToFfiTypeConversion::InitializerListToPointer,
+        )?;
+        insert_array_length_argument(function, position, index)?;
+        position += 2;
+    }
+    Ok(())
+}
 
-        match result {
-            Err(error) => {
-                debug!("failed to add FFI item: {}: {}", item.item, error);
+/// Adds an argument receiving the message of a caught exception to the FFI
+/// wrapper `function` of `method`. Destructors, casts and `noexcept` functions
+/// can't throw, so they are not changed. See `Config::set_catch_exceptions`.
+pub fn add_exception_output(function: &mut CppFfiFunction, method: &CppFunction) -> Result<()> {
+    if method.is_noexcept || method.is_destructor() || method.cast.is_some() {
+        return Ok(());
+    }
+    function.arguments.push(CppFfiFunctionArgument {
+        name: "exception".to_string(),
+        argument_type: CppFfiType::new(
+            CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Char)),
+            CppToFfiTypeConversion::NoChange,
+        )?,
+        meaning: CppFfiArgumentMeaning::ExceptionOutput,
+    });
+    Ok(())
+}
+
+/// Marks the arguments of `function` configured as callback and user data pairs
+/// with the corresponding meanings. `method` is the original C++ function.
+/// See `Config::add_callback_argument`.
+fn apply_callback_arguments(
+    function: &mut CppFfiFunction,
+    method: &CppFunction,
+    callback_arguments: &[(CppPath, usize, usize)],
+) -> Result<()> {
+    let pairs = callback_arguments
+        .iter()
+        .filter(|(path, _, _)| path == &method.path)
+        .collect_vec();
+    if pairs.is_empty() {
+        return Ok(());
+    }
+    if pairs.len() > 1 {
+        bail!("only one callback argument per function is supported");
+    }
+    let (_, callback_index, user_data_index) = *pairs[0];
+    if callback_index == user_data_index {
+        bail!("callback and user data must be different arguments");
+    }
+
+    let is_void_pointer = |cpp_type: &CppType| {
+        if let CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::Pointer,
+            is_const: false,
+            target,
+        } = cpp_type
+        {
+            target.is_void()
+        } else {
+            false
+        }
+    };
+    let callback_type = method
+        .arguments
+        .get(callback_index)
+        .ok_or_else(|| format_err!("callback argument index is out of range"))?;
+    match &callback_type.argument_type {
+        CppType::FunctionPointer(CppFunctionPointerType {
+            return_type,
+            arguments,
+            ..
+        }) => {
+            if !return_type.is_void() {
+                bail!("callback must return void");
             }
-            Ok(r) => {
-                let source_id = item.id;
-                for new_item in r {
-                    data.db.add_ffi_item(Some(source_id.clone()), new_item)?;
+            if !arguments.last().map_or(false, |arg| is_void_pointer(arg)) {
+                bail!("callback must accept user data (void*) as its last argument");
+            }
+        }
+        _ => bail!("callback argument must be a function pointer"),
+    }
+    let user_data_type = method
+        .arguments
+        .get(user_data_index)
+        .ok_or_else(|| format_err!("user data argument index is out of range"))?;
+    if !is_void_pointer(&user_data_type.argument_type) {
+        bail!("user data argument must be void*");
+    }
+
+    let position = |index| {
+        function
+            .arguments
+            .iter()
+            .position(|arg| arg.meaning == CppFfiArgumentMeaning::Argument(index))
+    };
+    // arguments with default values may be omitted in the FFI function
+    if let (Some(callback_position), Some(user_data_position)) =
+        (position(callback_index), position(user_data_index))
+    {
+        function.arguments[callback_position].meaning =
+            CppFfiArgumentMeaning::Callback(callback_index);
+        function.arguments[user_data_position].meaning =
+            CppFfiArgumentMeaning::CallbackUserData(user_data_index);
+    }
+    Ok(())
+}
+
+/// Replaces arguments and the return value of `function` that have one of `duration_types`
+/// with their tick counts. `method` is the original C++ function.
+/// See `Config::add_duration_type`.
+pub fn apply_duration_types(
+    function: &mut CppFfiFunction,
+    method: &CppFunction,
+    duration_types: &[(CppPath, DurationPeriod)],
+) -> Result<()> {
+    if duration_types.is_empty() {
+        return Ok(());
+    }
+    let duration_period = |cpp_type: &CppType| -> Option<DurationPeriod> {
+        let path = match cpp_type {
+            CppType::Class(path) => path,
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                is_const: true,
+                target,
+            } => {
+                if let CppType::Class(path) = &**target {
+                    path
+                } else {
+                    return None;
                 }
             }
+            _ => return None,
+        };
+        duration_types
+            .iter()
+            .find(|(duration_path, _)| duration_path == path)
+            .map(|(_, period)| *period)
+    };
+
+    for arg in &mut function.arguments {
+        if !arg.meaning.is_argument() {
+            continue;
+        }
+        if let Some(period) = duration_period(arg.argument_type.original_type()) {
+            arg.argument_type = CppFfiType::new(
+                arg.argument_type.original_type().clone(),
+                CppToFfiTypeConversion::DurationToTicks { period },
+            )?;
+        }
+    }
+
+    if method.is_constructor() {
+        return Ok(());
+    }
+    let return_value_index = function
+        .arguments
+        .iter()
+        .position(|arg| arg.meaning == CppFfiArgumentMeaning::ReturnValue);
+    let original_return_type = match return_value_index {
+        Some(index) => function.arguments[index].argument_type.original_type(),
+        None => function.return_type.original_type(),
+    }
+    .clone();
+    if let Some(period) = duration_period(&original_return_type) {
+        if let Some(index) = return_value_index {
+            function.arguments.remove(index);
         }
+        function.return_type = CppFfiType::new(
+            original_return_type,
+            CppToFfiTypeConversion::DurationToTicks { period },
+        )?;
+        function.allocation_place = ReturnValueAllocationPlace::NotApplicable;
     }
     Ok(())
 }
 
+/// Adds classes from `movable` to `movable_types` and removes classes in `non_movable`
+/// from it. `non_movable` takes priority if a class is present in both lists.
+/// See `Config::add_movable_type` and `Config::add_non_movable_type`.
+pub fn apply_movable_type_overrides(
+    mut movable_types: Vec<CppPath>,
+    movable: &[CppPath],
+    non_movable: &[CppPath],
+) -> Vec<CppPath> {
+    for path in movable {
+        if !movable_types.contains(path) {
+            movable_types.push(path.clone());
+        }
+    }
+    movable_types.retain(|path| !non_movable.contains(path));
+    movable_types
+}
+
+/// Returns classes that are allocated on the stack according to `config`.
+/// Movable types are not detected for now, so only the types explicitly
+/// marked as movable or selected by the allocation place hook are allocated
+/// on the stack. Non-relocatable types are removed last, so the hook can't
+/// force stack allocation for them.
+pub fn stack_allocated_types(db: &DatabaseClient, config: &Config) -> Vec<CppPath> {
+    let movable_types = apply_movable_type_overrides(
+        Vec::new(),
+        config.movable_types(),
+        config.non_movable_types(),
+    );
+    let movable_types = if let Some(hook) = config.allocation_place_hook() {
+        apply_allocation_place_hook(db, movable_types, hook)
+    } else {
+        movable_types
+    };
+    remove_non_relocatable_types(db, movable_types, config.non_relocatable_types())
+}
+
+/// Removes classes that can't be relocated by copying their bytes from `movable_types`,
+/// so that they are always allocated on the heap. See `non_relocatable_types`.
+pub fn remove_non_relocatable_types(
+    db: &DatabaseClient,
+    mut movable_types: Vec<CppPath>,
+    forced_types: &[CppPath],
+) -> Vec<CppPath> {
+    let non_relocatable_types = non_relocatable_types(db, forced_types);
+    for path in &movable_types {
+        if non_relocatable_types.contains(path) {
+            warn!(
+                "{} is not relocatable, so it can't be allocated on the stack",
+                path.to_cpp_pseudo_code()
+            );
+        }
+    }
+    movable_types.retain(|path| !non_relocatable_types.contains(path));
+    movable_types
+}
+
+/// Adds or removes classes from `movable_types` according to the allocation place
+/// returned by `hook` (see `Config::set_allocation_place_hook`).
+pub fn apply_allocation_place_hook(
+    db: &DatabaseClient,
+    mut movable_types: Vec<CppPath>,
+    hook: &AllocationPlaceHook,
+) -> Vec<CppPath> {
+    let class_paths = db
+        .all_cpp_items()
+        .filter_map(|item| item.item.as_type_ref())
+        .filter(|type1| type1.kind.is_class())
+        .map(|type1| type1.path.clone())
+        .collect_vec();
+    for path in class_paths {
+        match hook(&path) {
+            Some(ReturnValueAllocationPlace::Stack) => {
+                if !movable_types.contains(&path) {
+                    debug!("forcing stack allocation for {}", path.to_cpp_pseudo_code());
+                    movable_types.push(path);
+                }
+            }
+            Some(ReturnValueAllocationPlace::Heap) => {
+                if movable_types.contains(&path) {
+                    debug!("forcing heap allocation for {}", path.to_cpp_pseudo_code());
+                    movable_types.retain(|t| t != &path);
+                }
+            }
+            Some(ReturnValueAllocationPlace::NotApplicable) | None => {}
+        }
+    }
+    movable_types
+}
+
+/// Returns classes that can't be relocated by copying their bytes and therefore
+/// must never be allocated on the stack. This includes `forced_types`, classes
+/// with virtual bases (the virtual base pointer may point into the object's storage),
+/// and classes that derive from such classes or contain them as non-static fields.
+fn non_relocatable_types(db: &DatabaseClient, forced_types: &[CppPath]) -> HashSet<CppPath> {
+    let mut types = forced_types.iter().cloned().collect::<HashSet<_>>();
+    loop {
+        let mut new_types = Vec::new();
+        for item in db.all_cpp_items() {
+            let path = match &item.item {
+                CppItem::ClassBase(base) => {
+                    if base.is_virtual || types.contains(&base.base_class_type) {
+                        Some(base.derived_class_type.clone())
+                    } else {
+                        None
+                    }
+                }
+                CppItem::ClassField(field) => match &field.field_type {
+                    CppType::Class(field_path)
+                        if !field.is_static && types.contains(field_path) =>
+                    {
+                        field.path.parent().ok()
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            if let Some(path) = path {
+                if !types.contains(&path) {
+                    new_types.push(path);
+                }
+            }
+        }
+        if new_types.is_empty() {
+            break;
+        }
+        types.extend(new_types);
+    }
+    types
+}
+
+/// Generates FFI wrappers for `method`. If `heap_return_variants` is true,
+/// a heap-allocating variant is additionally generated if
+/// the regular wrapper allocates the return value on the stack
+/// (or if `method` is the destructor of a movable type).
 fn generate_ffi_methods_for_method(
     method: &CppFunction,
     movable_types: &[CppPath],
-    name_provider: &mut FfiNameProvider,
+    names: &mut Vec<FfiNameRequest>,
+    heap_return_variants: bool,
 ) -> Result<Vec<CppFfiItem>> {
     let mut methods = Vec::new();
-    methods.push(CppFfiItem::Function(to_ffi_method(
+    let function = to_unnamed_ffi_method(
         NewFfiFunctionKind::Function {
             cpp_function: method.clone(),
         },
         movable_types,
-        name_provider,
-    )?));
+        names,
+        None,
+    )?;
+    let needs_heap_variant = heap_return_variants
+        && function.allocation_place == ReturnValueAllocationPlace::Stack
+        && !method.is_constructor();
+    methods.push(CppFfiItem::Function(function));
+
+    if needs_heap_variant {
+        methods.push(CppFfiItem::Function(to_unnamed_ffi_method(
+            NewFfiFunctionKind::Function {
+                cpp_function: method.clone(),
+            },
+            movable_types,
+            names,
+            Some(ReturnValueAllocationPlace::Heap),
+        )?));
+    }
 
     Ok(methods)
 }
@@ -223,37 +1300,139 @@ pub enum NewFfiFunctionKind {
     },
 }
 
+/// Returns caption of the FFI function wrapping the destructor of `class_path`.
+///
+/// Destructors of heap-allocated types delete the object (`_delete` suffix),
+/// and destructors of stack-allocated types only run the destructor
+/// without freeing the memory (`_drop` suffix).
+pub fn destructor_caption(
+    class_path: &CppPath,
+    allocation_place: ReturnValueAllocationPlace,
+) -> String {
+    let suffix = match allocation_place {
+        ReturnValueAllocationPlace::Stack => "drop",
+        ReturnValueAllocationPlace::Heap | ReturnValueAllocationPlace::NotApplicable => "delete",
+    };
+    format!("{}_{}", class_path.ascii_caption(), suffix)
+}
+
+fn destructor_allocation_place(is_movable: bool) -> ReturnValueAllocationPlace {
+    if is_movable {
+        ReturnValueAllocationPlace::Stack
+    } else {
+        ReturnValueAllocationPlace::Heap
+    }
+}
+
 /// Creates FFI function signature for this function:
 /// - converts all types to FFI types;
 /// - adds "this" argument explicitly if present;
 /// - adds "output" argument for return value if
 ///   the return value is stack-allocated.
+///
+/// The allocation place of the return value (or the destructor)
+/// is determined by `movable_types` unless `forced_allocation_place` is specified.
 pub fn to_ffi_method(
     kind: NewFfiFunctionKind,
     movable_types: &[CppPath],
     name_provider: &mut FfiNameProvider,
+    forced_allocation_place: Option<ReturnValueAllocationPlace>,
 ) -> Result<CppFfiFunction> {
+    let mut names = Vec::new();
+    let function = to_unnamed_ffi_method(kind, movable_types, &mut names, forced_allocation_place);
+    let mut paths = names
+        .iter()
+        .map(|name| name_provider.assign_name(name))
+        .collect_vec();
+    let mut function = function?;
+    function.path = paths
+        .pop()
+        .ok_or_else(|| err_msg("FFI name was not requested"))?;
+    Ok(function)
+}
+
+/// Same as `to_ffi_method`, but instead of assigning the name,
+/// adds the name request to `names` and uses a temporary path.
+fn to_unnamed_ffi_method(
+    kind: NewFfiFunctionKind,
+    movable_types: &[CppPath],
+    names: &mut Vec<FfiNameRequest>,
+    forced_allocation_place: Option<ReturnValueAllocationPlace>,
+) -> Result<CppFfiFunction> {
+    if let NewFfiFunctionKind::Function { cpp_function } = &kind {
+        // checked before creating the name to avoid reserving a name
+        // that is never used
+        if cpp_function.allows_variadic_arguments {
+            bail!("Variable arguments are not supported");
+        }
+    }
+    let signature = match &kind {
+        NewFfiFunctionKind::Function { cpp_function } => cpp_function.short_text(),
+        NewFfiFunctionKind::FieldAccessor { field, .. } => field.short_text(),
+    };
+    let is_movable = |path: &CppPath| match forced_allocation_place {
+        Some(place) => place == ReturnValueAllocationPlace::Stack,
+        None => movable_types.iter().any(|t| t == path),
+    };
+    let is_heap_variant = forced_allocation_place == Some(ReturnValueAllocationPlace::Heap);
     let ascii_caption = match &kind {
-        NewFfiFunctionKind::Function { cpp_function, .. } => cpp_function.path.ascii_caption(),
+        NewFfiFunctionKind::Function { cpp_function, .. } => {
+            if cpp_function.is_destructor() {
+                let class_path = cpp_function.class_path()?;
+                destructor_caption(
+                    &class_path,
+                    destructor_allocation_place(is_movable(&class_path)),
+                )
+            } else {
+                let caption =
+                    if let Some(CppOperator::Conversion(target_type)) = &cpp_function.operator {
+                        format!(
+                            "{}_convert_to_{}",
+                            cpp_function.class_path()?.ascii_caption(),
+                            target_type.ascii_caption()
+                        )
+                    } else {
+                        cpp_function.path.ascii_caption()
+                    };
+                if is_heap_variant {
+                    format!("{}_heap", caption)
+                } else {
+                    caption
+                }
+            }
+        }
         NewFfiFunctionKind::FieldAccessor {
             field,
             accessor_type,
         } => {
             let field_caption = field.path.ascii_caption();
             match *accessor_type {
-                CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => {
-                    field_caption
-                }
+                CppFieldAccessorType::CopyGetter
+                | CppFieldAccessorType::ConstRefGetter
+                | CppFieldAccessorType::ArrayPointerGetter => field_caption,
                 CppFieldAccessorType::MutRefGetter => format!("{}_mut", field_caption),
-                CppFieldAccessorType::Setter => format!("set_{}", field_caption),
+                CppFieldAccessorType::Setter | CppFieldAccessorType::ArrayElementSetter => {
+                    format!("set_{}", field_caption)
+                }
             }
         }
     };
 
+    let is_field_accessor = match &kind {
+        NewFfiFunctionKind::Function { .. } => false,
+        NewFfiFunctionKind::FieldAccessor { .. } => true,
+    };
+    let path = CppPath::from_item(CppPathItem::from_good_str(&ascii_caption));
+    names.push(FfiNameRequest {
+        caption: ascii_caption,
+        signature,
+        is_field_accessor,
+    });
+
     let mut r = CppFfiFunction {
         arguments: Vec::new(),
         return_type: CppFfiType::void(),
-        path: name_provider.create_path(&ascii_caption),
+        path,
         allocation_place: ReturnValueAllocationPlace::NotApplicable,
         kind: match kind {
             NewFfiFunctionKind::Function { .. } => CppFfiFunctionKind::Function,
@@ -261,6 +1440,14 @@ pub fn to_ffi_method(
                 CppFfiFunctionKind::FieldAccessor { accessor_type }
             }
         },
+        is_thread_safe: false,
+        calling_convention: match &kind {
+            NewFfiFunctionKind::Function { cpp_function } => cpp_function.calling_convention,
+            NewFfiFunctionKind::FieldAccessor { .. } => None,
+        },
+        purity: None,
+        is_heap_variant,
+        is_noexcept: false,
     };
 
     let this_arg_type = match &kind {
@@ -281,7 +1468,10 @@ pub fn to_ffi_method(
                 let class_type = CppType::Class(field.path.parent()?);
                 let is_const = match *accessor_type {
                     CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => true,
-                    CppFieldAccessorType::MutRefGetter | CppFieldAccessorType::Setter => false,
+                    CppFieldAccessorType::ArrayPointerGetter => field.is_const,
+                    CppFieldAccessorType::MutRefGetter
+                    | CppFieldAccessorType::Setter
+                    | CppFieldAccessorType::ArrayElementSetter => false,
                 };
                 Some(CppType::new_pointer(is_const, class_type))
             }
@@ -298,19 +1488,12 @@ pub fn to_ffi_method(
 
     let normal_args = match &kind {
         NewFfiFunctionKind::Function { cpp_function, .. } => {
-            if cpp_function.allows_variadic_arguments {
-                bail!("Variable arguments are not supported");
-            }
-
             if cpp_function.is_destructor() {
                 // destructor doesn't have a return type that needs special handling,
-                // but its `allocation_place` must match `allocation_place` of the type's constructor
+                // but its `allocation_place` must match the way objects are allocated,
+                // which only depends on whether the type is movable
                 let class_type = &cpp_function.class_path().unwrap();
-                r.allocation_place = if movable_types.iter().any(|t| t == class_type) {
-                    ReturnValueAllocationPlace::Stack
-                } else {
-                    ReturnValueAllocationPlace::Heap
-                };
+                r.allocation_place = destructor_allocation_place(is_movable(class_type));
             }
             cpp_function.arguments.clone()
         }
@@ -318,21 +1501,53 @@ pub fn to_ffi_method(
             field,
             accessor_type,
         } => {
-            if accessor_type == &CppFieldAccessorType::Setter {
-                let arg = CppFunctionArgument {
-                    name: "value".to_string(),
-                    argument_type: field.field_type.clone(),
-                    has_default_value: false,
-                };
-                vec![arg]
-            } else {
-                Vec::new()
+            let value_arg = CppFunctionArgument {
+                name: "value".to_string(),
+                argument_type: field.field_type.clone(),
+                has_default_value: false,
+            };
+            match *accessor_type {
+                CppFieldAccessorType::Setter => vec![value_arg],
+                CppFieldAccessorType::ArrayElementSetter => {
+                    let index_arg = CppFunctionArgument {
+                        name: "index".to_string(),
+                        argument_type: CppType::PointerSizedInteger {
+                            path: CppPath::from_good_str("size_t"),
+                            is_signed: false,
+                        },
+                        has_default_value: false,
+                    };
+                    vec![index_arg, value_arg]
+                }
+                CppFieldAccessorType::CopyGetter
+                | CppFieldAccessorType::ConstRefGetter
+                | CppFieldAccessorType::MutRefGetter
+                | CppFieldAccessorType::ArrayPointerGetter => Vec::new(),
             }
         }
     };
 
+    let (is_constructor, is_move_constructor) = match &kind {
+        NewFfiFunctionKind::Function { cpp_function } => (
+            cpp_function.is_constructor(),
+            cpp_function.is_move_constructor(),
+        ),
+        NewFfiFunctionKind::FieldAccessor { .. } => (false, false),
+    };
     for (index, arg) in normal_args.iter().enumerate() {
-        let c_type = ffi_type(&arg.argument_type, CppTypeRole::NotReturnType)?;
+        let c_type = if is_move_constructor {
+            // the source object is moved into the constructed object
+            CppFfiType::new(
+                arg.argument_type.clone(),
+                CppToFfiTypeConversion::RValueReferenceToPointer,
+            )?
+        } else if is_constructor {
+            // objects passed by value are moved into the constructor's arguments,
+            // so they are passed through non-const pointers
+            to_ffi_type(&arg.argument_type, CppTypeRole::NotReturnType, Some(false))?
+        } else {
+            ffi_type(&arg.argument_type, CppTypeRole::NotReturnType)?
+        };
         r.arguments.push(CppFfiFunctionArgument {
             name: arg.name.clone(),
             argument_type: c_type,
@@ -358,38 +1573,149 @@ pub fn to_ffi_method(
             CppFieldAccessorType::MutRefGetter => {
                 CppType::new_reference(false, field.field_type.clone())
             }
-            CppFieldAccessorType::Setter => CppType::Void,
+            CppFieldAccessorType::ArrayPointerGetter => {
+                CppType::new_pointer(field.is_const, field.field_type.clone())
+            }
+            CppFieldAccessorType::Setter | CppFieldAccessorType::ArrayElementSetter => {
+                CppType::Void
+            }
         },
     };
-    let real_return_type_ffi = ffi_type(&real_return_type, CppTypeRole::ReturnType)?;
-    match &real_return_type {
-        // QFlags is converted to uint in FFI
-        CppType::Class(path) if !is_qflags(path) => {
-            if movable_types.iter().any(|t| t == path) {
-                r.arguments.push(CppFfiFunctionArgument {
-                    name: "output".to_string(),
-                    argument_type: real_return_type_ffi,
-                    meaning: CppFfiArgumentMeaning::ReturnValue,
-                });
-                r.allocation_place = ReturnValueAllocationPlace::Stack;
-            } else {
+    let is_three_way_comparison = match &kind {
+        NewFfiFunctionKind::Function { cpp_function, .. } => {
+            cpp_function.operator == Some(CppOperator::ThreeWayComparison)
+        }
+        NewFfiFunctionKind::FieldAccessor { .. } => false,
+    };
+    if let Some(value_type) = real_return_type.optional_value_type() {
+        match value_type {
+            CppType::BuiltInNumeric(_)
+            | CppType::SpecificNumeric(_)
+            | CppType::PointerSizedInteger { .. }
+            | CppType::Enum { .. } => {}
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Pointer,
+                ..
+            } => {}
+            // class types would need an additional output argument for the value,
+            // so they are not supported
+            _ => bail!("only std::optional of numbers, enums and pointers is supported"),
+        }
+        // the value is returned directly and its presence
+        // is written to an additional argument
+        r.return_type = CppFfiType::new(real_return_type, CppToFfiTypeConversion::OptionalToValue)?;
+        r.arguments.push(CppFfiFunctionArgument {
+            name: "has_value".to_string(),
+            argument_type: CppFfiType::new(
+                CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)),
+                CppToFfiTypeConversion::NoChange,
+            )?,
+            meaning: CppFfiArgumentMeaning::HasValue,
+        });
+    } else {
+        let real_return_type_ffi = if is_three_way_comparison {
+            // ordering types can't be passed through FFI,
+            // so the result is converted to int
+            CppFfiType::new(
+                real_return_type.clone(),
+                CppToFfiTypeConversion::OrderingToInt,
+            )?
+        } else {
+            ffi_type(&real_return_type, CppTypeRole::ReturnType)?
+        };
+        match &real_return_type {
+            // QFlags is converted to uint in FFI
+            CppType::Class(path) if !is_qflags(path) && !is_three_way_comparison => {
+                if is_movable(path) {
+                    r.arguments.push(CppFfiFunctionArgument {
+                        name: "output".to_string(),
+                        argument_type: real_return_type_ffi,
+                        meaning: CppFfiArgumentMeaning::ReturnValue,
+                    });
+                    r.allocation_place = ReturnValueAllocationPlace::Stack;
+                } else {
+                    r.return_type = real_return_type_ffi;
+                    r.allocation_place = ReturnValueAllocationPlace::Heap;
+                }
+            }
+            _ => {
                 r.return_type = real_return_type_ffi;
-                r.allocation_place = ReturnValueAllocationPlace::Heap;
             }
         }
-        _ => {
-            r.return_type = real_return_type_ffi;
-        }
+    }
+
+    if let NewFfiFunctionKind::Function { cpp_function } = &kind {
+        r.purity = ffi_function_purity(cpp_function.purity, &r);
+        r.is_noexcept = cpp_function.is_noexcept && !ffi_function_can_throw(&r);
     }
 
     Ok(r)
 }
 
+/// Returns the purity attribute that can be applied to the FFI wrapper `function`
+/// of a C++ function with `purity`. Wrappers that allocate the return value
+/// have side effects, so they can't have any purity attribute. Wrappers that
+/// dereference pointer arguments (e.g. `this`) can read memory, so they can only be pure.
+fn ffi_function_purity(
+    purity: Option<CppFunctionPurity>,
+    function: &CppFfiFunction,
+) -> Option<CppFunctionPurity> {
+    let purity = purity?;
+    if function.allocation_place != ReturnValueAllocationPlace::NotApplicable {
+        return None;
+    }
+    let has_pointer_arguments = function
+        .arguments
+        .iter()
+        .any(|arg| arg.argument_type.ffi_type().is_pointer());
+    if purity == CppFunctionPurity::Const && has_pointer_arguments {
+        Some(CppFunctionPurity::Pure)
+    } else {
+        Some(purity)
+    }
+}
+
+/// Returns true if the FFI wrapper `function` can throw an exception
+/// even if the original C++ function can't. Allocating the return value
+/// can throw `std::bad_alloc`, and copying or moving arguments and return values
+/// passed by value can throw from the constructors of their types.
+fn ffi_function_can_throw(function: &CppFfiFunction) -> bool {
+    let is_by_value = |t: &CppFfiType| match t.conversion() {
+        CppToFfiTypeConversion::ValueToPointer { .. }
+        | CppToFfiTypeConversion::RValueReferenceToPointer => true,
+        _ => false,
+    };
+    function.allocation_place != ReturnValueAllocationPlace::NotApplicable
+        || is_by_value(&function.return_type)
+        || function
+            .arguments
+            .iter()
+            .any(|arg| is_by_value(&arg.argument_type))
+}
+
 /// Adds fictional getter and setter methods for each known public field of each class.
-fn generate_field_accessors(
+/// Protected fields (if allowed by `check_preconditions`) are handled in the same way.
+/// Const fields only get a getter.
+///
+/// Arrays can't be copied or assigned, so array fields get a getter returning
+/// a pointer to the first element and a setter of an element at the specified index.
+/// The index is not checked.
+pub fn generate_field_accessors(
     field: &CppClassField,
     movable_types: &[CppPath],
     name_provider: &mut FfiNameProvider,
+) -> Result<Vec<CppFfiItem>> {
+    let mut names = Vec::new();
+    let items = generate_unnamed_field_accessors(field, movable_types, &mut names);
+    UnnamedFfiItems { names, items }.assign_names(name_provider)
+}
+
+/// Same as `generate_field_accessors`, but instead of assigning the names,
+/// adds the name requests to `names` and uses temporary paths.
+fn generate_unnamed_field_accessors(
+    field: &CppClassField,
+    movable_types: &[CppPath],
+    names: &mut Vec<FfiNameRequest>,
 ) -> Result<Vec<CppFfiItem>> {
     let mut new_methods = Vec::new();
     let mut create_method = |accessor_type| -> Result<CppFfiItem> {
@@ -397,39 +1723,72 @@ fn generate_field_accessors(
             field: field.clone(),
             accessor_type,
         };
-        let ffi_function = to_ffi_method(kind, movable_types, name_provider)?;
+        let ffi_function = to_unnamed_ffi_method(kind, movable_types, names, None)?;
         Ok(CppFfiItem::Function(ffi_function))
     };
 
-    if field.visibility == CppVisibility::Public {
+    if field.visibility == CppVisibility::Private {
+        return Ok(new_methods);
+    }
+    if field.array_size.is_some() {
+        new_methods.push(create_method(CppFieldAccessorType::ArrayPointerGetter)?);
+        if !field.is_const {
+            new_methods.push(create_method(CppFieldAccessorType::ArrayElementSetter)?);
+        }
+    } else {
         // Classes may be non-copyable, so copy getters may not be possible for them,
         // so we generate reference getters instead.
         if field.field_type.is_class() {
             new_methods.push(create_method(CppFieldAccessorType::ConstRefGetter)?);
-            new_methods.push(create_method(CppFieldAccessorType::MutRefGetter)?);
+            if !field.is_const {
+                new_methods.push(create_method(CppFieldAccessorType::MutRefGetter)?);
+            }
         } else {
             new_methods.push(create_method(CppFieldAccessorType::CopyGetter)?);
         }
-        new_methods.push(create_method(CppFieldAccessorType::Setter)?);
+        if !field.is_const {
+            new_methods.push(create_method(CppFieldAccessorType::Setter)?);
+        }
     }
 
     Ok(new_methods)
 }
 
-fn check_preconditions(item: &CppItem) -> Result<()> {
+/// Checks whether FFI wrappers can be generated for `item`.
+/// Protected member functions (including constructors, but not destructors) and
+/// protected fields are accepted only if enabled in `config`.
+fn check_preconditions(item: &CppItem, config: &Config) -> Result<()> {
     match item {
         CppItem::Function(function) => {
+            if function.is_deleted {
+                bail!("function is deleted");
+            }
             if let Some(membership) = &function.member {
                 if membership.visibility == CppVisibility::Private {
                     bail!("function is private");
                 }
                 if membership.visibility == CppVisibility::Protected {
-                    bail!("function is protected");
+                    if membership.kind.is_destructor() {
+                        bail!("protected destructors are not supported");
+                    }
+                    if !config.wrap_protected_functions() {
+                        bail!("function is protected");
+                    }
                 }
                 if membership.is_signal {
                     bail!("signals are excluded");
                 }
             }
+            if function.allows_variadic_arguments {
+                bail!("variadic functions are not supported");
+            }
+            if let Some(signature) = config
+                .denied_function_signatures()
+                .iter()
+                .find(|signature| function.matches_signature(signature))
+            {
+                bail!("signature {:?} is in the deny-list", signature);
+            }
             if let Some(args) = &function.path.last().template_arguments {
                 if args
                     .iter()
@@ -438,12 +1797,17 @@ fn check_preconditions(item: &CppItem) -> Result<()> {
                     bail!("template functions are excluded");
                 }
             }
+            if function.return_type.is_or_contains_template_parameter()
+                && function.has_deduced_return_type()
+            {
+                bail!("return type is an unresolved auto or decltype type");
+            }
         }
         CppItem::ClassField(field) => {
             if field.visibility == CppVisibility::Private {
                 bail!("field is private");
             }
-            if field.visibility == CppVisibility::Protected {
+            if field.visibility == CppVisibility::Protected && !config.wrap_protected_fields() {
                 bail!("field is protected");
             }
         }
@@ -11,6 +11,91 @@ use ritual_common::utils::MapIfOk;
 use serde_derive::{Deserialize, Serialize};
 use std::fmt::Write;
 
+/// Splits `tokens` of a function declaration into tokens of each argument.
+/// Returns `None` if the argument list is not found or not terminated.
+///
+/// `>>` closes two template argument lists. In default values, `<` is only
+/// treated as a bracket if it follows a name and has a matching `>` before
+/// the end of the argument list, so comparisons don't affect the parsing.
+fn argument_tokens<'a>(tokens: &[&'a str]) -> Option<Vec<Vec<&'a str>>> {
+    // find the opening parenthesis of the argument list,
+    // skipping template arguments of the return type
+    let mut angle_depth = 0;
+    let mut index = 0;
+    let start = loop {
+        match *tokens.get(index)? {
+            // the name of the operator may contain brackets
+            "operator" => match tokens.get(index + 1) {
+                Some(&"(") | Some(&"[") => index += 2,
+                _ => index += 1,
+            },
+            "<" => angle_depth += 1,
+            ">" => angle_depth -= 1,
+            ">>" => angle_depth -= 2,
+            "(" if angle_depth <= 0 => break index + 1,
+            _ => {}
+        }
+        index += 1;
+    };
+
+    let mut arguments = vec![Vec::new()];
+    let mut depth = 0;
+    let mut angle_depth = 0;
+    let mut is_default_value = false;
+    for (index, &token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return Some(arguments),
+            ")" | "]" | "}" => depth -= 1,
+            "<" if !is_default_value || is_template_argument_list(tokens, index) => {
+                angle_depth += 1;
+            }
+            ">" if angle_depth > 0 => angle_depth -= 1,
+            ">>" if angle_depth > 0 => angle_depth = (angle_depth - 2).max(0),
+            "=" if depth == 0 && angle_depth == 0 => is_default_value = true,
+            "," if depth == 0 && angle_depth == 0 => {
+                arguments.push(Vec::new());
+                is_default_value = false;
+                continue;
+            }
+            _ => {}
+        }
+        arguments.last_mut().unwrap().push(token);
+    }
+    None
+}
+
+/// Returns true if `<` at `index` in `tokens` opens a template argument list,
+/// i.e. it follows a name and is closed before the end of the enclosing brackets.
+fn is_template_argument_list(tokens: &[&str], index: usize) -> bool {
+    let follows_name = index > 0
+        && tokens[index - 1]
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_alphabetic() || c == '_');
+    if !follows_name {
+        return false;
+    }
+    let mut depth = 0;
+    let mut angle_depth = 0;
+    for &token in &tokens[index..] {
+        match token {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return false,
+            ")" | "]" | "}" => depth -= 1,
+            "<" if depth == 0 => angle_depth += 1,
+            ">" | ">>" if depth == 0 => {
+                angle_depth -= if token == ">" { 1 } else { 2 };
+                if angle_depth <= 0 {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 /// Information about an argument of a C++ method
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct CppFunctionArgument {
@@ -28,7 +113,9 @@ pub struct CppFunctionArgument {
 impl CppFunctionArgument {
     /// Generates C++ code for the argument declaration
     pub fn to_cpp_code(&self) -> Result<String> {
-        if let CppType::FunctionPointer(..) = self.argument_type {
+        if self.argument_type.is_function_pointer()
+            || self.argument_type.is_member_function_pointer()
+        {
             Ok(self.argument_type.to_cpp_code(Some(&self.name))?)
         } else {
             Ok(format!(
@@ -121,6 +208,69 @@ pub struct CppFunction {
     /// C++ code of the method's declaration.
     /// None if the method was not explicitly declared.
     pub declaration_code: Option<String>,
+    /// Non-default calling convention of the function.
+    /// None if the function uses the default calling convention.
+    pub calling_convention: Option<CppCallingConvention>,
+    /// `pure` or `const` attribute of the function.
+    /// None if the function doesn't have these attributes.
+    pub purity: Option<CppFunctionPurity>,
+    /// True if the function is declared `noexcept` (or `throw()`).
+    /// False if the function can throw or it's unknown.
+    #[serde(default)]
+    pub is_noexcept: bool,
+    /// True if the function is declared as deleted (`= delete`).
+    /// Deleted functions can't be called, so they are not wrapped.
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// Non-default calling convention of a C++ function
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub enum CppCallingConvention {
+    /// `__stdcall`
+    Stdcall,
+    /// `__fastcall`
+    Fastcall,
+}
+
+impl CppCallingConvention {
+    /// Returns the C++ keyword specifying this calling convention.
+    pub fn to_cpp_code(self) -> &'static str {
+        match self {
+            CppCallingConvention::Stdcall => "__stdcall",
+            CppCallingConvention::Fastcall => "__fastcall",
+        }
+    }
+
+    /// Returns the ABI string used in a Rust `extern` block
+    /// for functions with this calling convention.
+    pub fn rust_abi(self) -> &'static str {
+        match self {
+            CppCallingConvention::Stdcall => "stdcall",
+            CppCallingConvention::Fastcall => "fastcall",
+        }
+    }
+}
+
+/// Attribute indicating that a C++ function has no side effects
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub enum CppFunctionPurity {
+    /// `[[gnu::pure]]`: the return value depends only on the arguments
+    /// and the global memory
+    Pure,
+    /// `[[gnu::const]]`: the return value depends only on the arguments
+    Const,
+}
+
+impl CppFunctionPurity {
+    /// Returns the macro (defined in the global header of the C++ library)
+    /// that expands to this attribute on compilers that support it.
+    pub fn to_cpp_code(self) -> &'static str {
+        match self {
+            CppFunctionPurity::Pure => "RITUAL_PURE",
+            CppFunctionPurity::Const => "RITUAL_CONST",
+        }
+    }
 }
 
 /// Chosen type allocation place for the method
@@ -193,6 +343,47 @@ impl CppFunction {
             && self.argument_types_equal(other)
     }
 
+    /// Returns extent of the argument with `index` if it was declared
+    /// as a fixed-size array (e.g. `float out[16]`). Such arguments are
+    /// presented as pointers in the parsed types, so the extent is retrieved
+    /// from `declaration_code`.
+    pub fn fixed_array_argument_size(&self, index: usize) -> Option<usize> {
+        let code = self.declaration_code.as_ref()?;
+        let tokens = code.split_whitespace().collect_vec();
+        let arguments = argument_tokens(&tokens)?;
+        let argument = arguments.get(index)?;
+        let argument = match argument.iter().position(|&t| t == "=") {
+            Some(default_value_position) => &argument[..default_value_position],
+            None => &argument[..],
+        };
+        if argument.len() < 3 || argument[argument.len() - 3] != "[" {
+            return None;
+        }
+        if argument[argument.len() - 1] != "]" {
+            return None;
+        }
+        argument[argument.len() - 2].parse().ok()
+    }
+
+    /// Returns true if the return type of the function is declared
+    /// as `auto`, `decltype(...)` or a trailing return type (`auto f() -> T`),
+    /// according to `declaration_code`.
+    pub fn has_deduced_return_type(&self) -> bool {
+        let code = if let Some(code) = &self.declaration_code {
+            code
+        } else {
+            return false;
+        };
+        let tokens = code.split_whitespace().collect_vec();
+        let arguments_start = tokens
+            .iter()
+            .position(|&t| t == "(")
+            .unwrap_or_else(|| tokens.len());
+        tokens.contains(&"decltype")
+            || tokens.contains(&"->")
+            || tokens[..arguments_start].contains(&"auto")
+    }
+
     pub fn class_path(&self) -> Result<CppPath> {
         if self.member.is_some() {
             Ok(self.path.parent().with_context(|_| {
@@ -322,6 +513,31 @@ impl CppFunction {
         s.trim().to_string()
     }
 
+    /// Returns the signature of this function in a normalized form: the full path
+    /// (including template arguments), argument types and the `const` qualifier,
+    /// e.g. `QString::arg(int, int, QChar) const`. Argument names, default values
+    /// and the return type are not included.
+    pub fn normalized_signature(&self) -> String {
+        let is_const = self.member.as_ref().map_or(false, |info| info.is_const);
+        format!(
+            "{}({}){}",
+            self.path.to_cpp_pseudo_code(),
+            self.arguments
+                .iter()
+                .map(|arg| arg.argument_type.to_cpp_pseudo_code())
+                .join(", "),
+            if is_const { " const" } else { "" }
+        )
+    }
+
+    /// Returns true if `signature` is equal to the normalized signature
+    /// of this function (see `CppFunction::normalized_signature`).
+    /// Whitespace is ignored.
+    pub fn matches_signature(&self, signature: &str) -> bool {
+        let without_whitespace = |s: &str| s.split_whitespace().join("");
+        without_whitespace(signature) == without_whitespace(&self.normalized_signature())
+    }
+
     /// Returns true if this method is a constructor.
     pub fn is_constructor(&self) -> bool {
         match &self.member {
@@ -341,6 +557,22 @@ impl CppFunction {
         arg == self.arguments[0].argument_type
     }
 
+    /// Returns true if this method is a move constructor (`T(T&&)`).
+    pub fn is_move_constructor(&self) -> bool {
+        if !self.is_constructor() {
+            return false;
+        }
+        if self.arguments.len() != 1 {
+            return false;
+        }
+        let arg = CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::RValueReference,
+            is_const: false,
+            target: Box::new(CppType::Class(self.class_path().unwrap())),
+        };
+        arg == self.arguments[0].argument_type
+    }
+
     /// Returns true if this method is a destructor.
     pub fn is_destructor(&self) -> bool {
         match &self.member {
@@ -371,6 +603,13 @@ impl CppFunction {
         }
     }
 
+    pub fn is_protected(&self) -> bool {
+        match &self.member {
+            Some(info) => info.visibility == CppVisibility::Protected,
+            None => false,
+        }
+    }
+
     pub fn receiver_id_from_data<'a>(
         receiver_type: RustQtReceiverType,
         name: &'a str,
@@ -440,6 +679,30 @@ impl CppFunction {
         result
     }
 
+    /// Returns types of the operands of this operator: the class type
+    /// (for class member operators) followed by the argument types.
+    /// References are replaced by their target types.
+    /// Returns `None` if this function is not an operator.
+    pub fn operand_types(&self) -> Option<Vec<CppType>> {
+        self.operator.as_ref()?;
+        let mut result = Vec::new();
+        if self.member.is_some() {
+            result.push(CppType::Class(self.class_path().ok()?));
+        }
+        for arg in &self.arguments {
+            let operand_type = match &arg.argument_type {
+                CppType::PointerLike {
+                    kind: CppPointerLikeTypeKind::Reference,
+                    target,
+                    ..
+                } => (**target).clone(),
+                other => other.clone(),
+            };
+            result.push(operand_type);
+        }
+        Some(result)
+    }
+
     pub fn can_infer_template_arguments(&self) -> bool {
         if let Some(args) = &self.path.last().template_arguments {
             for t in args {
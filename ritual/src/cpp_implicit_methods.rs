@@ -45,6 +45,10 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 allows_variadic_arguments: false,
                 cast: None,
                 declaration_code: None,
+                calling_convention: None,
+                purity: None,
+                is_noexcept: false,
+                is_deleted: false,
             };
             methods.push(ItemWithSource::new(&type1.id, destructor));
 
@@ -68,6 +72,10 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 arguments: vec![],
                 allows_variadic_arguments: false,
                 declaration_code: None,
+                calling_convention: None,
+                purity: None,
+                is_noexcept: false,
+                is_deleted: false,
                 cast: None,
             };
             methods.push(ItemWithSource::new(&type1.id, default_constructor));
@@ -99,6 +107,10 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 allows_variadic_arguments: false,
                 cast: None,
                 declaration_code: None,
+                calling_convention: None,
+                purity: None,
+                is_noexcept: false,
+                is_deleted: false,
             };
             methods.push(ItemWithSource::new(&type1.id, copy_constructor));
 
@@ -123,6 +135,10 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 allows_variadic_arguments: false,
                 cast: None,
                 declaration_code: None,
+                calling_convention: None,
+                purity: None,
+                is_noexcept: false,
+                is_deleted: false,
             };
             methods.push(ItemWithSource::new(&type1.id, assignment_operator));
         }
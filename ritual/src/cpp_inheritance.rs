@@ -28,6 +28,22 @@ pub fn inherits(class_name: &CppPath, base_name: &CppPath, data: &ProcessorData<
     false
 }
 
+/// Returns true if the base class method `method` is hidden in the derived class
+/// because one of `derived_class_functions` (functions declared in the derived class)
+/// has the same name. In C++, declaring any overload of a name in a derived class
+/// hides all overloads of that name in the base class.
+///
+/// `using Base::name;` declarations are not parsed, so overloads re-exposed
+/// by them are also considered hidden.
+pub fn is_hidden_in_derived(
+    method: &CppFunction,
+    derived_class_functions: &[&CppFunction],
+) -> bool {
+    derived_class_functions
+        .iter()
+        .any(|function| function.path.last().name == method.path.last().name)
+}
+
 fn detect_inherited_methods2(data: &ProcessorData<'_>) -> Result<Vec<CppFunction>> {
     let mut remaining_classes = data
         .db
@@ -60,15 +76,23 @@ fn detect_inherited_methods2(data: &ProcessorData<'_>) -> Result<Vec<CppFunction
     let mut result = Vec::new();
     for class in ordered_classes {
         trace!("Detecting inherited methods for {:?}\n", class);
+        let derived_class_functions = data
+            .db
+            .all_cpp_items()
+            .filter_map(|item| item.item.as_function_ref())
+            .filter(|m| m.class_path_parts().ok() == Some(class.derived_class_type.items()))
+            .collect_vec();
         let methods = data
             .db
             .all_cpp_items()
             .filter_map(|item| item.item.as_function_ref())
-            .filter(|m| m.class_path_parts().ok() == Some(class.base_class_type.items()));
+            .filter(|m| m.class_path_parts().ok() == Some(class.base_class_type.items()))
+            .filter(|m| !m.is_constructor() && !m.is_destructor())
+            .filter(|m| !is_hidden_in_derived(m, &derived_class_functions));
 
         for method in methods {
             let mut new_method = (*method).clone();
-            new_method.path = class.base_class_type.join(method.path.last().clone());
+            new_method.path = class.derived_class_type.join(method.path.last().clone());
             new_method.declaration_code = None;
             //new_method.is_fake_inherited_method = true;
             trace!("Method added: {}", new_method.short_text());
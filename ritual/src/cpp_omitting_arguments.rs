@@ -1,7 +1,46 @@
 use crate::cpp_data::CppItem;
+use crate::cpp_function::CppFunction;
 use crate::database::ItemWithSource;
 use crate::processor::ProcessorData;
-use ritual_common::errors::Result;
+use log::debug;
+use ritual_common::errors::{bail, Result};
+
+/// Returns copies of `function` with the trailing arguments that have
+/// default values omitted, from the longest to the shortest argument list.
+///
+/// Returns an error if an argument with a default value is followed by
+/// an argument without one. Valid C++ declarations can't have such arguments,
+/// but merging multiple declarations of the same function can produce them,
+/// and omitting arguments from such a function would assign values
+/// to wrong arguments.
+pub fn omit_default_arguments(function: &CppFunction) -> Result<Vec<CppFunction>> {
+    let first_default = if let Some(index) = function
+        .arguments
+        .iter()
+        .position(|arg| arg.has_default_value)
+    {
+        index
+    } else {
+        return Ok(Vec::new());
+    };
+    if let Some(arg) = function.arguments[first_default..]
+        .iter()
+        .find(|arg| !arg.has_default_value)
+    {
+        bail!(
+            "argument without default value follows an argument with default value: {}",
+            arg.name
+        );
+    }
+
+    let mut results = Vec::new();
+    let mut function_copy = function.clone();
+    while function_copy.arguments.len() > first_default {
+        function_copy.arguments.pop();
+        results.push(function_copy.clone());
+    }
+    Ok(results)
+}
 
 pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     let mut results = Vec::new();
@@ -12,13 +51,18 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
             continue;
         };
 
-        if function.arguments.iter().any(|arg| arg.has_default_value) {
-            let mut function_copy = function.clone();
-            while let Some(arg) = function_copy.arguments.pop() {
-                if !arg.has_default_value {
-                    break;
+        match omit_default_arguments(function) {
+            Ok(functions) => {
+                for function in functions {
+                    results.push(ItemWithSource::new(&item.id, function));
                 }
-                results.push(ItemWithSource::new(&item.id, function_copy.clone()));
+            }
+            Err(err) => {
+                debug!(
+                    "failed to omit arguments of function: {}: {}",
+                    function.short_text(),
+                    err
+                );
             }
         }
     }
@@ -44,6 +44,8 @@ pub enum CppOperator {
     GreaterThanOrEqualTo,
     /// a <= b
     LessThanOrEqualTo,
+    /// a <=> b
+    ThreeWayComparison,
     /// !a
     LogicalNot,
     /// a && b
@@ -163,6 +165,7 @@ impl CppOperator {
             LessThan => oi("<", 2),
             GreaterThanOrEqualTo => oi(">=", 2),
             LessThanOrEqualTo => oi("<=", 2),
+            ThreeWayComparison => oi("<=>", 2),
             LogicalNot => oi("!", 1),
             LogicalAnd => oi("&&", 2),
             LogicalOr => oi("||", 2),
@@ -223,6 +226,7 @@ impl CppOperator {
             LessThan,
             GreaterThanOrEqualTo,
             LessThanOrEqualTo,
+            ThreeWayComparison,
             LogicalNot,
             LogicalAnd,
             LogicalOr,
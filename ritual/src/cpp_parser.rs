@@ -4,12 +4,14 @@ use crate::cpp_data::{
     CppPath, CppPathItem, CppTypeDeclaration, CppTypeDeclarationKind, CppVisibility,
 };
 use crate::cpp_function::{
-    CppFunction, CppFunctionArgument, CppFunctionKind, CppFunctionMemberData,
+    CppCallingConvention, CppFunction, CppFunctionArgument, CppFunctionKind, CppFunctionMemberData,
+    CppFunctionPurity,
 };
 use crate::cpp_operator::CppOperator;
 use crate::cpp_type::{
-    CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind, CppSpecificNumericType,
-    CppSpecificNumericTypeKind, CppTemplateParameter, CppType,
+    CppBuiltInNumericType, CppFunctionPointerType, CppMemberFunctionPointerType,
+    CppPointerLikeTypeKind, CppSpecificNumericType, CppSpecificNumericTypeKind,
+    CppTemplateParameter, CppType,
 };
 use crate::database::ItemId;
 use crate::processor::ProcessorData;
@@ -17,6 +19,7 @@ use clang::diagnostic::{Diagnostic, Severity};
 use clang::*;
 use itertools::Itertools;
 use log::{debug, trace, warn};
+use once_cell::sync::Lazy;
 use regex::Regex;
 use ritual_common::env_var_names;
 use ritual_common::errors::{bail, err_msg, format_err, print_trace, Result, ResultExt};
@@ -166,6 +169,12 @@ fn get_path_item(entity: Entity<'_>) -> Result<CppPathItem> {
     })
 }
 
+/// Checks if the type with `name` is an `auto` or `decltype(...)` type.
+fn is_deduced_type_name(name: &str) -> bool {
+    let name = name.trim_start_matches("const ");
+    name == "auto" || name.starts_with("decltype(")
+}
+
 /// Returns fully qualified name of `entity`.
 fn get_path(entity: Entity<'_>) -> Result<CppPath> {
     let mut current_entity = entity;
@@ -199,6 +208,71 @@ fn get_path(entity: Entity<'_>) -> Result<CppPath> {
     Ok(CppPath::from_items(parts))
 }
 
+/// Returns true if the class `entity` declares or inherits a virtual function.
+fn is_polymorphic_class(entity: Entity<'_>) -> bool {
+    entity
+        .get_children()
+        .into_iter()
+        .any(|child| match child.get_kind() {
+            EntityKind::Method | EntityKind::Destructor | EntityKind::ConversionFunction => {
+                child.is_virtual_method()
+            }
+            EntityKind::BaseSpecifier => child
+                .get_type()
+                .and_then(|t| t.get_declaration())
+                .and_then(|d| d.get_definition())
+                .map_or(false, is_polymorphic_class),
+            _ => false,
+        })
+}
+
+/// Returns the part of the function declaration `code` after the closing parenthesis
+/// of its parameter list, or the whole `code` if the parameter list can't be found.
+fn declaration_specifiers(code: &str) -> &str {
+    static OPERATOR: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\boperator\s*(\(\s*\)|[^\s\w(]+)").unwrap());
+
+    // skip the name of the operator because it may contain brackets
+    let start = OPERATOR.find(code).map_or(0, |m| m.end());
+    let mut angle_depth = 0;
+    let mut paren_depth = 0;
+    for (index, c) in code[start..].char_indices() {
+        match c {
+            '<' if paren_depth == 0 => angle_depth += 1,
+            '>' if paren_depth == 0 && angle_depth > 0 => angle_depth -= 1,
+            '(' if angle_depth == 0 => paren_depth += 1,
+            ')' if angle_depth == 0 && paren_depth > 0 => {
+                paren_depth -= 1;
+                if paren_depth == 0 {
+                    return &code[start + index + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+    code
+}
+
+/// Checks if the function declaration `code` has a non-throwing
+/// exception specification (`noexcept`, `noexcept(true)` or `throw()`).
+/// Conditional specifications like `noexcept(noexcept(T()))` are treated
+/// as potentially throwing. Only the specifiers following the parameter list
+/// are checked, so `noexcept` in argument types or default values is ignored.
+fn is_noexcept_declaration(code: &str) -> bool {
+    static NOEXCEPT: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\bnoexcept\b\s*(\(\s*true\s*\)|[^\s(]|$)").unwrap());
+    static THROW: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bthrow\s*\(\s*\)").unwrap());
+
+    let specifiers = declaration_specifiers(code);
+    NOEXCEPT.is_match(specifiers) || THROW.is_match(specifiers)
+}
+
+/// Checks if the function declaration `code` ends with `= delete`.
+fn is_deleted_declaration(code: &str) -> bool {
+    static DELETED: Lazy<Regex> = Lazy::new(|| Regex::new(r"=\s*delete\s*$").unwrap());
+    DELETED.is_match(code)
+}
+
 fn get_full_name_display(entity: Entity<'_>) -> String {
     match get_path(entity) {
         Ok(name) => name.to_cpp_pseudo_code(),
@@ -238,9 +312,7 @@ fn run_clang<R, F: FnMut(Entity<'_>) -> Result<R>>(
     let tmp_cpp_path = tmp_path.join("1.cpp");
     {
         let mut tmp_file = create_file(&tmp_cpp_path)?;
-        for directive in config.include_directives() {
-            writeln!(tmp_file, "#include \"{}\"", path_to_str(directive)?)?;
-        }
+        writeln!(tmp_file, "{}", config.include_directives_code()?)?;
         if let Some(cpp_code) = cpp_code {
             write!(tmp_file, "{}", cpp_code)?;
         }
@@ -324,7 +396,6 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
         None,
         |translation_unit| parser.parse(translation_unit),
     )?;
-
     Ok(())
 }
 
@@ -332,7 +403,18 @@ pub fn parse_generated_items(data: &mut ProcessorData<'_>) -> Result<()> {
     let current_target = LibraryTarget {
         cpp_library_version: data.config.cpp_lib_version().map(ToString::to_string),
         target: current_target(),
+        cpp_std_lib: None,
     };
+    // use the environment recorded by the C++ checker
+    // (it also contains the detected standard library)
+    let current_target = data
+        .db
+        .environments()
+        .iter()
+        .rev()
+        .find(|env| env.is_same_platform(&current_target))
+        .cloned()
+        .unwrap_or(current_target);
     for ffi_item_id in data.db.ffi_item_ids().collect_vec() {
         let ffi_item = data.db.ffi_item(&ffi_item_id)?;
         if !ffi_item.item.is_source_item() {
@@ -806,6 +888,48 @@ impl CppParser<'_, '_> {
                     None => bail!("can't get pointee type"),
                 }
             }
+            TypeKind::MemberPointer => {
+                let pointee = type1
+                    .get_pointee_type()
+                    .ok_or_else(|| err_msg("can't get pointee type"))?;
+                if pointee.get_canonical_type().get_kind() != TypeKind::FunctionPrototype {
+                    bail!("pointers to data members are not supported");
+                }
+                if pointee.get_ref_qualifier().is_some() {
+                    bail!("pointers to ref-qualified member functions are not supported");
+                }
+                let class_type = type1
+                    .get_class_type()
+                    .ok_or_else(|| err_msg("can't get class type of member pointer"))?;
+                let class_path = if let CppType::Class(path) =
+                    self.parse_type(class_type, context_template_args)?
+                {
+                    path
+                } else {
+                    bail!(
+                        "member pointer's class type is not a class: {:?}",
+                        class_type
+                    );
+                };
+                let function = if let CppType::FunctionPointer(function) =
+                    self.parse_type(pointee, context_template_args)?
+                {
+                    function
+                } else {
+                    bail!("failed to parse member function type: {:?}", pointee);
+                };
+                if function.allows_variadic_arguments {
+                    bail!("pointers to variadic member functions are not supported");
+                }
+                Ok(CppType::MemberFunctionPointer(
+                    CppMemberFunctionPointerType {
+                        class_path,
+                        is_const: pointee.get_display_name().trim_end().ends_with(" const"),
+                        return_type: function.return_type,
+                        arguments: function.arguments,
+                    },
+                ))
+            }
             TypeKind::Elaborated => {
                 self.parse_type(type1.get_canonical_type(), context_template_args)
             }
@@ -845,7 +969,19 @@ impl CppParser<'_, '_> {
                     parsed_canonical
                 }
             }
-            _ => bail!("Unsupported kind of type: {:?}", type1.get_kind()),
+            _ => {
+                let name = type1.get_display_name();
+                if is_deduced_type_name(&name) {
+                    // `auto` and `decltype(...)` types are usable
+                    // if the compiler was able to deduce them
+                    let canonical = type1.get_canonical_type();
+                    if canonical.get_kind() != type1.get_kind() {
+                        return self.parse_type(canonical, context_template_args);
+                    }
+                    bail!("unresolved auto or decltype type: {}", name);
+                }
+                bail!("Unsupported kind of type: {:?}", type1.get_kind())
+            }
         }
     }
 
@@ -908,7 +1044,7 @@ impl CppParser<'_, '_> {
                     kind: CppSpecificNumericTypeKind::Integer { is_signed: false },
                 }))
             }
-            "qintptr" | "qptrdiff" | "QList::difference_type" => {
+            "qintptr" | "qptrdiff" | "ptrdiff_t" | "std::ptrdiff_t" | "QList::difference_type" => {
                 Some(CppType::PointerSizedInteger {
                     path: CppPath::from_good_str(name),
                     is_signed: true,
@@ -966,6 +1102,13 @@ impl CppParser<'_, '_> {
             Ok(x) => x,
             Err(msg) => {
                 trace!("return type: {:?}", return_type);
+                if is_deduced_type_name(&return_type.get_display_name()) {
+                    bail!(
+                        "Unresolved auto or decltype return type: {}: {}",
+                        return_type.get_display_name(),
+                        msg
+                    );
+                }
                 bail!(
                     "Can't parse return type: {}: {}",
                     return_type.get_display_name(),
@@ -1185,6 +1328,35 @@ impl CppParser<'_, '_> {
             Some(token_strings.join(" "))
         };
 
+        // `__thiscall` is not recorded because FFI wrappers of methods are free functions
+        let calling_convention = match entity.get_type().and_then(|t| t.get_calling_convention()) {
+            Some(CallingConvention::Stdcall) => Some(CppCallingConvention::Stdcall),
+            Some(CallingConvention::Fastcall) => Some(CppCallingConvention::Fastcall),
+            _ => None,
+        };
+
+        let attributes = entity
+            .get_children()
+            .into_iter()
+            .map(|child| child.get_kind())
+            .collect_vec();
+        let purity = if attributes.contains(&EntityKind::ConstAttr) {
+            Some(CppFunctionPurity::Const)
+        } else if attributes.contains(&EntityKind::PureAttr) {
+            Some(CppFunctionPurity::Pure)
+        } else {
+            None
+        };
+
+        let is_noexcept = match &declaration_code {
+            Some(code) => is_noexcept_declaration(code),
+            None => false,
+        };
+        let is_deleted = match &declaration_code {
+            Some(code) => is_deleted_declaration(code),
+            None => false,
+        };
+
         let function = CppFunction {
             path: name_with_namespace,
             operator: method_operator,
@@ -1216,6 +1388,10 @@ impl CppParser<'_, '_> {
             return_type: return_type_parsed,
             cast: None,
             declaration_code,
+            calling_convention,
+            purity,
+            is_noexcept,
+            is_deleted,
         };
 
         self.add_output(
@@ -1275,9 +1451,20 @@ impl CppParser<'_, '_> {
         let field_name = entity
             .get_name()
             .ok_or_else(|| err_msg("failed to get field name"))?;
-        let field_clang_type = entity
+        let mut field_clang_type = entity
             .get_type()
             .ok_or_else(|| err_msg("failed to get field type"))?;
+        let mut array_size = None;
+        if field_clang_type.get_kind() == TypeKind::ConstantArray {
+            array_size = Some(
+                field_clang_type
+                    .get_size()
+                    .ok_or_else(|| err_msg("failed to get array size"))?,
+            );
+            field_clang_type = field_clang_type
+                .get_element_type()
+                .ok_or_else(|| err_msg("failed to get array element type"))?;
+        }
         let field_type = self
             .parse_type(field_clang_type, &get_context_template_args(entity))
             .with_context(|_| err_msg("failed to parse field type"))?;
@@ -1293,6 +1480,8 @@ impl CppParser<'_, '_> {
                     Accessibility::Private => CppVisibility::Private,
                 },
                 is_static: entity.get_kind() == EntityKind::VarDecl,
+                is_const: field_clang_type.is_const_qualified(),
+                array_size,
             }),
         )?;
 
@@ -1393,7 +1582,10 @@ impl CppParser<'_, '_> {
             include_file,
             get_origin_location(entity).unwrap(),
             CppItem::Type(CppTypeDeclaration {
-                kind: CppTypeDeclarationKind::Class,
+                kind: CppTypeDeclarationKind::Class {
+                    is_pod: entity.get_type().map_or(false, |t| t.is_pod()),
+                    is_polymorphic: is_polymorphic_class(entity),
+                },
                 path: full_name,
             }),
         )?;
@@ -100,6 +100,37 @@ pub fn instantiate_function(
     }
 }
 
+/// Instantiates `function` templated only on its return type
+/// (e.g. `template<class T> T get()`) with the explicit template argument `return_type`.
+/// Returns `Err` if the function's template parameter is not used
+/// in the return type or can be inferred from the arguments.
+pub fn instantiate_return_type(
+    function: &CppFunction,
+    return_type: &CppType,
+) -> Result<CppFunction> {
+    let template_arguments = function
+        .path
+        .last()
+        .template_arguments
+        .as_ref()
+        .ok_or_else(|| err_msg("function is not a template"))?;
+    if template_arguments.len() != 1 {
+        bail!("function must have exactly 1 template parameter");
+    }
+    let param = if let CppType::TemplateParameter(param) = &template_arguments[0] {
+        param
+    } else {
+        bail!("function is already instantiated");
+    };
+    if !function.return_type.contains_template_parameter(param) {
+        bail!("template parameter is not used in the return type");
+    }
+    if function.can_infer_template_arguments() {
+        bail!("template parameter can be inferred from the arguments");
+    }
+    instantiate_function(function, param.nested_level, &[return_type.clone()])
+}
+
 // TODO: instantiations of QObject::findChild and QObject::findChildren should be available
 
 #[derive(Debug)]
@@ -276,11 +307,70 @@ fn instantiate_functions(data: &mut ProcessorData<'_>) -> Result<()> {
     Ok(())
 }
 
+/// Adds instantiations of functions templated on their return type
+/// requested by `Config::add_return_type_template_instantiation`.
+fn instantiate_requested_return_types(data: &mut ProcessorData<'_>) -> Result<()> {
+    let mut new_methods = Vec::new();
+    for (path, return_type) in data.config.return_type_template_instantiations() {
+        for item in data.db.cpp_items() {
+            let function = if let Some(f) = item.item.as_function_ref() {
+                f
+            } else {
+                continue;
+            };
+            let mut templateless_path = function.path.clone();
+            templateless_path.last_mut().template_arguments = None;
+            if &templateless_path != path {
+                continue;
+            }
+            match instantiate_return_type(function, return_type) {
+                Ok(method) => {
+                    if let Err(err) = method
+                        .all_involved_types()
+                        .iter()
+                        .try_for_each(|type1| check_template_type(&data, type1))
+                    {
+                        debug!("method is not accepted: {}: {}", method.short_text(), err);
+                        continue;
+                    }
+                    if data
+                        .db
+                        .all_cpp_items()
+                        .filter_map(|item| item.item.as_function_ref())
+                        .any(|item| item.is_same(&method))
+                        || new_methods
+                            .iter()
+                            .any(|i: &ItemWithSource<CppFunction>| i.item.is_same(&method))
+                    {
+                        trace!("this method already exists");
+                    } else {
+                        new_methods.push(ItemWithSource::new(&item.id, method));
+                    }
+                }
+                Err(err) => debug!(
+                    "failed to instantiate {} with {}: {}",
+                    function.short_text(),
+                    return_type.to_cpp_pseudo_code(),
+                    err
+                ),
+            }
+        }
+    }
+    for new_method in new_methods {
+        data.db.add_cpp_item(
+            Some(new_method.source_id),
+            CppItem::Function(new_method.item),
+        )?;
+    }
+    Ok(())
+}
+
 /// Generates methods as template instantiations of
 /// methods of existing template classes and existing template methods.
 pub fn instantiate_templates(data: &mut ProcessorData<'_>) -> Result<()> {
     instantiate_types(data)?;
     instantiate_functions(data)?;
+    instantiate_requested_return_types(data)?;
     Ok(())
 }
 
@@ -58,6 +58,20 @@ pub struct CppFunctionPointerType {
     pub allows_variadic_arguments: bool,
 }
 
+/// Information about a C++ pointer to member function type,
+/// e.g. `int (QObject::*)(int) const`
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct CppMemberFunctionPointerType {
+    /// Class the function is a member of
+    pub class_path: CppPath,
+    /// Whether the function is a const member function
+    pub is_const: bool,
+    /// Return type of the function
+    pub return_type: Box<CppType>,
+    /// Arguments of the function
+    pub arguments: Vec<CppType>,
+}
+
 /// Information about a numeric C++ type that is
 /// guaranteed to be the same on all platforms,
 /// e.g. `uint32_t`.
@@ -115,6 +129,8 @@ pub enum CppType {
     TemplateParameter(CppTemplateParameter),
     /// Function pointer type
     FunctionPointer(CppFunctionPointerType),
+    /// Pointer to member function type
+    MemberFunctionPointer(CppMemberFunctionPointerType),
     PointerLike {
         kind: CppPointerLikeTypeKind,
         is_const: bool,
@@ -243,6 +259,73 @@ impl CppType {
         }
     }
 
+    /// Returns true if this is a pointer to member function.
+    pub fn is_member_function_pointer(&self) -> bool {
+        match self {
+            CppType::MemberFunctionPointer(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `T` if this is `std::optional<T>`.
+    pub fn optional_value_type(&self) -> Option<&CppType> {
+        if let CppType::Class(path) = self {
+            if path.to_templateless_string() == "std::optional" {
+                if let Some(args) = &path.last().template_arguments {
+                    if args.len() == 1 {
+                        return Some(&args[0]);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the alternative types if this is a `std::variant<...>` type.
+    pub fn variant_alternatives(&self) -> Option<&[CppType]> {
+        if let CppType::Class(path) = self {
+            if path.to_templateless_string() == "std::variant" {
+                if let Some(args) = &path.last().template_arguments {
+                    if !args.is_empty() {
+                        return Some(args);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns `T` if this is `std::initializer_list<T>` or a reference to it.
+    pub fn initializer_list_element_type(&self) -> Option<&CppType> {
+        match self {
+            CppType::Class(path) if path.to_templateless_string() == "std::initializer_list" => {
+                match &path.last().template_arguments {
+                    Some(args) if args.len() == 1 => Some(&args[0]),
+                    _ => None,
+                }
+            }
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                target,
+                ..
+            } => target.initializer_list_element_type(),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is `std::initializer_list<T>` or a reference to it.
+    pub fn is_initializer_list(&self) -> bool {
+        match self {
+            CppType::Class(path) => path.to_templateless_string() == "std::initializer_list",
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                target,
+                ..
+            } => target.is_initializer_list(),
+            _ => false,
+        }
+    }
+
     pub fn is_pointer(&self) -> bool {
         match self {
             CppType::PointerLike { kind, .. } => *kind == CppPointerLikeTypeKind::Pointer,
@@ -263,6 +346,14 @@ impl CppType {
                         .iter()
                         .any(CppType::is_or_contains_template_parameter)
             }
+            CppType::MemberFunctionPointer(type1) => {
+                CppType::Class(type1.class_path.clone()).is_or_contains_template_parameter()
+                    || type1.return_type.is_or_contains_template_parameter()
+                    || type1
+                        .arguments
+                        .iter()
+                        .any(CppType::is_or_contains_template_parameter)
+            }
             CppType::Class(path) => path.items().iter().any(|item| {
                 if let Some(template_arguments) = &item.template_arguments {
                     template_arguments
@@ -276,6 +367,38 @@ impl CppType {
         }
     }
 
+    /// Adds paths of all classes and enums used in this type
+    /// (including template arguments) to `output`.
+    pub fn collect_paths(&self, output: &mut Vec<CppPath>) {
+        match self {
+            CppType::Class(path) | CppType::Enum { path } => {
+                for item in path.items() {
+                    if let Some(args) = &item.template_arguments {
+                        for arg in args {
+                            arg.collect_paths(output);
+                        }
+                    }
+                }
+                output.push(path.clone());
+            }
+            CppType::FunctionPointer(type1) => {
+                type1.return_type.collect_paths(output);
+                for arg in &type1.arguments {
+                    arg.collect_paths(output);
+                }
+            }
+            CppType::MemberFunctionPointer(type1) => {
+                CppType::Class(type1.class_path.clone()).collect_paths(output);
+                type1.return_type.collect_paths(output);
+                for arg in &type1.arguments {
+                    arg.collect_paths(output);
+                }
+            }
+            CppType::PointerLike { target, .. } => target.collect_paths(output),
+            _ => {}
+        }
+    }
+
     pub fn contains_template_parameter(&self, param: &CppTemplateParameter) -> bool {
         match self {
             CppType::TemplateParameter(self_params) => {
@@ -289,6 +412,14 @@ impl CppType {
                         .iter()
                         .any(|t| t.contains_template_parameter(param))
             }
+            CppType::MemberFunctionPointer(type1) => {
+                CppType::Class(type1.class_path.clone()).contains_template_parameter(param)
+                    || type1.return_type.contains_template_parameter(param)
+                    || type1
+                        .arguments
+                        .iter()
+                        .any(|t| t.contains_template_parameter(param))
+            }
             CppType::Class(path) => path.items().iter().any(|item| {
                 if let Some(template_arguments) = &item.template_arguments {
                     template_arguments
@@ -303,8 +434,14 @@ impl CppType {
     }
 
     /// Returns C++ code representing this type.
+    ///
+    /// For function pointers and pointers to member functions, `function_pointer_inner_text`
+    /// is the declared name placed inside the type (use `Some("")` for the type itself).
     pub fn to_cpp_code(&self, function_pointer_inner_text: Option<&str>) -> Result<String> {
-        if !self.is_function_pointer() && function_pointer_inner_text.is_some() {
+        if !self.is_function_pointer()
+            && !self.is_member_function_pointer()
+            && function_pointer_inner_text.is_some()
+        {
             bail!("unexpected function_pointer_inner_text");
         }
         match self {
@@ -340,6 +477,29 @@ impl CppType {
                     bail!("function_pointer_inner_text argument is missing");
                 }
             }
+            CppType::MemberFunctionPointer(CppMemberFunctionPointerType {
+                class_path,
+                is_const,
+                return_type,
+                arguments,
+            }) => {
+                let mut arg_texts = Vec::new();
+                for arg in arguments {
+                    arg_texts.push(arg.to_cpp_code(None)?);
+                }
+                if let Some(function_pointer_inner_text) = function_pointer_inner_text {
+                    Ok(format!(
+                        "{} ({}::*{})({}){}",
+                        return_type.as_ref().to_cpp_code(None)?,
+                        class_path.to_cpp_code()?,
+                        function_pointer_inner_text,
+                        arg_texts.join(", "),
+                        if *is_const { " const" } else { "" }
+                    ))
+                } else {
+                    bail!("function_pointer_inner_text argument is missing");
+                }
+            }
             CppType::PointerLike {
                 kind,
                 is_const,
@@ -365,7 +525,7 @@ impl CppType {
                 return param.name.to_string();
             }
             CppType::Class(base) => return base.to_cpp_pseudo_code(),
-            CppType::FunctionPointer(..) => {
+            CppType::FunctionPointer(..) | CppType::MemberFunctionPointer(..) => {
                 return self
                     .to_cpp_code(Some(&"FN_PTR".to_string()))
                     .unwrap_or_else(|_| "[?]".to_string());
@@ -402,6 +562,9 @@ impl CppType {
             | CppType::Class(path) => path.ascii_caption(),
             CppType::TemplateParameter(param) => param.name.to_string(),
             CppType::FunctionPointer(_) => "fn".into(),
+            CppType::MemberFunctionPointer(type1) => {
+                format!("{}_member_fn", type1.class_path.ascii_caption())
+            }
             CppType::PointerLike {
                 kind,
                 is_const,
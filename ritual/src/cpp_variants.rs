@@ -0,0 +1,120 @@
+use crate::cpp_data::{CppItem, CppPath, CppPathItem, CppVisibility};
+use crate::cpp_function::{
+    CppFunction, CppFunctionArgument, CppFunctionKind, CppFunctionMemberData,
+};
+use crate::cpp_type::CppType;
+use crate::database::ItemWithSource;
+use crate::processor::ProcessorData;
+use ritual_common::errors::Result;
+
+/// Creates `std::size_t std::variant<...>::index() const` function.
+fn create_index_function(class_path: &CppPath) -> CppFunction {
+    CppFunction {
+        path: class_path.join(CppPathItem::from_good_str("index")),
+        member: Some(CppFunctionMemberData {
+            is_virtual: false,
+            is_pure_virtual: false,
+            is_const: true,
+            is_static: false,
+            visibility: CppVisibility::Public,
+            is_signal: false,
+            is_slot: false,
+            kind: CppFunctionKind::Regular,
+        }),
+        operator: None,
+        return_type: CppType::PointerSizedInteger {
+            path: CppPath::from_good_str("std::size_t"),
+            is_signed: false,
+        },
+        arguments: vec![],
+        allows_variadic_arguments: false,
+        cast: None,
+        declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_deleted: false,
+    }
+}
+
+/// Creates `const T* std::get_if<T>(const std::variant<...>*)` function
+/// that returns a pointer to the alternative `T` if it's active
+/// and a null pointer otherwise.
+fn create_get_if_function(class_path: &CppPath, alternative: &CppType) -> CppFunction {
+    CppFunction {
+        path: CppPath::from_good_str("std").join(CppPathItem {
+            name: "get_if".into(),
+            template_arguments: Some(vec![alternative.clone()]),
+        }),
+        member: None,
+        operator: None,
+        return_type: CppType::new_pointer(true, alternative.clone()),
+        arguments: vec![CppFunctionArgument {
+            name: "variant".to_string(),
+            argument_type: CppType::new_pointer(true, CppType::Class(class_path.clone())),
+            has_default_value: false,
+        }],
+        allows_variadic_arguments: false,
+        cast: None,
+        declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_deleted: false,
+    }
+}
+
+/// Returns functions for querying the active alternative (`index()`) and
+/// extracting each alternative (`std::get_if`) of the concrete `std::variant<...>`
+/// instantiation with `class_path`. Returns an empty list if `class_path` is not
+/// a concrete `std::variant` type.
+///
+/// `std::get_if` selects the alternative by type, so it's only added for
+/// alternatives that are present exactly once in the variant.
+pub fn variant_functions(class_path: &CppPath) -> Vec<CppFunction> {
+    let class_type = CppType::Class(class_path.clone());
+    let alternatives = if let Some(alternatives) = class_type.variant_alternatives() {
+        alternatives
+    } else {
+        return Vec::new();
+    };
+    if alternatives
+        .iter()
+        .any(|arg| arg.is_or_contains_template_parameter())
+    {
+        return Vec::new();
+    }
+
+    let mut functions = vec![create_index_function(class_path)];
+    for alternative in alternatives {
+        if alternatives.iter().filter(|t| t == &alternative).count() == 1 {
+            functions.push(create_get_if_function(class_path, alternative));
+        }
+    }
+    functions
+}
+
+/// Adds the functions returned by `variant_functions` for every concrete
+/// `std::variant<...>` instantiation. The Rust generator uses these functions
+/// to convert the variant to a Rust enum.
+pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
+    let mut functions = Vec::new();
+
+    let variants = data
+        .db
+        .cpp_items()
+        .filter_map(|item| item.filter_map(|item| item.as_type_ref()))
+        .filter(|item| item.item.kind.is_class());
+
+    for type1 in variants {
+        for function in variant_functions(&type1.item.path) {
+            functions.push(ItemWithSource::new(&type1.id, function));
+        }
+    }
+
+    for item in functions {
+        data.db
+            .add_cpp_item(Some(item.source_id), CppItem::Function(item.item))?;
+    }
+    Ok(())
+}
@@ -1,27 +1,46 @@
-use crate::config::CrateDependencySource;
+use crate::config::{Config, CrateDependencySource, CrateProperties};
 use crate::cpp_code_generator;
 use crate::cpp_code_generator::generate_cpp_type_size_requester;
 use crate::database::CRATE_DB_FILE_NAME;
 use crate::processor::ProcessorData;
+use crate::rust_benchmark_generator::{self, BENCHMARKS_FEATURE, BENCHMARKS_TARGET};
 use crate::rust_code_generator;
-use ritual_common::errors::Result;
+use log::warn;
+use ritual_common::errors::{Result, ResultExt};
 use ritual_common::file_utils::{
     copy_file, copy_recursively, crate_version, create_dir, create_dir_all, create_file,
-    diff_paths, path_to_str, read_dir, remove_dir_all, repo_dir_path, save_json, save_toml_table,
+    diff_paths, file_content_hash, load_json, path_to_str, read_dir, remove_dir, remove_dir_all,
+    remove_file, rename_or_move_dir, repo_dir_path, save_json, save_toml_table,
 };
 use ritual_common::toml;
-use ritual_common::utils::{run_command, MapIfOk};
+use ritual_common::utils::run_command;
 use ritual_common::BuildScriptData;
+use std::collections::BTreeMap;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
+/// Name of the file listing the files generated in the crate directory.
+/// When the crate is regenerated, only these files are removed,
+/// so files added to the crate manually are preserved.
+pub const GENERATED_FILES_FILE_NAME: &str = "ritual_generated_files.json";
+
 /// Merges `a` and `b` recursively. `b` take precedence over `a`.
-fn recursive_merge_toml(a: toml::Value, b: toml::Value) -> toml::Value {
+/// Arrays are concatenated. If `deduplicate_arrays` is true, values of `b` that are
+/// already present in the array (or occur earlier in `b`) are skipped.
+pub fn recursive_merge_toml(
+    a: toml::Value,
+    b: toml::Value,
+    deduplicate_arrays: bool,
+) -> toml::Value {
     if a.same_type(&b) {
         if let toml::Value::Array(mut a_array) = a {
-            if let toml::Value::Array(mut b_array) = b {
-                a_array.append(&mut b_array);
+            if let toml::Value::Array(b_array) = b {
+                for value in b_array {
+                    if !deduplicate_arrays || !a_array.contains(&value) {
+                        a_array.push(value);
+                    }
+                }
                 toml::Value::Array(a_array)
             } else {
                 unreachable!()
@@ -30,7 +49,10 @@ fn recursive_merge_toml(a: toml::Value, b: toml::Value) -> toml::Value {
             if let toml::Value::Table(b_table) = b {
                 for (key, value) in b_table {
                     if let Some(old_value) = a_table.remove(&key) {
-                        a_table.insert(key, recursive_merge_toml(old_value, value));
+                        a_table.insert(
+                            key,
+                            recursive_merge_toml(old_value, value, deduplicate_arrays),
+                        );
                     } else {
                         a_table.insert(key, value);
                     }
@@ -53,45 +75,46 @@ fn toml_table_with_single_item(key: &str, value: impl Into<toml::Value>) -> toml
     toml::Value::Table(table)
 }
 
-/// Generates `Cargo.toml` file and skeleton of the crate.
-/// If a crate template was supplied, files from it are
-/// copied to the output location.
-fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) -> Result<()> {
-    let template_build_rs_path =
-        data.config
-            .crate_template_path()
-            .as_ref()
-            .and_then(|crate_template_path| {
-                let template_build_rs_path = crate_template_path.join("build.rs");
-                if template_build_rs_path.exists() {
-                    Some(template_build_rs_path)
-                } else {
-                    None
-                }
-            });
-    let output_build_rs_path = output_path.join("build.rs");
-    if let Some(template_build_rs_path) = &template_build_rs_path {
-        copy_file(template_build_rs_path, output_build_rs_path)?;
-    } else {
-        let mut build_rs_file = create_file(&output_build_rs_path)?;
-        write!(
-            build_rs_file,
-            "{}",
-            include_str!("../templates/crate/build.rs")
-        )?;
-    }
+/// Returns `Cargo.toml` value of a dependency fetched from the git repository `url`
+/// at revision `rev`. Git dependencies don't need a version, so it's omitted.
+pub fn git_dependency_toml(url: &str, rev: &str) -> toml::Value {
+    let mut value = toml::value::Table::new();
+    value.insert("git".into(), toml::Value::String(url.into()));
+    value.insert("rev".into(), toml::Value::String(rev.into()));
+    value.into()
+}
 
+/// Returns auto-generated `[package]` section of `Cargo.toml`.
+pub fn package_table(properties: &CrateProperties) -> toml::value::Table {
     let mut package = toml::value::Table::new();
-    package.insert(
-        "name".into(),
-        toml::Value::String(data.config.crate_properties().name().into()),
-    );
+    package.insert("name".into(), toml::Value::String(properties.name().into()));
     package.insert(
         "version".into(),
-        toml::Value::String(data.config.crate_properties().version().into()),
+        toml::Value::String(properties.version().into()),
     );
     package.insert("build".into(), toml::Value::String("build.rs".into()));
-    package.insert("edition".into(), toml::Value::String("2018".into()));
+    package.insert(
+        "edition".into(),
+        toml::Value::String(properties.edition().into()),
+    );
+    if !properties.authors().is_empty() {
+        let authors = properties
+            .authors()
+            .iter()
+            .map(|author| toml::Value::String(author.clone()))
+            .collect::<Vec<_>>();
+        package.insert("authors".into(), toml::Value::Array(authors));
+    }
+    let optional_fields = [
+        ("license", properties.license()),
+        ("repository", properties.repository()),
+        ("description", properties.description()),
+    ];
+    for &(key, value) in &optional_fields {
+        if let Some(value) = value {
+            package.insert(key.into(), toml::Value::String(value.into()));
+        }
+    }
 
     let docs_rs_metadata = toml_table_with_single_item(
         "features",
@@ -101,6 +124,50 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
         "metadata".into(),
         toml_table_with_single_item("docs", toml_table_with_single_item("rs", docs_rs_metadata)),
     );
+    package
+}
+
+/// Returns `[lib]` section of `Cargo.toml` or `None` if no crate types are configured.
+pub fn lib_table(properties: &CrateProperties) -> Option<toml::Value> {
+    if properties.crate_types().is_empty() {
+        return None;
+    }
+    let crate_types = properties
+        .crate_types()
+        .iter()
+        .map(|crate_type| toml::Value::String(crate_type.clone()))
+        .collect::<Vec<_>>();
+    Some(toml_table_with_single_item("crate-type", crate_types))
+}
+
+/// Returns `Cargo.toml` value of the optional `criterion` dependency
+/// enabled by the benchmarks feature.
+pub fn criterion_dependency_toml() -> toml::Value {
+    let mut value = toml::value::Table::new();
+    value.insert("version".into(), toml::Value::String("0.3".into()));
+    value.insert("optional".into(), toml::Value::Boolean(true));
+    value.into()
+}
+
+/// Returns `[[bench]]` section of `Cargo.toml` for the generated benchmarks.
+/// The target is only built if the benchmarks feature is enabled.
+pub fn bench_table() -> toml::Value {
+    let mut bench = toml::value::Table::new();
+    bench.insert("name".into(), toml::Value::String(BENCHMARKS_TARGET.into()));
+    bench.insert("harness".into(), toml::Value::Boolean(false));
+    bench.insert(
+        "required-features".into(),
+        vec![toml::Value::String(BENCHMARKS_FEATURE.into())].into(),
+    );
+    bench.into()
+}
+
+/// Returns content of `Cargo.toml` file of the crate located at `output_path`.
+fn cargo_toml_data(data: &ProcessorData<'_>, output_path: &Path) -> Result<toml::Value> {
+    let mut package = package_table(data.config.crate_properties());
+    if template_file_path(data, "README.md").is_none() {
+        package.insert("readme".into(), toml::Value::String("README.md".into()));
+    }
 
     let add_dependency = |table: &mut toml::value::Table,
                           name: &str,
@@ -117,6 +184,10 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
                 let version = data.db.dependency_version(name)?;
                 (version.to_string(), Some(path))
             }
+            CrateDependencySource::Git { url, rev } => {
+                table.insert(name.into(), git_dependency_toml(url, rev));
+                return Ok(());
+            }
         };
 
         let value = if local_path.is_none() || !data.config.write_dependencies_local_paths() {
@@ -171,18 +242,116 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
     }
     let mut features = toml::value::Table::new();
     features.insert("ritual_rustdoc".into(), toml::value::Array::new().into());
+    for (feature, _) in data.config.feature_macros() {
+        features.insert(
+            feature.cargo_feature.clone(),
+            toml::value::Array::new().into(),
+        );
+    }
+
+    let mut benches = toml::value::Array::new();
+    if !data.config.benchmark_functions().is_empty() {
+        dependencies.insert("criterion".into(), criterion_dependency_toml());
+        features.insert(
+            BENCHMARKS_FEATURE.into(),
+            vec![toml::Value::String("criterion".into())].into(),
+        );
+        benches.push(bench_table());
+    }
 
     let mut table = toml::value::Table::new();
     table.insert("package".into(), package.into());
+    if let Some(lib) = lib_table(data.config.crate_properties()) {
+        table.insert("lib".into(), lib);
+    }
     table.insert("dependencies".into(), dependencies.into());
     table.insert("build-dependencies".into(), build_dependencies.into());
     table.insert("features".into(), features.into());
+    if !benches.is_empty() {
+        table.insert("bench".into(), benches.into());
+    }
 
-    let cargo_toml_data = recursive_merge_toml(
+    // custom fields may repeat values added by default (e.g. features)
+    Ok(recursive_merge_toml(
         toml::Value::Table(table),
         toml::Value::Table(data.config.crate_properties().custom_fields().clone()),
-    );
-    save_toml_table(output_path.join("Cargo.toml"), &cargo_toml_data)?;
+        true,
+    ))
+}
+
+/// Returns content of the generated `build.rs` used if the crate template
+/// doesn't contain it.
+fn default_build_rs(data: &ProcessorData<'_>) -> &'static str {
+    if data.config.pkg_config_module().is_some() {
+        include_str!("../templates/crate/build_pkg_config.rs")
+    } else {
+        include_str!("../templates/crate/build.rs")
+    }
+}
+
+/// Returns content of `README.md` generated for the crate with `properties`
+/// if the crate template doesn't contain it.
+pub fn readme(
+    properties: &CrateProperties,
+    cpp_lib_name: Option<&str>,
+    cpp_lib_version: Option<&str>,
+) -> String {
+    let cpp_library = match (cpp_lib_name, cpp_lib_version) {
+        (Some(name), Some(version)) => format!("{} {}", name, version),
+        (Some(name), None) => name.to_string(),
+        (None, Some(version)) => format!("a C++ library (version {})", version),
+        (None, None) => "a C++ library".to_string(),
+    };
+    format!(
+        include_str!("../templates/crate/README.md"),
+        crate_name = properties.name(),
+        crate_version = properties.version(),
+        cpp_library = cpp_library
+    )
+}
+
+fn default_readme(data: &ProcessorData<'_>) -> String {
+    readme(
+        data.config.crate_properties(),
+        data.config.cpp_lib_name(),
+        data.config.cpp_lib_version(),
+    )
+}
+
+/// Returns path to the file `name` in the crate template, if the template contains it.
+fn template_file_path(data: &ProcessorData<'_>, name: &str) -> Option<PathBuf> {
+    let path = data.config.crate_template_path()?.join(name);
+    if path.exists() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Generates `Cargo.toml` file and skeleton of the crate.
+/// If a crate template was supplied, files from it are
+/// copied to the output location.
+fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) -> Result<()> {
+    let output_build_rs_path = output_path.join("build.rs");
+    if let Some(template_build_rs_path) = template_file_path(data, "build.rs") {
+        copy_file(template_build_rs_path, output_build_rs_path)?;
+    } else {
+        let mut build_rs_file = create_file(&output_build_rs_path)?;
+        write!(build_rs_file, "{}", default_build_rs(data))?;
+    }
+
+    let output_readme_path = output_path.join("README.md");
+    if let Some(template_readme_path) = template_file_path(data, "README.md") {
+        copy_file(template_readme_path, output_readme_path)?;
+    } else {
+        let mut readme_file = create_file(&output_readme_path)?;
+        write!(readme_file, "{}", default_readme(data))?;
+    }
+
+    save_toml_table(
+        output_path.join("Cargo.toml"),
+        &cargo_toml_data(data, output_path)?,
+    )?;
 
     if let Some(template_path) = &data.config.crate_template_path() {
         for item in read_dir(template_path)? {
@@ -202,7 +371,8 @@ fn generate_c_lib_template(
     lib_name: &str,
     lib_path: &Path,
     global_header_name: &str,
-    include_directives: &[PathBuf],
+    cpp_file_names: &[String],
+    config: &Config,
 ) -> Result<()> {
     let name_upper = lib_name.to_uppercase();
     let cmakelists_path = lib_path.join("CMakeLists.txt");
@@ -212,12 +382,14 @@ fn generate_c_lib_template(
         cmakelists_file,
         include_str!("../templates/c_lib/CMakeLists.txt"),
         lib_name_lowercase = lib_name,
-        lib_name_uppercase = name_upper
+        lib_name_uppercase = name_upper,
+        sources = cpp_file_names.join(" "),
+        output_name = config.cpp_wrapper_lib_output_name().unwrap_or(lib_name),
+        lib_destination = config.cpp_wrapper_lib_install_dir(),
+        include_destination = config.cpp_wrapper_header_install_dir()
     )?;
 
-    let include_directives_code = include_directives
-        .map_if_ok(|d| -> Result<_> { Ok(format!("#include \"{}\"", path_to_str(d)?)) })?
-        .join("\n");
+    let include_directives_code = config.include_directives_code()?;
 
     let global_header_path = lib_path.join(&global_header_name);
     let mut global_header_file = create_file(&global_header_path)?;
@@ -229,17 +401,295 @@ fn generate_c_lib_template(
     Ok(())
 }
 
+/// Formats the Rust code of the crate at `output_path` with `rustfmt`.
+fn format_crate(config: &Config, output_path: &Path) -> Result<()> {
+    let rustfmt = config
+        .rustfmt_path()
+        .map_or_else(|| PathBuf::from("rustfmt"), PathBuf::clone);
+    let context = || {
+        format!(
+            "failed to format the generated crate with {:?}; \
+             make sure rustfmt is installed, set its path with `Config::set_rustfmt_path` \
+             or disable formatting with `Config::set_format_output(false)`",
+            rustfmt
+        )
+    };
+
+    // The crate is not a member of the workspace yet, so `cargo fmt` can't be used.
+    // `rustfmt` formats the modules declared in `lib.rs` as well.
+    let mut files = vec![
+        PathBuf::from("build.rs"),
+        Path::new("src").join("lib.rs"),
+        Path::new("src").join("ffi.in.rs"),
+    ];
+    let benches_path = Path::new("benches").join(format!("{}.rs", BENCHMARKS_TARGET));
+    if output_path.join(&benches_path).exists() {
+        files.push(benches_path);
+    }
+    run_command(
+        Command::new(&rustfmt)
+            .arg("--edition")
+            .arg(config.crate_properties().edition())
+            .args(&files)
+            .current_dir(output_path),
+    )
+    .with_context(|_| context())?;
+    Ok(())
+}
+
+/// Kind of a file system operation performed by the crate writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOperationKind {
+    /// A new file is created.
+    Create,
+    /// An existing file is replaced.
+    Overwrite,
+    /// A file or a directory is removed.
+    Delete,
+}
+
+/// A file system operation that `run` would perform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileOperation {
+    pub kind: FileOperationKind,
+    pub path: PathBuf,
+    /// Content of the file, if it's known before generating the crate.
+    pub content: Option<String>,
+}
+
+/// Adds an operation writing the file at `path` to `operations`. The operation is
+/// `Overwrite` if the file exists (and is not deleted by a previous operation)
+/// or a previous operation already wrote the file.
+fn add_file_operation(operations: &mut Vec<FileOperation>, path: PathBuf, content: Option<String>) {
+    let is_written = operations
+        .iter()
+        .any(|op| op.kind != FileOperationKind::Delete && op.path == path);
+    let is_deleted = operations
+        .iter()
+        .any(|op| op.kind == FileOperationKind::Delete && op.path == path);
+    let kind = if is_written || (path.exists() && !is_deleted) {
+        FileOperationKind::Overwrite
+    } else {
+        FileOperationKind::Create
+    };
+    operations.push(FileOperation {
+        kind,
+        path,
+        content,
+    });
+}
+
+/// Adds paths of all files in `source` to `output` as copied to `destination`.
+fn collect_copied_files(
+    source: &Path,
+    destination: &Path,
+    output: &mut Vec<PathBuf>,
+) -> Result<()> {
+    if source.is_dir() {
+        for item in read_dir(source)? {
+            let item = item?;
+            collect_copied_files(&item.path(), &destination.join(item.file_name()), output)?;
+        }
+    } else {
+        output.push(destination.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Returns paths of the files generated in `output_path` by the previous run
+/// (see `GENERATED_FILES_FILE_NAME`). Paths leading outside of `output_path` are ignored.
+fn previously_generated_files(output_path: &Path) -> Result<Vec<PathBuf>> {
+    let list_path = output_path.join(GENERATED_FILES_FILE_NAME);
+    if !list_path.exists() {
+        return Ok(Vec::new());
+    }
+    let paths: Vec<PathBuf> = load_json(list_path)?;
+    Ok(paths
+        .into_iter()
+        .filter(|path| {
+            path.components()
+                .all(|component| component != Component::ParentDir)
+                && path.is_relative()
+        })
+        .map(|path| output_path.join(path))
+        .collect())
+}
+
+/// Removes `path` and its parent directories up to `root` if they are empty.
+fn remove_empty_dirs(path: &Path, root: &Path) -> Result<()> {
+    for dir in path.ancestors() {
+        if dir == root || !dir.starts_with(root) || !dir.is_dir() {
+            break;
+        }
+        if read_dir(dir)?.next().is_some() {
+            break;
+        }
+        remove_dir(dir)?;
+    }
+    Ok(())
+}
+
+/// Returns the file system operations `run` would perform,
+/// without touching the file system. Directory creation is not included,
+/// and `rustfmt`, which reformats the generated Rust files in place,
+/// is not reported.
+///
+/// Files generated by the previous run are deleted, and other files in the crate
+/// directory are kept.
+pub fn plan(data: &ProcessorData<'_>) -> Result<Vec<FileOperation>> {
+    let crate_name = data.config.crate_properties().name();
+    let output_path = data.workspace.crate_path(crate_name);
+
+    let mut operations = Vec::new();
+    for path in previously_generated_files(&output_path)? {
+        if path.is_file() {
+            operations.push(FileOperation {
+                kind: FileOperationKind::Delete,
+                path,
+                content: None,
+            });
+        }
+    }
+    let mut add_file = |path: PathBuf, content: Option<String>| {
+        add_file_operation(&mut operations, path, content);
+    };
+
+    let build_rs_content = if template_file_path(data, "build.rs").is_some() {
+        None
+    } else {
+        Some(default_build_rs(data).to_string())
+    };
+    add_file(output_path.join("build.rs"), build_rs_content);
+    let readme_content = if template_file_path(data, "README.md").is_some() {
+        None
+    } else {
+        Some(default_readme(data))
+    };
+    add_file(output_path.join("README.md"), readme_content);
+    add_file(
+        output_path.join("Cargo.toml"),
+        Some(cargo_toml_data(data, &output_path)?.to_string()),
+    );
+    if let Some(template_path) = &data.config.crate_template_path() {
+        let mut paths = Vec::new();
+        collect_copied_files(template_path, &output_path, &mut paths)?;
+        for path in paths {
+            add_file(path, None);
+        }
+    }
+
+    let c_lib_path = output_path.join("c_lib");
+    let c_lib_name = format!("{}_c", crate_name);
+    add_file(c_lib_path.join("CMakeLists.txt"), None);
+    add_file(c_lib_path.join(format!("{}_global.h", c_lib_name)), None);
+    for name in cpp_code_generator::cpp_file_names(data.config.cpp_file_count()) {
+        add_file(c_lib_path.join(name), None);
+    }
+    add_file(c_lib_path.join(format!("{}.h", c_lib_name)), None);
+    add_file(c_lib_path.join("sized_types.cxx"), None);
+
+    for path in rust_code_generator::file_paths(&data.db, &output_path.join("src"))? {
+        add_file(path, None);
+    }
+    if !data.config.benchmark_functions().is_empty() {
+        add_file(
+            output_path
+                .join("benches")
+                .join(format!("{}.rs", BENCHMARKS_TARGET)),
+            None,
+        );
+    }
+    add_file(output_path.join("build_script_data.json"), None);
+    add_file(output_path.join(CRATE_DB_FILE_NAME), None);
+    add_file(output_path.join(GENERATED_FILES_FILE_NAME), None);
+
+    // the workspace is outside of the removed output directory
+    let workspace_cargo_toml_path = data.workspace.path().join("Cargo.toml");
+    operations.push(FileOperation {
+        kind: if workspace_cargo_toml_path.exists() {
+            FileOperationKind::Overwrite
+        } else {
+            FileOperationKind::Create
+        },
+        path: workspace_cargo_toml_path,
+        content: None,
+    });
+    Ok(operations)
+}
+
+/// Generates the crate. The crate is written to a temporary directory
+/// next to the crate directory, which replaces the crate directory
+/// only if generation succeeds, so a failed run doesn't leave a half-written crate.
+/// Files in the crate directory that were not generated by the previous run are kept.
 pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     let crate_name = data.config.crate_properties().name();
     let output_path = data.workspace.crate_path(crate_name);
+    let temp_path = output_path.with_file_name(format!(".{}.ritual_tmp", crate_name));
+    let old_path = output_path.with_file_name(format!(".{}.ritual_old", crate_name));
+    for path in &[&temp_path, &old_path] {
+        if path.exists() {
+            remove_dir_all(path)?;
+        }
+    }
 
-    if output_path.exists() {
-        remove_dir_all(&output_path)?;
+    let operations = plan(data)?;
+    if let Err(err) = write_crate(data, &output_path, &temp_path, &operations) {
+        if temp_path.exists() {
+            if let Err(remove_err) = remove_dir_all(&temp_path) {
+                warn!("failed to remove temporary directory: {}", remove_err);
+            }
+        }
+        return Err(err);
     }
 
-    create_dir(&output_path)?;
-    generate_crate_template(data, &output_path)?;
+    if output_path.exists() {
+        rename_or_move_dir(&output_path, &old_path)?;
+    }
+    if let Err(err) = rename_or_move_dir(&temp_path, &output_path) {
+        // restore the previous output
+        if old_path.exists() {
+            if output_path.exists() {
+                if let Err(remove_err) = remove_dir_all(&output_path) {
+                    warn!("failed to remove incomplete output: {}", remove_err);
+                }
+            }
+            if let Err(restore_err) = rename_or_move_dir(&old_path, &output_path) {
+                warn!("failed to restore previous output: {}", restore_err);
+            }
+        }
+        return Err(err);
+    }
+    if old_path.exists() {
+        remove_dir_all(&old_path)?;
+    }
     data.workspace.update_cargo_toml()?;
+    Ok(())
+}
+
+/// Writes the crate planned by `operations` (with paths in `final_path`)
+/// to `output_path`.
+fn write_crate(
+    data: &mut ProcessorData<'_>,
+    final_path: &Path,
+    output_path: &Path,
+    operations: &[FileOperation],
+) -> Result<()> {
+    let crate_name = data.config.crate_properties().name();
+    if final_path.exists() {
+        copy_recursively(&final_path.to_path_buf(), &output_path.to_path_buf())?;
+    }
+    for operation in operations {
+        if operation.kind == FileOperationKind::Delete {
+            let path = output_path.join(operation.path.strip_prefix(final_path)?);
+            remove_file(&path)?;
+            if let Some(parent) = path.parent() {
+                remove_empty_dirs(parent, output_path)?;
+            }
+        }
+    }
+
+    create_dir_all(output_path)?;
+    generate_crate_template(data, output_path)?;
 
     let c_lib_path = output_path.join("c_lib");
     if !c_lib_path.exists() {
@@ -247,48 +697,90 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     }
     let c_lib_name = format!("{}_c", data.config.crate_properties().name());
     let global_header_name = format!("{}_global.h", c_lib_name);
+    let cpp_file_names = cpp_code_generator::cpp_file_names(data.config.cpp_file_count());
     generate_c_lib_template(
         &c_lib_name,
         &c_lib_path,
         &global_header_name,
-        data.config.include_directives(),
+        &cpp_file_names,
+        data.config,
     )?;
 
-    cpp_code_generator::generate_cpp_file(
+    cpp_code_generator::generate_cpp_files(
         &data.db,
-        &c_lib_path.join("file1.cpp"),
+        &c_lib_path,
+        data.config.cpp_file_count(),
         &global_header_name,
+        data.config.feature_macros(),
+    )?;
+
+    cpp_code_generator::generate_c_header(
+        &data.db,
+        &c_lib_path.join(format!("{}.h", c_lib_name)),
+        data.config.feature_macros(),
     )?;
 
     let file = create_file(c_lib_path.join("sized_types.cxx"))?;
-    generate_cpp_type_size_requester(data.db, data.config.include_directives(), file)?;
+    generate_cpp_type_size_requester(data.db, &data.config.include_directives_code()?, file)?;
+
+    let mut generated_file_hashes = BTreeMap::new();
+    let c_lib_files = [
+        "CMakeLists.txt".to_string(),
+        global_header_name.clone(),
+        format!("{}.h", c_lib_name),
+        "sized_types.cxx".to_string(),
+    ];
+    for name in c_lib_files.iter().chain(&cpp_file_names) {
+        generated_file_hashes.insert(
+            format!("c_lib/{}", name),
+            file_content_hash(c_lib_path.join(name))?,
+        );
+    }
 
     rust_code_generator::generate(
         &data.db,
         &output_path.join("src"),
         data.config.crate_template_path().map(|s| s.join("src")),
+        data.config.callback_panic_policy(),
+        data.config.feature_macros(),
     )?;
 
-    // -p shouldn't be needed, it's a workaround for this bug on Windows:
-    // https://github.com/rust-lang/rustfmt/issues/2694
-    run_command(
-        Command::new("cargo")
-            .arg("fmt")
-            .arg(format!("-p{}", crate_name))
-            .current_dir(&output_path),
-    )?;
-    run_command(
-        Command::new("rustfmt")
-            .arg("src/ffi.in.rs")
-            .current_dir(&output_path),
-    )?;
+    if !data.config.benchmark_functions().is_empty() {
+        let benches_path = output_path.join("benches");
+        create_dir_all(&benches_path)?;
+        rust_benchmark_generator::generate(
+            &data.db,
+            &benches_path.join(format!("{}.rs", BENCHMARKS_TARGET)),
+            data.config.benchmark_functions(),
+            data.config.feature_macros(),
+        )?;
+    }
+
+    if data.config.format_output() {
+        format_crate(data.config, output_path)?;
+    }
 
     save_json(
         output_path.join("build_script_data.json"),
         &BuildScriptData {
             cpp_build_config: data.config.cpp_build_config().clone(),
-            cpp_wrapper_lib_name: c_lib_name,
+            cpp_wrapper_lib_name: data
+                .config
+                .cpp_wrapper_lib_output_name()
+                .unwrap_or(&c_lib_name)
+                .to_string(),
+            cpp_wrapper_lib_install_dir: Some(
+                data.config.cpp_wrapper_lib_install_dir().to_string(),
+            ),
             known_targets: data.db.environments().to_vec(),
+            feature_macros: data
+                .config
+                .feature_macros()
+                .iter()
+                .map(|(feature, _)| feature.clone())
+                .collect(),
+            pkg_config_module: data.config.pkg_config_module().map(String::from),
+            generated_file_hashes: Some(generated_file_hashes),
         },
         None,
     )?;
@@ -298,5 +790,19 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
         output_path.join(CRATE_DB_FILE_NAME),
     )?;
 
+    let mut generated_files = operations
+        .iter()
+        .filter(|operation| operation.kind != FileOperationKind::Delete)
+        .filter_map(|operation| operation.path.strip_prefix(final_path).ok())
+        .map(Path::to_path_buf)
+        .collect::<Vec<_>>();
+    generated_files.sort();
+    generated_files.dedup();
+    save_json(
+        output_path.join(GENERATED_FILES_FILE_NAME),
+        &generated_files,
+        None,
+    )?;
+
     Ok(())
 }
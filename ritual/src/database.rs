@@ -943,6 +943,15 @@ impl DatabaseClient {
         self.all_databases().flat_map(|d| d.db.ffi_items())
     }
 
+    /// Returns C++ items of the current database with `path`
+    /// (e.g. all overloads of a function).
+    pub fn cpp_items_with_path<'a>(
+        &'a self,
+        path: &'a CppPath,
+    ) -> impl Iterator<Item = DbItem<&CppItem>> + 'a {
+        self.current_database.filter_by_cpp_path(path)
+    }
+
     pub fn find_rust_items_for_cpp_path(
         &self,
         cpp_path: &CppPath,
@@ -3,7 +3,8 @@
 
 #![allow(dead_code)]
 
-use crate::cpp_ffi_data::{CppFfiFunctionKind, CppFieldAccessorType};
+use crate::cpp_code_generator::MAX_INITIALIZER_LIST_SIZE;
+use crate::cpp_ffi_data::{CppFfiFunctionKind, CppFieldAccessorType, CppToFfiTypeConversion};
 use crate::cpp_type::CppType;
 use crate::database::{DatabaseClient, DbItem, DocItem};
 use crate::rust_code_generator::rust_type_to_code;
@@ -11,6 +12,7 @@ use crate::rust_info::{
     RustEnumValue, RustFunction, RustFunctionKind, RustModule, RustModuleKind, RustQtReceiverType,
     RustSpecialModuleKind, RustStruct, RustStructKind, RustWrapperTypeKind,
 };
+use crate::rust_type::RustToFfiTypeConversion;
 use itertools::Itertools;
 use ritual_common::errors::{bail, err_msg, Result};
 use std::fmt::Write;
@@ -95,7 +97,12 @@ fn first_phrase(html: &str) -> &str {
 pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Result<String> {
     let mut output = String::new();
 
-    let doc_item = database.find_doc_for(&type1.id)?;
+    // these enums share the source with the class,
+    // so the class documentation is not used for them
+    let doc_item = match &type1.item.kind {
+        RustStructKind::ConstructorArgs(_) | RustStructKind::VariantEnum(_) => None,
+        _ => database.find_doc_for(&type1.id)?,
+    };
     if let Some(doc_item) = &doc_item {
         if !doc_item.item.html.is_empty() {
             writeln!(output, "{}\n", first_phrase(&doc_item.item.html))?;
@@ -115,14 +122,27 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
                 .to_cpp_pseudo_code();
 
             match kind {
-                RustWrapperTypeKind::EnumWrapper => {
+                RustWrapperTypeKind::EnumWrapper { is_flags } => {
                     writeln!(
                         output,
                         "C++ enum: {}.\n",
                         wrap_inline_cpp_code(&cpp_type_code)
                     )?;
+                    if *is_flags {
+                        writeln!(
+                            output,
+                            "Values of this enum are distinct bits that can be combined.\n"
+                        )?;
+                    }
+                    writeln!(
+                        output,
+                        "The value may not correspond to any of the associated constants \
+                         (e.g. if a newer version of the C++ library adds enumerators), \
+                         so `match` on it must include a wildcard arm.\n"
+                    )?;
                 }
-                RustWrapperTypeKind::ImmovableClassWrapper => {
+                RustWrapperTypeKind::ImmovableClassWrapper
+                | RustWrapperTypeKind::TransparentClassWrapper { .. } => {
                     writeln!(
                         output,
                         "C++ class: {}.\n",
@@ -240,6 +260,14 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
                 )?;
             }
 
+            if !wrapper.return_type.api_type().is_unit() {
+                writeln!(
+                    output,
+                    "The closure returns `{}`, which is returned from the slot.\n",
+                    rust_type_to_code(wrapper.return_type.api_type(), Some(database.crate_name()))
+                )?;
+            }
+
             writeln!(
                 output,
                 "Create an object using `new()` \
@@ -260,6 +288,50 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
         }
         // private struct, no doc needed
         RustStructKind::SizedType(_) => {}
+        RustStructKind::ConstructorArgs(args) => {
+            let cpp_item = database
+                .source_cpp_item(&type1.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            let cpp_type_code = cpp_item
+                .item
+                .path()
+                .ok_or_else(|| err_msg("cpp item expected to have path"))?
+                .to_cpp_pseudo_code();
+
+            writeln!(
+                output,
+                "Arguments of one of the constructors of C++ class: {}. \
+                 Each variant calls the corresponding constructor:\n",
+                wrap_inline_cpp_code(&cpp_type_code)
+            )?;
+            for variant in &args.variants {
+                writeln!(
+                    output,
+                    "- `{}` calls `{}`.",
+                    variant.name,
+                    variant.function_path.last()
+                )?;
+            }
+            writeln!(output)?;
+        }
+        RustStructKind::VariantEnum(data) => {
+            let cpp_item = database
+                .source_cpp_item(&type1.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            let cpp_type_code = cpp_item
+                .item
+                .path()
+                .ok_or_else(|| err_msg("cpp item expected to have path"))?
+                .to_cpp_pseudo_code();
+
+            writeln!(
+                output,
+                "Reference to the active alternative of C++ type: {}. \
+                 Use `{}::to_enum` to obtain it.\n",
+                wrap_inline_cpp_code(&cpp_type_code),
+                data.variant_type_path.last()
+            )?;
+        }
     };
 
     if let Some(doc_item) = doc_item {
@@ -313,6 +385,29 @@ fn format_doc_item(cpp_doc: &DocItem) -> String {
     output
 }
 
+/// Returns a sentence describing the arguments of `function` (except `self`).
+/// The C++ documentation is shared by all overloads, so the arguments
+/// are the only way to tell them apart.
+pub fn overload_arguments_doc(function: &RustFunction, crate_name: &str) -> String {
+    let arguments = function
+        .arguments
+        .iter()
+        .filter(|arg| arg.name != "self")
+        .map(|arg| {
+            format!(
+                "`{}: {}`",
+                arg.name,
+                rust_type_to_code(arg.argument_type.api_type(), Some(crate_name))
+            )
+        })
+        .join(", ");
+    if arguments.is_empty() {
+        "This overload takes no arguments.".to_string()
+    } else {
+        format!("This overload takes {}.", arguments)
+    }
+}
+
 pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient) -> Result<String> {
     let cpp_item = database
         .source_cpp_item(&function.id)?
@@ -363,7 +458,10 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
         return Ok(output);
     }
 
-    let doc_item = database.find_doc_for(&function.id)?;
+    let doc_item = match &function.item.kind {
+        RustFunctionKind::ConstructorDispatch(_) | RustFunctionKind::VariantToEnum(_) => None,
+        _ => database.find_doc_for(&function.id)?,
+    };
     if let Some(doc_item) = &doc_item {
         if !doc_item.item.html.is_empty() {
             writeln!(output, "{}\n", first_phrase(&doc_item.item.html))?;
@@ -385,11 +483,48 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
                         .item
                         .as_function_ref()
                         .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+                    let overloads_count = database
+                        .cpp_items_with_path(&cpp_item.path)
+                        .filter(|item| item.item.as_function_ref().is_some())
+                        .count();
+                    if overloads_count > 1 {
+                        writeln!(
+                            output,
+                            "{}\n",
+                            overload_arguments_doc(function.item, database.crate_name())
+                        )?;
+                    }
                     write!(
                         output,
                         "Calls C++ function: {}.\n\n",
                         wrap_inline_cpp_code(&cpp_item.short_text())
                     )?;
+                    for arg in &function.item.arguments {
+                        if arg.argument_type.conversion()
+                            == &RustToFfiTypeConversion::ClosureToCallback
+                        {
+                            write!(
+                                output,
+                                "The closure passed as `{}` is never freed, because \
+                                 the wrapper can't know when the C++ library stops using it. \
+                                 Each call leaks the closure, so avoid calling this function \
+                                 repeatedly (e.g. to register and unregister a callback).\n\n",
+                                arg.name
+                            )?;
+                        }
+                        if cpp_ffi_function.arguments[arg.ffi_index]
+                            .argument_type
+                            .conversion()
+                            == &CppToFfiTypeConversion::InitializerListToPointer
+                        {
+                            write!(
+                                output,
+                                "Panics if `{}` contains more than {} elements, because \
+                                 the C++ wrapper can't construct longer `std::initializer_list`s.\n\n",
+                                arg.name, MAX_INITIALIZER_LIST_SIZE
+                            )?;
+                        }
+                    }
 
                     // TODO: detect omitted arguments using source_id
                     /*if let Some(arguments_before_omitting) =
@@ -426,6 +561,21 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
                         CppFieldAccessorType::Setter => {
                             write!(output, "Sets the value of the {} field.", field_text)?;
                         }
+                        CppFieldAccessorType::ArrayPointerGetter => {
+                            write!(
+                                output,
+                                "Returns a pointer to the first element of the {} array field.",
+                                field_text
+                            )?;
+                        }
+                        CppFieldAccessorType::ArrayElementSetter => {
+                            write!(
+                                output,
+                                "Sets the value of the element at `index` of the {} array field. \
+                                 The index is not checked.",
+                                field_text
+                            )?;
+                        }
                     };
                 }
             }
@@ -447,10 +597,52 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
                 cpp_path = cpp_item.path.to_cpp_pseudo_code()
             )?;
         }
-        // FFI functions are private
-        RustFunctionKind::FfiFunction => {}
+        RustFunctionKind::RegistrationGuard(_) => {
+            let cpp_item = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            write!(
+                output,
+                "Calls C++ function: {} and returns a guard that \
+                 undoes the registration when dropped.\n\n",
+                wrap_inline_cpp_code(&cpp_item.short_text())
+            )?;
+        }
+        RustFunctionKind::ConstructorDispatch(data) => {
+            write!(
+                output,
+                "Calls the constructor of C++ class: {} selected by the variant of `args` \
+                 (see `{}`).\n\n",
+                wrap_inline_cpp_code(
+                    &cpp_item
+                        .item
+                        .path()
+                        .ok_or_else(|| err_msg("cpp item expected to have path"))?
+                        .to_cpp_pseudo_code()
+                ),
+                data.args_enum_path.last()
+            )?;
+        }
+        RustFunctionKind::VariantToEnum(data) => {
+            write!(
+                output,
+                "Returns a reference to the active alternative of the variant \
+                 as `{}`, or `None` if the variant is valueless by exception.\n\n",
+                data.enum_path.last()
+            )?;
+        }
+        // FFI functions are private, but their documentation
+        // still refers to the original C++ item
+        RustFunctionKind::FfiFunction => {
+            write!(
+                output,
+                "Wraps C++ item: {}.\n\n",
+                wrap_inline_cpp_code(&cpp_item.item.to_string())
+            )?;
+        }
     }
-    if let Some(doc_item) = database.find_doc_for(&function.id)? {
+    if let Some(doc_item) = doc_item {
         write!(output, "{}", format_doc_item(doc_item.item))?;
     }
     Ok(output)
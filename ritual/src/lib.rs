@@ -14,6 +14,7 @@ pub mod cpp_checker;
 mod cpp_checks;
 mod cpp_code_generator;
 pub mod cpp_data;
+pub mod cpp_enums;
 pub mod cpp_ffi_data;
 pub mod cpp_ffi_generator;
 pub mod cpp_function;
@@ -24,11 +25,13 @@ mod cpp_operator;
 pub mod cpp_parser;
 pub mod cpp_template_instantiator;
 pub mod cpp_type;
+mod cpp_variants;
 mod crate_writer;
 pub mod database;
 mod doc_formatter;
 mod download_db;
 pub mod processor;
+mod rust_benchmark_generator;
 mod rust_code_generator;
 mod rust_generator;
 pub mod rust_info;
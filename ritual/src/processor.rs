@@ -3,7 +3,7 @@ use crate::database::{DatabaseClient, ItemId};
 use crate::workspace::Workspace;
 use crate::{
     cpp_casts, cpp_checker, cpp_ffi_generator, cpp_implicit_methods, cpp_omitting_arguments,
-    cpp_parser, cpp_template_instantiator, crate_writer, rust_generator,
+    cpp_parser, cpp_template_instantiator, cpp_variants, crate_writer, rust_generator,
 };
 use itertools::Itertools;
 use log::{error, info, trace};
@@ -110,6 +110,7 @@ impl Default for ProcessingSteps {
                 cpp_omitting_arguments::run,
             );
             s.push(&format!("cpp_casts{}", suffix), cpp_casts::run);
+            s.push(&format!("cpp_variants{}", suffix), cpp_variants::run);
             s.push(
                 &format!("cpp_ffi_generator{}", suffix),
                 cpp_ffi_generator::run,
@@ -138,6 +139,8 @@ impl Default for ProcessingSteps {
             Ok(())
         });
         s.add_custom("show_non_portable", show_non_portable);
+        s.add_custom("plan_crate_writer", plan_crate_writer);
+        s.add_custom("validate_config", validate_config);
 
         //        s.add_custom(
         //            "suggest_allocation_places",
@@ -258,6 +261,33 @@ fn show_non_portable(data: &mut ProcessorData<'_>) -> Result<()> {
     Ok(())
 }
 
+/// Reports the file operations the `crate_writer` step would perform
+/// without changing any files.
+fn plan_crate_writer(data: &mut ProcessorData<'_>) -> Result<()> {
+    for operation in crate_writer::plan(data)? {
+        info!("{:?}: {}", operation.kind, operation.path.display());
+        if let Some(content) = &operation.content {
+            trace!("{}", content);
+        }
+    }
+    Ok(())
+}
+
+fn validate_config(data: &mut ProcessorData<'_>) -> Result<()> {
+    let unresolved = data.config.unresolved_paths(data.db);
+    if unresolved.is_empty() {
+        info!("all C++ paths in the config are resolved");
+    }
+    for item in unresolved {
+        error!(
+            "unresolved C++ path in Config::{}: {}",
+            item.setting,
+            item.path.to_cpp_pseudo_code()
+        );
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 struct MainItemRef<'a> {
     step: &'a ProcessingStep,
@@ -0,0 +1,182 @@
+//! Generator of Criterion benchmarks for the generated Rust API.
+
+use crate::cpp_checks::{item_feature_condition, Condition};
+use crate::cpp_data::{CppItem, CppPath};
+use crate::database::{DatabaseClient, DbItem};
+use crate::rust_info::{RustFunction, RustFunctionKind, RustFunctionSelfArgKind};
+use crate::rust_type::{RustPath, RustToFfiTypeConversion, RustType};
+use log::debug;
+use ritual_common::errors::{bail, err_msg, format_err, Result};
+use ritual_common::file_utils::create_file;
+use ritual_common::FeatureMacro;
+use std::io::Write;
+use std::path::Path;
+
+/// Name of the cargo feature that enables the benchmarks.
+pub const BENCHMARKS_FEATURE: &str = "ritual_benchmarks";
+
+/// Name of the benchmark target in the output crate.
+pub const BENCHMARKS_TARGET: &str = "ritual_benchmarks";
+
+/// Returns an expression producing a value of `rust_type` that can be passed
+/// to a wrapper in a benchmark.
+fn default_argument_value(
+    rust_type: &RustType,
+    conversion: &RustToFfiTypeConversion,
+) -> Result<String> {
+    match conversion {
+        RustToFfiTypeConversion::None => {
+            if let RustType::Primitive(_) = rust_type {
+                Ok("Default::default()".to_string())
+            } else {
+                bail!("unsupported argument type: {:?}", rust_type);
+            }
+        }
+        RustToFfiTypeConversion::QFlagsToUInt { .. }
+        | RustToFfiTypeConversion::DurationToTicks { .. } => Ok("Default::default()".to_string()),
+        _ => bail!("unsupported argument type: {:?}", rust_type),
+    }
+}
+
+/// Returns an expression constructing the object the method is called on.
+fn default_object(db: &DatabaseClient, struct_path: &RustPath) -> Result<String> {
+    let constructor = db
+        .find_rust_item(&struct_path.join("new"))
+        .and_then(|item| item.item.as_function_ref())
+        .ok_or_else(|| format_err!("no `new` function for {}", struct_path.full_name(None)))?;
+    if !constructor.arguments.is_empty() {
+        bail!(
+            "`new` function for {} has arguments",
+            struct_path.full_name(None)
+        );
+    }
+    Ok(format!("{}()", constructor.path.full_name(None)))
+}
+
+/// Generates the benchmark for the wrapper `item` of the C++ function `cpp_item`.
+fn generate_benchmark(
+    db: &DatabaseClient,
+    item: DbItem<&RustFunction>,
+    cpp_item: &CppItem,
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+) -> Result<String> {
+    let function = item.item;
+    if !function.is_public {
+        bail!("function is not public");
+    }
+    let ffi_item = db
+        .source_ffi_item(&item.id)?
+        .ok_or_else(|| err_msg("source ffi item not found"))?;
+    if !db.cpp_checks(&ffi_item.id)?.all_success(db.environments()) {
+        bail!("function is not available in all environments");
+    }
+    if item_feature_condition(cpp_item, feature_macros) != Condition::True {
+        bail!("function depends on a cargo feature");
+    }
+
+    let mut setup = None;
+    let mut arguments = Vec::new();
+    for arg in &function.arguments {
+        if arg.name == "self" {
+            let is_mut = match function.self_arg_kind()? {
+                RustFunctionSelfArgKind::ConstRef => false,
+                RustFunctionSelfArgKind::MutRef => true,
+                _ => bail!("unsupported self argument"),
+            };
+            let object = default_object(db, &function.path.parent()?)?;
+            setup = Some(format!(
+                "let {}object = unsafe {{ {} }};\n",
+                if is_mut { "mut " } else { "" },
+                object
+            ));
+        } else {
+            arguments.push(default_argument_value(
+                arg.argument_type.api_type(),
+                arg.argument_type.conversion(),
+            )?);
+        }
+    }
+    let call = if setup.is_some() {
+        format!("object.{}({})", function.path.last(), arguments.join(", "))
+    } else {
+        format!(
+            "{}({})",
+            function.path.full_name(None),
+            arguments.join(", ")
+        )
+    };
+    let name = function.path.parts[1..].join("::");
+    Ok(format!(
+        "    c.bench_function({:?}, |b| {{\n{}b.iter(|| unsafe {{ black_box({}) }})\n}});\n",
+        name,
+        setup.unwrap_or_default(),
+        call,
+    ))
+}
+
+/// Generates a Criterion benchmark for each public wrapper
+/// of a C++ function listed in `functions`. The benchmark calls the wrapper
+/// with default argument values and measures the overhead of the wrapper,
+/// including argument and return value conversions.
+///
+/// Only functions with primitive, `QFlags` and `Duration` arguments
+/// are supported, and methods can only be benchmarked if their class has
+/// a Rust constructor without arguments. Unsupported functions are skipped.
+pub fn generate(
+    db: &DatabaseClient,
+    output_path: &Path,
+    functions: &[CppPath],
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
+) -> Result<()> {
+    let mut benchmarks = Vec::new();
+    for item in db.rust_items() {
+        let function = if let Some(function) = item.filter_map(|item| item.as_function_ref()) {
+            function
+        } else {
+            continue;
+        };
+        if let RustFunctionKind::FfiWrapper(_) = function.item.kind {
+        } else {
+            continue;
+        }
+        let cpp_item = if let Some(cpp_item) = db.original_cpp_item(&function.id)? {
+            cpp_item
+        } else {
+            continue;
+        };
+        let cpp_function = if let Some(cpp_function) = cpp_item.item.as_function_ref() {
+            cpp_function
+        } else {
+            continue;
+        };
+        if !functions.contains(&cpp_function.path) {
+            continue;
+        }
+        let path = function.item.path.full_name(None);
+        match generate_benchmark(db, function, cpp_item.item, feature_macros) {
+            Ok(code) => benchmarks.push(code),
+            Err(err) => debug!("skipping benchmark for {}: {}", path, err),
+        }
+    }
+
+    let mut file = create_file(output_path)?;
+    writeln!(
+        file,
+        "//! Benchmarks of the generated wrappers. Run with `cargo bench --features {}`.",
+        BENCHMARKS_FEATURE
+    )?;
+    writeln!(file, "#![allow(unused_unsafe, unused_variables)]")?;
+    writeln!(file)?;
+    writeln!(
+        file,
+        "use criterion::{{black_box, criterion_group, criterion_main, Criterion}};"
+    )?;
+    writeln!(file)?;
+    writeln!(file, "fn benchmarks(c: &mut Criterion) {{")?;
+    writeln!(file, "{}", benchmarks.join(""))?;
+    writeln!(file, "}}")?;
+    writeln!(file)?;
+    writeln!(file, "criterion_group!(benches, benchmarks);")?;
+    writeln!(file, "criterion_main!(benches);")?;
+    Ok(())
+}
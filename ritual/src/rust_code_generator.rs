@@ -1,14 +1,20 @@
 //! Types and functions used for Rust code generation.
 
-use crate::cpp_checks::Condition;
-use crate::cpp_ffi_data::CppFfiArgumentMeaning;
+use crate::config::CallbackPanicPolicy;
+use crate::cpp_checks::{item_feature_condition, Condition};
+use crate::cpp_code_generator::{
+    generator_version_function_name, GENERATOR_VERSION, MAX_INITIALIZER_LIST_SIZE,
+};
+use crate::cpp_data::CppPath;
+use crate::cpp_ffi_data::{CppFfiArgumentMeaning, CppToFfiTypeConversion};
 use crate::database::{DatabaseClient, DbItem, ItemId};
 use crate::doc_formatter;
 use crate::rust_generator::qt_core_path;
 use crate::rust_info::{
-    RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData, RustFunction,
-    RustFunctionArgument, RustFunctionKind, RustItem, RustModule, RustModuleKind,
-    RustSpecialModuleKind, RustStruct, RustStructKind, RustTraitImpl, RustWrapperTypeKind,
+    RustConstructorDispatchData, RustEnumValue, RustExtraImpl, RustExtraImplKind,
+    RustFfiWrapperData, RustFunction, RustFunctionArgument, RustFunctionKind, RustItem, RustModule,
+    RustModuleKind, RustRegistrationGuardData, RustSpecialModuleKind, RustStruct, RustStructKind,
+    RustTraitImpl, RustVariantToEnumData, RustWrapperTypeKind,
 };
 use crate::rust_type::{
     RustCommonType, RustFinalType, RustPath, RustPointerLikeTypeKind, RustToFfiTypeConversion,
@@ -19,6 +25,7 @@ use ritual_common::errors::{bail, err_msg, format_err, Result};
 use ritual_common::file_utils::{create_dir_all, create_file, file_to_string, File};
 use ritual_common::string_utils::trim_slice;
 use ritual_common::utils::MapIfOk;
+use ritual_common::FeatureMacro;
 use std::fmt::Write as _;
 use std::fs;
 use std::io::{self, BufWriter, Write};
@@ -48,6 +55,49 @@ pub fn rust_common_type_to_code(rust_type: &RustCommonType, current_crate: Optio
     code
 }
 
+/// Generates an expression that performs `call` of a Rust closure from an `extern "C"`
+/// callback and evaluates to its result. A panic is handled according to `policy`,
+/// so that it doesn't unwind into C++.
+pub fn callback_call_code(call: &str, policy: CallbackPanicPolicy) -> String {
+    let on_panic = match policy {
+        CallbackPanicPolicy::Abort => "::std::process::abort()".to_string(),
+        CallbackPanicPolicy::LogAndSwallow => format!(
+            "{{ eprintln!(\"{}\"); Default::default() }}",
+            "panic in a callback called from C++ was ignored"
+        ),
+    };
+    format!(
+        "match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {})) {{\n\
+         Ok(value) => value,\n\
+         Err(_) => {},\n\
+         }}",
+        call, on_panic
+    )
+}
+
+/// Generates Rust code for the `FnMut` trait of a closure with
+/// `arguments` and `return_type` (e.g. `FnMut(i32) -> bool + Send`).
+/// The C++ library may call the callback from any thread,
+/// so the closure must be `Send`.
+fn closure_trait_to_code(
+    return_type: &RustType,
+    arguments: &[RustType],
+    current_crate: Option<&str>,
+) -> String {
+    format!(
+        "FnMut({}){} + Send",
+        arguments
+            .iter()
+            .map(|arg| rust_type_to_code(arg, current_crate))
+            .join(", "),
+        if return_type.is_unit() {
+            String::new()
+        } else {
+            format!(" -> {}", rust_type_to_code(return_type, current_crate))
+        }
+    )
+}
+
 /// Generates Rust code representing type `rust_type` inside crate `crate_name`.
 /// Same as `RustCodeGenerator::rust_type_to_code`, but accessible by other modules.
 pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> String {
@@ -107,6 +157,17 @@ pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> S
             "impl {}",
             rust_common_type_to_code(trait_type, current_crate)
         ),
+        RustType::Closure {
+            return_type,
+            arguments,
+        } => format!(
+            "impl {} + 'static",
+            closure_trait_to_code(return_type, arguments, current_crate)
+        ),
+        RustType::Array { element, size } => {
+            format!("[{}; {}]", rust_type_to_code(element, current_crate), size)
+        }
+        RustType::Slice { element } => format!("[{}]", rust_type_to_code(element, current_crate)),
     }
 }
 
@@ -115,6 +176,8 @@ struct Generator<'a> {
     crate_template_src_path: Option<PathBuf>,
     destination: Vec<File<BufWriter<fs::File>>>,
     current_database: &'a DatabaseClient,
+    callback_panic_policy: CallbackPanicPolicy,
+    feature_macros: &'a [(FeatureMacro, Vec<CppPath>)],
 }
 
 impl Write for Generator<'_> {
@@ -182,6 +245,7 @@ fn condition_expression(condition: &Condition) -> String {
         Condition::Env(_) => unimplemented!(),
         Condition::PointerWidth(_) => unimplemented!(),
         Condition::Endian(_) => unimplemented!(),
+        Condition::Feature(feature) => format!("feature = {:?}", feature.cargo_feature),
         Condition::And(conditions) => {
             let list = conditions.iter().map(condition_expression).join(", ");
             format!("all({})", list)
@@ -196,6 +260,44 @@ fn condition_expression(condition: &Condition) -> String {
     }
 }
 
+/// Returns a key that determines the position of `item` in the generated code.
+/// Enum values have the same key, so they retain the declaration order.
+fn stable_order_key(item: &RustItem) -> (u8, String) {
+    match item {
+        RustItem::EnumValue(_) => (0, String::new()),
+        RustItem::Module(_)
+        | RustItem::Struct(_)
+        | RustItem::Function(_)
+        | RustItem::Reexport(_) => {
+            let path = item
+                .path()
+                .expect("item must have path because it's not a trait impl");
+            (1, path.parts.join("::"))
+        }
+        RustItem::TraitImpl(data) => (
+            2,
+            format!(
+                "{} for {}",
+                rust_common_type_to_code(&data.trait_type, None),
+                rust_type_to_code(&data.target_type, None)
+            ),
+        ),
+        RustItem::ExtraImpl(data) => (3, format!("{:?}", data.kind)),
+    }
+}
+
+/// Returns children of `parent` in a deterministic order that doesn't depend on
+/// the order of items in the database, so that regenerating the crate
+/// after a small change in the C++ library produces a small diff.
+fn sorted_rust_children<'a>(
+    db: &'a DatabaseClient,
+    parent: &'a RustPath,
+) -> Vec<DbItem<&'a RustItem>> {
+    let mut children = db.rust_children(parent).collect_vec();
+    children.sort_by_cached_key(|item| stable_order_key(item.item));
+    children
+}
+
 impl Generator<'_> {
     fn module_path(&self, rust_path: &RustPath, root_path: &Path) -> Result<PathBuf> {
         let parts = &rust_path.parts;
@@ -206,17 +308,7 @@ impl Generator<'_> {
             "Generator::push_file expects path from this crate"
         );
 
-        let path = if parts.len() == 1 {
-            root_path.join("lib.rs")
-        } else {
-            let mut path = root_path.to_path_buf();
-            for middle_part in &parts[1..parts.len() - 1] {
-                path.push(middle_part);
-            }
-            path.push(format!("{}.rs", parts.last().expect("path is empty")));
-            path
-        };
-        Ok(path)
+        Ok(module_file_path(rust_path, root_path))
     }
 
     fn push_file(&mut self, path: &Path) -> Result<()> {
@@ -284,20 +376,26 @@ impl Generator<'_> {
 
         let mut condition_texts = ConditionTexts::default();
 
-        if let Some(ffi_item) = ffi_item {
-            let condition = self
-                .current_database
+        let mut condition = if let Some(ffi_item) = ffi_item {
+            self.current_database
                 .cpp_checks(&ffi_item.id)?
-                .condition(self.current_database.environments());
-            if condition != Condition::True {
-                let expression = condition_expression(&condition);
-                condition_texts.attribute = format!(
-                    "#[cfg(any({}, feature = \"ritual_rustdoc\"))]\n",
-                    expression
-                );
-                condition_texts.doc_text =
-                    format!("\n\nThis item is available if `{}`.", expression);
-            }
+                .condition(self.current_database.environments())
+        } else {
+            Condition::True
+        };
+        if let Some(cpp_item) = self
+            .current_database
+            .original_cpp_item(&item_for_condition.id)?
+        {
+            condition = condition.and(item_feature_condition(cpp_item.item, self.feature_macros));
+        }
+        if condition != Condition::True {
+            let expression = condition_expression(&condition);
+            condition_texts.attribute = format!(
+                "#[cfg(any({}, feature = \"ritual_rustdoc\"))]\n",
+                expression
+            );
+            condition_texts.doc_text = format!("\n\nThis item is available if `{}`.", expression);
         }
 
         match &item.item {
@@ -402,8 +500,11 @@ impl Generator<'_> {
                     "include!(concat!(env!(\"OUT_DIR\"), \"/sized_types.rs\"));"
                 )?;
             }
-            RustModuleKind::Special(RustSpecialModuleKind::CrateRoot)
-            | RustModuleKind::Special(RustSpecialModuleKind::Ops)
+            RustModuleKind::Special(RustSpecialModuleKind::CrateRoot) => {
+                self.generate_children(&module.item.path, None)?;
+                self.generate_generator_version_check()?;
+            }
+            RustModuleKind::Special(RustSpecialModuleKind::Ops)
             | RustModuleKind::CppNamespace { .. }
             | RustModuleKind::CppNestedTypes { .. } => {
                 self.generate_children(&module.item.path, None)?;
@@ -420,15 +521,70 @@ impl Generator<'_> {
         if module.item.kind == RustModuleKind::Special(RustSpecialModuleKind::Ffi) {
             let path = self.output_src_path.join("ffi.in.rs");
             self.destination.push(create_file(&path)?);
-            writeln!(self, "extern \"C\" {{\n")?;
-            self.generate_children(&module.item.path, None)?;
-            writeln!(self, "}}\n")?;
+            // functions with different calling conventions
+            // must be declared in separate `extern` blocks
+            let mut abis = vec!["C"];
+            let children = sorted_rust_children(self.current_database, &module.item.path);
+            for item in &children {
+                let abi = self.ffi_abi(item)?;
+                if !abis.contains(&abi) {
+                    abis.push(abi);
+                }
+            }
+            for abi in abis {
+                writeln!(self, "extern \"{}\" {{\n", abi)?;
+                for item in &children {
+                    if self.ffi_abi(item)? == abi {
+                        self.generate_item(item.clone(), None)?;
+                    }
+                }
+                if abi == "C" {
+                    writeln!(
+                        self,
+                        "pub fn {}() -> *const ::std::os::raw::c_char;\n",
+                        generator_version_function_name(&self.current_database.crate_name())
+                    )?;
+                }
+                writeln!(self, "}}\n")?;
+            }
             self.pop_file();
         }
 
         Ok(())
     }
 
+    /// Generates a function that checks that the C++ wrapper library
+    /// was generated by the same version of the generator as the crate.
+    fn generate_generator_version_check(&mut self) -> Result<()> {
+        let ffi_module = self
+            .current_database
+            .rust_items()
+            .filter_map(|i| i.filter_map(|i| i.as_module_ref()))
+            .find(|module| module.item.kind == RustModuleKind::Special(RustSpecialModuleKind::Ffi))
+            .ok_or_else(|| err_msg("ffi module not found"))?;
+        let version_function_path = ffi_module.item.path.join(generator_version_function_name(
+            &self.current_database.crate_name(),
+        ));
+        writeln!(
+            self,
+            include_str!("../templates/crate/generator_version_check.rs.in"),
+            generator_version = GENERATOR_VERSION,
+            version_function = self.rust_path_to_string(&version_function_path),
+        )?;
+        Ok(())
+    }
+
+    /// Returns ABI of the `extern` block that should contain
+    /// the declaration of the FFI function `item`.
+    fn ffi_abi(&self, item: &DbItem<&RustItem>) -> Result<&'static str> {
+        let calling_convention = self
+            .current_database
+            .source_ffi_item(&item.id)?
+            .and_then(|ffi_item| ffi_item.item.as_function_ref())
+            .and_then(|function| function.calling_convention);
+        Ok(calling_convention.map_or("C", |c| c.rust_abi()))
+    }
+
     fn qt_core_path(&self) -> RustPath {
         qt_core_path(&self.current_database.crate_name())
     }
@@ -442,6 +598,46 @@ impl Generator<'_> {
         }
     }
 
+    /// Generates code that calls a Rust closure from an `extern "C"` callback,
+    /// preventing a panic from unwinding into C++.
+    fn callback_call(&self, call: &str) -> String {
+        callback_call_code(call, self.callback_panic_policy)
+    }
+
+    /// Returns the `FnMut` trait of the closure type `closure`.
+    fn closure_trait_to_code(&self, closure: &RustType) -> Result<String> {
+        if let RustType::Closure {
+            return_type,
+            arguments,
+        } = closure
+        {
+            Ok(closure_trait_to_code(
+                return_type,
+                arguments,
+                Some(&self.current_database.crate_name()),
+            ))
+        } else {
+            bail!("expected closure type, got {:?}", closure);
+        }
+    }
+
+    /// Returns the function pointer type of a `ClosureToCallback` argument.
+    fn closure_callback_type(&self, type1: &RustFinalType) -> Result<RustType> {
+        let function_pointer = type1
+            .ffi_type()
+            .as_common()?
+            .generic_arguments
+            .iter()
+            .flatten()
+            .next()
+            .ok_or_else(|| err_msg("expected Option<fn> for ClosureToCallback"))?;
+        if let RustType::FunctionPointer { .. } = function_pointer {
+            Ok(function_pointer.clone())
+        } else {
+            bail!("expected function pointer for ClosureToCallback");
+        }
+    }
+
     fn generate_struct(
         &mut self,
         rust_struct: DbItem<&RustStruct>,
@@ -458,7 +654,7 @@ impl Generator<'_> {
         };
         match &rust_struct.item.kind {
             RustStructKind::WrapperType(kind) => match kind {
-                RustWrapperTypeKind::EnumWrapper => {
+                RustWrapperTypeKind::EnumWrapper { .. } => {
                     writeln!(
                         self,
                         include_str!("../templates/crate/enum_wrapper.rs.in"),
@@ -486,6 +682,25 @@ impl Generator<'_> {
                     )?;
                     writeln!(self)?;
                 }
+                RustWrapperTypeKind::TransparentClassWrapper { fields, .. } => {
+                    writeln!(self, "#[repr(C)]")?;
+                    writeln!(
+                        self,
+                        "{}struct {} {{",
+                        visibility,
+                        rust_struct.item.path.last()
+                    )?;
+                    for field in fields {
+                        writeln!(
+                            self,
+                            "pub {}: {},",
+                            field.name,
+                            self.rust_type_to_code(&field.field_type)
+                        )?;
+                    }
+                    writeln!(self, "}}")?;
+                    writeln!(self)?;
+                }
             },
             RustStructKind::QtSlotWrapper(slot_wrapper) => {
                 let arg_texts = slot_wrapper
@@ -509,6 +724,19 @@ impl Generator<'_> {
                         self.convert_type_from_ffi(t, format!("arg{}", num), false, false)
                     })?
                     .join(", ");
+                let return_type = &slot_wrapper.return_type;
+                let (closure_return_type, callback_return_type) =
+                    if return_type.api_type().is_unit() {
+                        (String::new(), String::new())
+                    } else {
+                        (
+                            format!(" -> {}", self.rust_type_to_code(return_type.api_type())),
+                            format!(" -> {}", self.rust_type_to_code(return_type.ffi_type())),
+                        )
+                    };
+                let callback_call = self.callback_call(
+                    &self.convert_type_to_ffi(&format!("func({})", func_args), return_type)?,
+                );
                 writeln!(
                     self,
                     include_str!("../templates/crate/closure_slot_wrapper.rs.in"),
@@ -516,7 +744,9 @@ impl Generator<'_> {
                     type_name = self.rust_path_to_string(&slot_wrapper.raw_slot_wrapper),
                     pub_type_name = rust_struct.item.path.last(),
                     args = args,
-                    func_args = func_args,
+                    closure_return_type = closure_return_type,
+                    callback_return_type = callback_return_type,
+                    callback_call = callback_call,
                     callback_args = callback_args,
                     condition_attribute = condition_texts.attribute,
                 )?;
@@ -524,6 +754,50 @@ impl Generator<'_> {
             RustStructKind::SizedType(_) => {
                 bail!("sized struct can't be generated with rust code generator")
             }
+            RustStructKind::ConstructorArgs(args) => {
+                writeln!(
+                    self,
+                    "{}enum {} {{",
+                    visibility,
+                    rust_struct.item.path.last()
+                )?;
+                for variant in &args.variants {
+                    if variant.argument_types.is_empty() {
+                        writeln!(self, "{},", variant.name)?;
+                    } else {
+                        writeln!(
+                            self,
+                            "{}({}),",
+                            variant.name,
+                            variant
+                                .argument_types
+                                .iter()
+                                .map(|t| self.rust_type_to_code(t))
+                                .join(", ")
+                        )?;
+                    }
+                }
+                writeln!(self, "}}")?;
+                writeln!(self)?;
+            }
+            RustStructKind::VariantEnum(data) => {
+                writeln!(
+                    self,
+                    "{}enum {} {{",
+                    visibility,
+                    rust_struct.item.path.last()
+                )?;
+                for alternative in &data.alternatives {
+                    writeln!(
+                        self,
+                        "{}({}),",
+                        alternative.name,
+                        self.rust_type_to_code(&alternative.value_type)
+                    )?;
+                }
+                writeln!(self, "}}")?;
+                writeln!(self)?;
+            }
         }
 
         if self
@@ -678,6 +952,29 @@ impl Generator<'_> {
                     source_expr
                 )
             }
+            RustToFfiTypeConversion::OrderingToInt => format!("{}.cmp(&0)", source_expr),
+            RustToFfiTypeConversion::OrderingEqToInt => format!("{} == 0", source_expr),
+            RustToFfiTypeConversion::BoolToResult => {
+                format!("if {} {{ Ok(()) }} else {{ Err(()) }}", source_expr)
+            }
+            RustToFfiTypeConversion::StaticStrToPtr { is_utf8 } => {
+                let c_str = "::std::ffi::CStr::from_ptr(ptr)";
+                let code = if *is_utf8 {
+                    format!("::std::str::from_utf8_unchecked({}.to_bytes())", c_str)
+                } else {
+                    c_str.to_string()
+                };
+                format!(
+                    "{{\n    let ptr = {};\n    if ptr.is_null() {{\n        None\n    \
+                     }} else {{\n        Some({})\n    }}\n}}",
+                    source_expr,
+                    wrap_unsafe(in_unsafe_context, &code)
+                )
+            }
+            RustToFfiTypeConversion::DurationToTicks { period } => format!(
+                "::cpp_core::duration::duration_from_ticks({}, {}, {})",
+                source_expr, period.numerator, period.denominator
+            ),
             RustToFfiTypeConversion::UnitToAnything => format!("let _ = {};", source_expr),
             RustToFfiTypeConversion::AsCast { api_type } => {
                 format!("{} as {}", source_expr, self.rust_type_to_code(api_type))
@@ -696,6 +993,21 @@ impl Generator<'_> {
             RustToFfiTypeConversion::ImplCastInto(_) => {
                 bail!("ImplCastInto is not convertable from FFI type");
             }
+            RustToFfiTypeConversion::ClosureToCallback => {
+                bail!("ClosureToCallback is not convertable from FFI type");
+            }
+            RustToFfiTypeConversion::OptionalToValue(_) => {
+                bail!("OptionalToValue must be converted in generate_ffi_call");
+            }
+            RustToFfiTypeConversion::ExceptionToResult(_) => {
+                bail!("ExceptionToResult must be converted in generate_ffi_call");
+            }
+            RustToFfiTypeConversion::ArrayRefToPtr { .. } => {
+                bail!("ArrayRefToPtr is not convertable from FFI type");
+            }
+            RustToFfiTypeConversion::SliceToPtr => {
+                bail!("SliceToPtr is not convertable from FFI type");
+            }
         };
         Ok(code1 + &code2)
     }
@@ -750,10 +1062,81 @@ impl Generator<'_> {
             RustToFfiTypeConversion::OptionUtilsRefToPtr { .. } => {
                 bail!("OptionUtilsRefToPtr is not supported in argument position");
             }
+            RustToFfiTypeConversion::ArrayRefToPtr { .. } | RustToFfiTypeConversion::SliceToPtr => {
+                if type1.ffi_type().is_const_pointer_like()? {
+                    format!("{}.as_ptr()", expr)
+                } else {
+                    format!("{}.as_mut_ptr()", expr)
+                }
+            }
             RustToFfiTypeConversion::QFlagsToUInt { .. } => format!("{}.to_int()", expr),
+            RustToFfiTypeConversion::DurationToTicks { period } => format!(
+                "::cpp_core::duration::duration_to_ticks({}, {}, {})",
+                expr, period.numerator, period.denominator
+            ),
+            RustToFfiTypeConversion::OrderingToInt | RustToFfiTypeConversion::OrderingEqToInt => {
+                bail!("three-way comparison results are not supported in argument position");
+            }
+            RustToFfiTypeConversion::BoolToResult => {
+                bail!("BoolToResult is not possible to use in argument position");
+            }
+            RustToFfiTypeConversion::StaticStrToPtr { .. } => {
+                bail!("StaticStrToPtr is not possible to use in argument position");
+            }
             RustToFfiTypeConversion::UnitToAnything => {
                 bail!("UnitToAnything is not possible to use in argument position");
             }
+            RustToFfiTypeConversion::OptionalToValue(_) => {
+                bail!("OptionalToValue is not possible to use in argument position");
+            }
+            RustToFfiTypeConversion::ExceptionToResult(_) => {
+                bail!("ExceptionToResult is not possible to use in argument position");
+            }
+            RustToFfiTypeConversion::ClosureToCallback => {
+                let function_pointer = self.closure_callback_type(type1)?;
+                let arguments =
+                    if let RustType::FunctionPointer { arguments, .. } = &function_pointer {
+                        arguments
+                    } else {
+                        unreachable!();
+                    };
+                let user_data_type = arguments
+                    .last()
+                    .ok_or_else(|| err_msg("callback must have user data argument"))?;
+                let callback_args = arguments[..arguments.len() - 1]
+                    .iter()
+                    .enumerate()
+                    .map(|(num, t)| format!("arg{}: {}", num, self.rust_type_to_code(t)))
+                    .chain(std::iter::once(format!(
+                        "data: {}",
+                        self.rust_type_to_code(user_data_type)
+                    )))
+                    .join(", ");
+                let func_args = (0..arguments.len() - 1)
+                    .map(|num| format!("arg{}", num))
+                    .join(", ");
+                let return_type =
+                    if let RustType::FunctionPointer { return_type, .. } = &function_pointer {
+                        return_type
+                    } else {
+                        unreachable!();
+                    };
+                format!(
+                    "{{\nextern \"C\" fn trampoline({args}){return_type} {{\n\
+                     let func = unsafe {{ &mut *(data as *mut ::std::boxed::Box<dyn {closure_trait}>) }};\n\
+                     {call}\n}}\n\
+                     Some(trampoline as {fn_type})\n}}",
+                    args = callback_args,
+                    return_type = if return_type.is_unit() {
+                        String::new()
+                    } else {
+                        format!(" -> {}", self.rust_type_to_code(return_type))
+                    },
+                    closure_trait = self.closure_trait_to_code(type1.api_type())?,
+                    call = self.callback_call(&format!("func({})", func_args)),
+                    fn_type = self.rust_type_to_code(&function_pointer),
+                )
+            }
             RustToFfiTypeConversion::AsCast { .. } => {
                 format!("{} as {}", expr, self.rust_type_to_code(type1.ffi_type()))
             }
@@ -793,6 +1176,63 @@ impl Generator<'_> {
         wrapper_data: &RustFfiWrapperData,
         in_unsafe_context: bool,
     ) -> Result<String> {
+        let mut exception_var_name = "exception_buffer".to_string();
+        let mut ii = 1;
+        while arguments.iter().any(|x| x.name == exception_var_name) {
+            ii += 1;
+            exception_var_name = format!("exception_buffer{}", ii);
+        }
+        let ffi_item = self
+            .current_database
+            .source_ffi_item(id)?
+            .ok_or_else(|| err_msg("source ffi item not found"))?
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid source ffi item type"))?;
+
+        if let RustToFfiTypeConversion::ExceptionToResult(conversion) = return_type.conversion() {
+            let value_type =
+                RustFinalType::new(return_type.ffi_type().clone(), (**conversion).clone())?;
+            // The return value is garbage if an exception was caught, so it must not
+            // be converted (e.g. to a reference or a `CppBox`) before the check.
+            // Values returned through output arguments are not converted.
+            let has_output_arguments = ffi_item.arguments.iter().any(|arg| {
+                arg.meaning == CppFfiArgumentMeaning::ReturnValue
+                    || arg.meaning == CppFfiArgumentMeaning::HasValue
+            });
+            let call_type = if has_output_arguments {
+                value_type.clone()
+            } else {
+                RustFinalType::new(
+                    return_type.ffi_type().clone(),
+                    RustToFfiTypeConversion::None,
+                )?
+            };
+            let call =
+                self.generate_ffi_call(id, arguments, &call_type, wrapper_data, in_unsafe_context)?;
+            let value = if has_output_arguments {
+                "value".to_string()
+            } else {
+                self.convert_type_from_ffi(
+                    &value_type,
+                    "value".to_string(),
+                    in_unsafe_context,
+                    true,
+                )?
+            };
+            return Ok(format!(
+                "{{\nlet mut {var} = [0 as ::std::os::raw::c_char; ::cpp_core::EXCEPTION_BUFFER_SIZE];\n\
+                 let value = {{ {call} }};\n\
+                 match ::cpp_core::CppException::from_buffer(&{var}) {{\n\
+                 Some(exception) => Err(exception),\n\
+                 None => Ok({{ {value} }}),\n\
+                 }}\n}}",
+                var = exception_var_name,
+                call = call,
+                value = value,
+            ));
+        }
+
         let mut final_args = Vec::new();
         for arg in arguments {
             let code = self.convert_type_to_ffi(&arg.name, &arg.argument_type)?;
@@ -803,14 +1243,6 @@ impl Generator<'_> {
         let mut result = Vec::new();
         let mut maybe_result_var_name = None;
 
-        let ffi_item = self
-            .current_database
-            .source_ffi_item(id)?
-            .ok_or_else(|| err_msg("source ffi item not found"))?
-            .item
-            .as_function_ref()
-            .ok_or_else(|| err_msg("invalid source ffi item type"))?;
-
         let return_type_ffi_index = ffi_item
             .arguments
             .iter()
@@ -855,10 +1287,121 @@ impl Generator<'_> {
             final_args[i] = Some(format!("&mut {}", return_var_name));
             maybe_result_var_name = Some(return_var_name);
         }
+        let has_value_ffi_index = ffi_item
+            .arguments
+            .iter()
+            .position(|arg| arg.meaning == CppFfiArgumentMeaning::HasValue);
+        let mut has_value_var_name = "has_value".to_string();
+        if let Some(i) = has_value_ffi_index {
+            let mut ii = 1;
+            while arguments.iter().any(|x| x.name == has_value_var_name) {
+                ii += 1;
+                has_value_var_name = format!("has_value{}", ii);
+            }
+            final_args.resize(i + 1, None);
+            final_args[i] = Some(format!("&mut {}", has_value_var_name));
+        }
+        let user_data_ffi_index = ffi_item.arguments.iter().position(|arg| {
+            if let CppFfiArgumentMeaning::CallbackUserData(_) = arg.meaning {
+                true
+            } else {
+                false
+            }
+        });
+        if let Some(i) = user_data_ffi_index {
+            let callback_arg = arguments
+                .iter()
+                .find(|arg| {
+                    arg.argument_type.conversion() == &RustToFfiTypeConversion::ClosureToCallback
+                })
+                .ok_or_else(|| err_msg("closure argument for callback user data not found"))?;
+            let user_data_type = match self.closure_callback_type(&callback_arg.argument_type)? {
+                RustType::FunctionPointer { arguments, .. } => arguments
+                    .last()
+                    .map(|t| self.rust_type_to_code(t))
+                    .ok_or_else(|| err_msg("callback must have user data argument"))?,
+                _ => unreachable!(),
+            };
+            // the closure is boxed twice because `Box<dyn FnMut>` is a fat pointer
+            final_args.resize(i + 1, None);
+            final_args[i] = Some(format!(
+                "::std::boxed::Box::into_raw(::std::boxed::Box::new(\
+                 ::std::boxed::Box::new({name}) as ::std::boxed::Box<dyn {closure_trait}>)) as {t}",
+                name = callback_arg.name,
+                closure_trait =
+                    self.closure_trait_to_code(callback_arg.argument_type.api_type())?,
+                t = user_data_type,
+            ));
+        }
+        for (i, ffi_arg) in ffi_item.arguments.iter().enumerate() {
+            let array_index = if let CppFfiArgumentMeaning::ArrayLength(index) = ffi_arg.meaning {
+                index
+            } else {
+                continue;
+            };
+            let array_arg = arguments
+                .iter()
+                .find(|arg| {
+                    ffi_item.arguments[arg.ffi_index].meaning
+                        == CppFfiArgumentMeaning::Argument(array_index)
+                })
+                .ok_or_else(|| err_msg("array argument for array length not found"))?;
+            let is_initializer_list = ffi_item.arguments[array_arg.ffi_index]
+                .argument_type
+                .conversion()
+                == &CppToFfiTypeConversion::InitializerListToPointer;
+            let length = if is_initializer_list {
+                // the C++ wrapper can't construct longer lists
+                format!(
+                    "{{ assert!({name}.len() <= {max}, \"initializer list argument {name} \
+                     is too long\"); {name}.len() }}",
+                    name = array_arg.name,
+                    max = MAX_INITIALIZER_LIST_SIZE,
+                )
+            } else {
+                format!("{}.len()", array_arg.name)
+            };
+            final_args.resize(final_args.len().max(i + 1), None);
+            final_args[i] = Some(length);
+        }
+        let exception_ffi_index = ffi_item
+            .arguments
+            .iter()
+            .position(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionOutput);
+        if let Some(i) = exception_ffi_index {
+            final_args.resize(final_args.len().max(i + 1), None);
+            final_args[i] = Some(format!("{}.as_mut_ptr()", exception_var_name));
+        }
         let final_args = final_args
             .into_iter()
             .map_if_ok(|x| x.ok_or_else(|| err_msg("ffi argument is missing")))?;
 
+        // the presence of the optional value is checked after the call,
+        // so the value is converted separately
+        let maybe_optional_value = if has_value_ffi_index.is_some() {
+            let conversion = if let RustToFfiTypeConversion::OptionalToValue(conversion) =
+                return_type.conversion()
+            {
+                conversion
+            } else {
+                bail!("OptionalToValue conversion expected for has_value argument");
+            };
+            let value_type =
+                RustFinalType::new(return_type.ffi_type().clone(), (**conversion).clone())?;
+            result.push(format!(
+                "{{\nlet mut {} = false;\nlet value = ",
+                has_value_var_name
+            ));
+            Some(self.convert_type_from_ffi(
+                &value_type,
+                "value".to_string(),
+                in_unsafe_context,
+                false,
+            )?)
+        } else {
+            None
+        };
+
         result.push(wrap_unsafe(
             in_unsafe_context,
             &format!(
@@ -875,14 +1418,140 @@ impl Generator<'_> {
         if let Some(name) = &maybe_result_var_name {
             result.push(format!("{}\n}}", name));
         }
+        if let Some(value) = &maybe_optional_value {
+            result.push(format!(
+                ";\nif {var} {{ Some({value}) }} else {{ None }}\n}}",
+                var = has_value_var_name,
+                value = value,
+            ));
+        }
         let code = result.join("");
-        if maybe_result_var_name.is_none() {
+        if maybe_result_var_name.is_none() && maybe_optional_value.is_none() {
             self.convert_type_from_ffi(&return_type, code, in_unsafe_context, true)
         } else {
             Ok(code)
         }
     }
 
+    /// Generates body of a function that calls the registration function
+    /// and returns a guard calling the unregistration function on drop.
+    fn generate_registration_guard_body(
+        &self,
+        func: DbItem<&RustFunction>,
+        data: &RustRegistrationGuardData,
+    ) -> Result<String> {
+        // the token is returned without conversion,
+        // so the guard's return type can be passed here
+        let register_call = self.generate_ffi_call(
+            &func.id,
+            &func.item.arguments,
+            &func.item.return_type,
+            &RustFfiWrapperData {
+                ffi_function_path: data.register_ffi_function_path.clone(),
+            },
+            func.item.is_unsafe,
+        )?;
+        let mut result = format!("let token = {};\n", register_call);
+        let mut unregister_args = Vec::new();
+        if let Some(this_type) = &data.unregister_this_type {
+            let self_arg = func
+                .item
+                .arguments
+                .iter()
+                .find(|arg| arg.name == "self")
+                .ok_or_else(|| err_msg("registration guard must have self argument"))?;
+            writeln!(
+                result,
+                "let this_ptr = {} as {};",
+                self.convert_type_to_ffi(&self_arg.name, &self_arg.argument_type)?,
+                self.rust_type_to_code(this_type)
+            )?;
+            unregister_args.push("this_ptr");
+        }
+        unregister_args.push("token");
+        write!(
+            result,
+            "::cpp_core::Guard::new(move || {})",
+            wrap_unsafe(
+                false,
+                &format!(
+                    "{}({});",
+                    self.rust_path_to_string(&data.unregister_ffi_function_path),
+                    unregister_args.join(", ")
+                )
+            )
+        )?;
+        Ok(result)
+    }
+
+    /// Generates body of a function that calls the constructor
+    /// corresponding to the variant of its `args` argument.
+    fn generate_constructor_dispatch_body(
+        &self,
+        data: &RustConstructorDispatchData,
+    ) -> Result<String> {
+        let enum_name = self.rust_path_to_string(&data.args_enum_path);
+        let mut result = "match args {\n".to_string();
+        for variant in &data.variants {
+            let names = (0..variant.argument_types.len())
+                .map(|i| format!("arg{}", i))
+                .join(", ");
+            let pattern = if variant.argument_types.is_empty() {
+                String::new()
+            } else {
+                format!("({})", names)
+            };
+            writeln!(
+                result,
+                "{}::{}{} => {}({}),",
+                enum_name,
+                variant.name,
+                pattern,
+                self.rust_path_to_string(&variant.function_path),
+                names
+            )?;
+        }
+        result.push('}');
+        Ok(result)
+    }
+
+    /// Generates body of a function that returns a Rust enum
+    /// referencing the active alternative of a `std::variant`.
+    fn generate_variant_to_enum_body(
+        &self,
+        func: DbItem<&RustFunction>,
+        data: &RustVariantToEnumData,
+    ) -> Result<String> {
+        let self_arg = func
+            .item
+            .arguments
+            .iter()
+            .find(|arg| arg.name == "self")
+            .ok_or_else(|| err_msg("variant to enum function must have self argument"))?;
+        let mut result = format!(
+            "let this_ptr = {};\n",
+            self.convert_type_to_ffi(&self_arg.name, &self_arg.argument_type)?
+        );
+        writeln!(
+            result,
+            "match {}(this_ptr) {{",
+            self.rust_path_to_string(&data.index_ffi_function_path)
+        )?;
+        let enum_name = self.rust_path_to_string(&data.enum_path);
+        for (index, alternative) in data.alternatives.iter().enumerate() {
+            writeln!(
+                result,
+                "{} => ::cpp_core::Ref::from_raw({}(this_ptr)).map({}::{}),",
+                index,
+                self.rust_path_to_string(&alternative.get_if_ffi_function_path),
+                enum_name,
+                alternative.name
+            )?;
+        }
+        result.push_str("_ => None,\n}");
+        Ok(result)
+    }
+
     /// Generates Rust code for declaring a function's arguments.
     fn arg_texts(
         &self,
@@ -975,6 +1644,15 @@ impl Generator<'_> {
                 Some(wrap_unsafe(func.item.is_unsafe, &call))
             }
             RustFunctionKind::FfiFunction => None,
+            RustFunctionKind::RegistrationGuard(data) => {
+                Some(self.generate_registration_guard_body(func.clone(), data)?)
+            }
+            RustFunctionKind::ConstructorDispatch(data) => {
+                Some(self.generate_constructor_dispatch_body(data)?)
+            }
+            RustFunctionKind::VariantToEnum(data) => {
+                Some(self.generate_variant_to_enum_body(func.clone(), data)?)
+            }
         };
 
         let maybe_body = match body {
@@ -1029,7 +1707,7 @@ impl Generator<'_> {
     }
 
     fn generate_children(&mut self, parent: &RustPath, self_type: Option<&RustType>) -> Result<()> {
-        for item in self.current_database.rust_children(&parent) {
+        for item in sorted_rust_children(self.current_database, parent) {
             self.generate_item(item, self_type)?;
         }
         Ok(())
@@ -1104,16 +1782,89 @@ impl Generator<'_> {
     }
 }
 
+/// Returns path to the file of the module `rust_path` within `root_path`.
+fn module_file_path(rust_path: &RustPath, root_path: &Path) -> PathBuf {
+    let parts = &rust_path.parts;
+    if parts.len() == 1 {
+        root_path.join("lib.rs")
+    } else {
+        let mut path = root_path.to_path_buf();
+        for middle_part in &parts[1..parts.len() - 1] {
+            path.push(middle_part);
+        }
+        path.push(format!("{}.rs", parts.last().expect("path is empty")));
+        path
+    }
+}
+
+/// Adds paths of the files `generate` creates for `module`
+/// and its child modules to `output`.
+fn collect_file_paths(
+    current_database: &DatabaseClient,
+    module: &RustModule,
+    output_src_path: &Path,
+    output: &mut Vec<PathBuf>,
+) {
+    if module.kind == RustModuleKind::Special(RustSpecialModuleKind::Ffi) {
+        output.push(output_src_path.join("ffi.in.rs"));
+        return;
+    }
+    if current_database
+        .rust_children(&module.path)
+        .next()
+        .is_none()
+    {
+        // empty modules are skipped
+        return;
+    }
+    if module.kind.is_in_separate_file() {
+        output.push(module_file_path(&module.path, output_src_path));
+    }
+    for child in current_database.rust_children(&module.path) {
+        if let Some(child) = child.item.as_module_ref() {
+            collect_file_paths(current_database, child, output_src_path, output);
+        }
+    }
+}
+
+/// Returns paths of the files `generate` would create in `output_src_path`.
+pub fn file_paths(
+    current_database: &DatabaseClient,
+    output_src_path: &Path,
+) -> Result<Vec<PathBuf>> {
+    let crate_root = current_database
+        .rust_items()
+        .filter_map(|i| i.filter_map(|i| i.as_module_ref()))
+        .find(|module| {
+            module.item.kind == RustModuleKind::Special(RustSpecialModuleKind::CrateRoot)
+        })
+        .ok_or_else(|| err_msg("crate root not found"))?;
+
+    let mut paths = Vec::new();
+    collect_file_paths(
+        current_database,
+        crate_root.item,
+        output_src_path,
+        &mut paths,
+    );
+    paths.sort();
+    Ok(paths)
+}
+
 pub fn generate(
     current_database: &DatabaseClient,
     output_src_path: impl Into<PathBuf>,
     crate_template_src_path: Option<impl Into<PathBuf>>,
+    callback_panic_policy: CallbackPanicPolicy,
+    feature_macros: &[(FeatureMacro, Vec<CppPath>)],
 ) -> Result<()> {
     let mut generator = Generator {
         destination: Vec::new(),
         output_src_path: output_src_path.into(),
         crate_template_src_path: crate_template_src_path.map(Into::into),
         current_database,
+        callback_panic_policy,
+        feature_macros,
     };
 
     let crate_root = generator
@@ -1,12 +1,15 @@
 use crate::config::CrateDependencyKind;
 use crate::cpp_checks::CppChecks;
-use crate::cpp_data::{CppItem, CppPath, CppPathItem, CppTypeDeclaration, CppTypeDeclarationKind};
+use crate::cpp_data::{
+    CppItem, CppPath, CppPathItem, CppTypeDeclaration, CppTypeDeclarationKind, CppVisibility,
+};
+use crate::cpp_enums::{enum_groups, CppEnumKind};
 use crate::cpp_ffi_data::{
-    CppCast, CppFfiArgumentMeaning, CppFfiFunction, CppFfiFunctionKind, CppFfiItem, CppFfiType,
-    CppFieldAccessorType, CppToFfiTypeConversion,
+    member_function_pointer_struct_path, CppCast, CppFfiArgumentMeaning, CppFfiFunction,
+    CppFfiFunctionKind, CppFfiItem, CppFfiType, CppFieldAccessorType, CppToFfiTypeConversion,
 };
 use crate::cpp_ffi_generator::ffi_type;
-use crate::cpp_function::{CppFunction, CppOperator, ReturnValueAllocationPlace};
+use crate::cpp_function::{CppFunction, CppFunctionKind, CppOperator, ReturnValueAllocationPlace};
 use crate::cpp_type::{
     is_qflags, CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind,
     CppSpecificNumericType, CppSpecificNumericTypeKind, CppType, CppTypeRole,
@@ -14,13 +17,16 @@ use crate::cpp_type::{
 use crate::database::{DbItem, ItemWithSource};
 use crate::processor::ProcessorData;
 use crate::rust_info::{
-    NameType, RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData,
-    RustFlagEnumImpl, RustFunction, RustFunctionArgument, RustFunctionCaptionStrategy,
-    RustFunctionKind, RustFunctionSelfArgKind, RustItem, RustModule, RustModuleKind, RustPathScope,
+    NameType, RustConstructorArgs, RustConstructorArgsVariant, RustConstructorDispatchData,
+    RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData, RustFlagEnumImpl,
+    RustFunction, RustFunctionArgument, RustFunctionCaptionStrategy, RustFunctionKind,
+    RustFunctionSelfArgKind, RustItem, RustModule, RustModuleKind, RustPathScope,
     RustQtReceiverType, RustQtSlotWrapper, RustRawQtSlotWrapperData, RustRawSlotReceiver,
-    RustReexport, RustReexportSource, RustSignalOrSlotGetter, RustSizedType, RustSpecialModuleKind,
-    RustStruct, RustStructKind, RustTraitAssociatedType, RustTraitImpl, RustTraitImplExtraKind,
-    RustTypeCaptionStrategy, RustWrapperTypeKind, UnnamedRustFunction,
+    RustReexport, RustReexportSource, RustRegistrationGuardData, RustSignalOrSlotGetter,
+    RustSizedType, RustSpecialModuleKind, RustStruct, RustStructField, RustStructKind,
+    RustTraitAssociatedType, RustTraitImpl, RustTraitImplExtraKind, RustTypeCaptionStrategy,
+    RustVariantAlternative, RustVariantEnum, RustVariantToEnumData, RustWrapperTypeKind,
+    UnnamedRustFunction,
 };
 use crate::rust_type::{
     RustCommonType, RustFinalType, RustPath, RustPointerLikeTypeKind, RustToFfiTypeConversion,
@@ -84,13 +90,14 @@ enum ProcessedFfiItem {
 #[derive(Debug, Clone, Copy)]
 enum ReturnTypeConstraint {
     Bool,
+    Ordering,
     Usize,
     Unit,
     Any,
 }
 
 #[derive(Debug, Clone, Copy)]
-struct TraitImplInfo {
+pub(crate) struct TraitImplInfo {
     trait_path: &'static str,
     function_name: &'static str,
     is_unsafe: bool,
@@ -104,7 +111,7 @@ struct TraitImplInfo {
 }
 
 impl TraitImplInfo {
-    fn from_operator(operator: &CppOperator) -> Option<TraitImplInfo> {
+    pub(crate) fn from_operator(operator: &CppOperator) -> Option<TraitImplInfo> {
         Some(match operator {
             CppOperator::Addition => TraitImplInfo {
                 trait_path: "std::ops::Add",
@@ -286,6 +293,18 @@ impl TraitImplInfo {
                 trait_arg_is_second_arg_type: true,
                 target_is_reference: false,
             },
+            CppOperator::ThreeWayComparison => TraitImplInfo {
+                trait_path: "cpp_core::cmp::Cmp",
+                function_name: "cmp",
+                is_unsafe: true,
+                is_inherent: false,
+                self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                second_arg_is_reference: true,
+                return_type_constraint: ReturnTypeConstraint::Ordering,
+                has_output_associated_type: false,
+                trait_arg_is_second_arg_type: true,
+                target_is_reference: false,
+            },
             CppOperator::LogicalNot => TraitImplInfo {
                 trait_path: "std::ops::Not",
                 function_name: "not",
@@ -613,7 +632,7 @@ impl TraitImplInfo {
 }
 
 #[derive(Debug)]
-struct TraitTypes {
+pub(crate) struct TraitTypes {
     target_type: RustType,
     trait_type: RustCommonType,
 }
@@ -651,9 +670,109 @@ fn check_trait_impl_uniqueness(
     Ok(())
 }
 
-struct State<'b, 'a> {
+/// Returns true if `function` is the FFI wrapper of a non-const method `cpp_function`
+/// that returns a mutable reference to its own class type (e.g. `T& T::setFoo(int)`)
+/// and was marked with one of `chaining_methods`. Such methods return `*this`
+/// to allow method chaining. The return type alone can't prove that because
+/// a method may return a reference to a different object of the same class.
+pub(crate) fn is_chaining_method(
+    function: &CppFfiFunction,
+    cpp_function: &CppFunction,
+    chaining_methods: &[CppPath],
+) -> bool {
+    if function.return_type.conversion() != &CppToFfiTypeConversion::ReferenceToPointer
+        || !chaining_methods.contains(&cpp_function.path)
+    {
+        return false;
+    }
+    let is_regular_method = cpp_function.operator.is_none()
+        && cpp_function.member.as_ref().map_or(false, |member| {
+            member.kind == CppFunctionKind::Regular && !member.is_static && !member.is_const
+        });
+    if !is_regular_method {
+        return false;
+    }
+    let this_type = function
+        .arguments
+        .iter()
+        .find(|arg| arg.meaning == CppFfiArgumentMeaning::This)
+        .map(|arg| arg.argument_type.ffi_type());
+    this_type == Some(function.return_type.ffi_type())
+}
+
+/// Returns true if the argument with `index` of `cpp_function` is one of
+/// `ownership_transfer_arguments` and can be passed as a `CppBox`,
+/// i.e. if it's a mutable pointer to a class.
+pub(crate) fn is_ownership_transfer_argument(
+    cpp_function: &CppFunction,
+    index: usize,
+    ownership_transfer_arguments: &[(CppPath, usize)],
+) -> bool {
+    let is_marked = ownership_transfer_arguments
+        .iter()
+        .any(|(path, i)| path == &cpp_function.path && *i == index);
+    if !is_marked {
+        return false;
+    }
+    let is_mut_class_pointer = match cpp_function
+        .arguments
+        .get(index)
+        .map(|arg| &arg.argument_type)
+    {
+        Some(CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::Pointer,
+            is_const: false,
+            target,
+        }) => target.is_class(),
+        _ => false,
+    };
+    if !is_mut_class_pointer {
+        debug!(
+            "ownership transfer argument is not a mutable pointer to class: {}: {}",
+            cpp_function.short_text(),
+            index
+        );
+    }
+    is_mut_class_pointer
+}
+
+/// Returns true if the FFI function `unregister_function` only takes a registration
+/// token of `token_type` and, if it's a method, is a method of the same class
+/// as the registration function (`register_this_type`).
+pub(crate) fn is_matching_unregistration_function(
+    unregister_function: &CppFfiFunction,
+    token_type: &CppType,
+    register_this_type: Option<&CppType>,
+) -> Result<bool> {
+    let mut unregister_this = None;
+    let mut has_token = false;
+    let mut has_other_arguments = false;
+    for arg in &unregister_function.arguments {
+        match arg.meaning {
+            CppFfiArgumentMeaning::This => unregister_this = Some(arg),
+            CppFfiArgumentMeaning::Argument(0) if arg.argument_type.ffi_type() == token_type => {
+                has_token = true;
+            }
+            _ => has_other_arguments = true,
+        }
+    }
+    if !has_token || has_other_arguments {
+        return Ok(false);
+    }
+    let unregister_this_type = unregister_this
+        .map(|arg| arg.argument_type.ffi_type().pointer_like_to_target())
+        .transpose()?;
+    Ok(unregister_this_type == register_this_type)
+}
+
+pub(crate) struct State<'b, 'a> {
     data: &'b mut ProcessorData<'a>,
     special_module_paths: HashMap<RustSpecialModuleKind, RustPath>,
+    /// Operators and operand types of all C++ operator functions
+    /// (see `State::has_matching_operator`).
+    operators: HashSet<(CppOperator, Vec<CppType>)>,
+    /// Paths of C++ enums that are used as sets of flags.
+    flag_enums: HashSet<CppPath>,
 }
 
 impl State<'_, '_> {
@@ -751,6 +870,12 @@ impl State<'_, '_> {
                 let name = if *is_signed { "isize" } else { "usize" };
                 RustType::Primitive(name.into())
             }
+            CppType::Class(path) if path == &member_function_pointer_struct_path() => {
+                RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("cpp_core::MemberFunctionPointer"),
+                    generic_arguments: None,
+                })
+            }
             CppType::Enum { path } | CppType::Class(path) => {
                 let rust_item = self.find_wrapper_type(path)?;
                 let path = rust_item
@@ -785,7 +910,9 @@ impl State<'_, '_> {
                     generic_arguments: Some(vec![pointer]),
                 })
             }
-            CppType::TemplateParameter { .. } => bail!("invalid cpp type"),
+            CppType::TemplateParameter { .. } | CppType::MemberFunctionPointer(..) => {
+                bail!("invalid cpp type")
+            }
         };
 
         Ok(rust_type)
@@ -893,6 +1020,14 @@ impl State<'_, '_> {
                             bail!("NotApplicable conflicts with ValueToPointer");
                         }
                     }
+                } else if cpp_ffi_type.conversion()
+                    == &(CppToFfiTypeConversion::ValueToPointer {
+                        is_ffi_const: false,
+                    })
+                {
+                    // constructor argument moved into the constructor
+                    // (see `cpp_code_generator::owned_argument_guards`)
+                    api_to_ffi_conversion = RustToFfiTypeConversion::CppBoxToPtr;
                 } else {
                     // argument passed by value is represented as a reference on Rust side
                     api_to_ffi_conversion = RustToFfiTypeConversion::ImplCastInto(Box::new(
@@ -922,65 +1057,32 @@ impl State<'_, '_> {
                         };
                 } else {
                     // argument
-                    api_to_ffi_conversion =
-                        if let CppToFfiTypeConversion::ReferenceToPointer { .. } =
-                            cpp_ffi_type.conversion()
-                        {
-                            RustToFfiTypeConversion::ImplCastInto(Box::new(
-                                RustToFfiTypeConversion::UtilsRefToPtr {
-                                    force_api_is_const: None,
-                                },
-                            ))
-                        } else {
-                            RustToFfiTypeConversion::ImplCastInto(Box::new(
-                                RustToFfiTypeConversion::UtilsPtrToPtr {
-                                    force_api_is_const: None,
-                                },
-                            ))
-                        };
+                    api_to_ffi_conversion = if cpp_ffi_type.conversion()
+                        == &CppToFfiTypeConversion::RValueReferenceToPointer
+                    {
+                        // the source object of a move constructor is consumed
+                        // (see `cpp_code_generator::owned_argument_guards`)
+                        RustToFfiTypeConversion::CppBoxToPtr
+                    } else if cpp_ffi_type.conversion()
+                        == &CppToFfiTypeConversion::ReferenceToPointer
+                    {
+                        RustToFfiTypeConversion::ImplCastInto(Box::new(
+                            RustToFfiTypeConversion::UtilsRefToPtr {
+                                force_api_is_const: None,
+                            },
+                        ))
+                    } else {
+                        RustToFfiTypeConversion::ImplCastInto(Box::new(
+                            RustToFfiTypeConversion::UtilsPtrToPtr {
+                                force_api_is_const: None,
+                            },
+                        ))
+                    };
                 }
             }
         }
         if cpp_ffi_type.conversion() == &CppToFfiTypeConversion::QFlagsToInt {
-            let qflags_type = match cpp_ffi_type.original_type() {
-                CppType::PointerLike {
-                    kind,
-                    is_const,
-                    target,
-                } => {
-                    if kind != &CppPointerLikeTypeKind::Reference {
-                        bail!(
-                            "unsupported indirection for QFlagsToUInt: {:?}",
-                            cpp_ffi_type
-                        );
-                    }
-                    if !*is_const {
-                        bail!("unsupported is_const for QFlagsToUInt: {:?}", cpp_ffi_type);
-                    }
-                    &*target
-                }
-                a => a,
-            };
-            let enum_type = if let CppType::Class(path) = qflags_type {
-                let template_arguments = path
-                    .last()
-                    .template_arguments
-                    .as_ref()
-                    .ok_or_else(|| err_msg("expected template arguments for QFlags"))?;
-                if template_arguments.len() != 1 {
-                    bail!("QFlags type must have exactly 1 template argument");
-                }
-                &template_arguments[0]
-            } else {
-                bail!("invalid original type for QFlagsToUInt: {:?}", cpp_ffi_type);
-            };
-
-            let enum_path = if let CppType::Enum { path } = &enum_type {
-                path
-            } else {
-                bail!("invalid QFlags argument type: {:?}", enum_type);
-            };
-
+            let enum_path = cpp_ffi_type.qflags_enum_path()?;
             let rust_enum_type = self.find_wrapper_type(enum_path)?;
             let rust_enum_path = rust_enum_type.item.path().ok_or_else(|| {
                 format_err!(
@@ -993,6 +1095,16 @@ impl State<'_, '_> {
                 api_type: self.create_qflags(rust_enum_path),
             };
         };
+        if cpp_ffi_type.conversion() == &CppToFfiTypeConversion::OrderingToInt {
+            api_to_ffi_conversion = RustToFfiTypeConversion::OrderingToInt;
+        }
+        if let CppToFfiTypeConversion::DurationToTicks { period } = cpp_ffi_type.conversion() {
+            api_to_ffi_conversion = RustToFfiTypeConversion::DurationToTicks { period: *period };
+        }
+        if cpp_ffi_type.conversion() == &CppToFfiTypeConversion::OptionalToValue {
+            api_to_ffi_conversion =
+                RustToFfiTypeConversion::OptionalToValue(Box::new(api_to_ffi_conversion));
+        }
 
         RustFinalType::new(rust_ffi_type, api_to_ffi_conversion)
     }
@@ -1046,7 +1158,7 @@ impl State<'_, '_> {
         Ok(unnamed_function)
     }
 
-    fn process_operator_as_trait_impl(
+    pub(crate) fn process_operator_as_trait_impl(
         unnamed_function: UnnamedRustFunction,
         operator_info: TraitImplInfo,
         crate_name: &str,
@@ -1174,6 +1286,11 @@ impl State<'_, '_> {
                     bail!("return type is not bool");
                 }
             }
+            ReturnTypeConstraint::Ordering => {
+                if function.return_type.conversion() != &RustToFfiTypeConversion::OrderingToInt {
+                    bail!("return type is not Ordering");
+                }
+            }
         }
 
         Ok(RustTraitImpl {
@@ -1399,13 +1516,35 @@ impl State<'_, '_> {
 
         let mut arguments = Vec::new();
         for (arg_index, arg) in function.arguments.iter().enumerate() {
-            if arg.meaning != CppFfiArgumentMeaning::ReturnValue {
-                let arg_type = self.rust_final_type(
+            match arg.meaning {
+                CppFfiArgumentMeaning::CallbackUserData(_) => {
+                    // user data is created from the closure passed
+                    // in place of the callback argument
+                    continue;
+                }
+                CppFfiArgumentMeaning::ArrayLength(_) => {
+                    // the length is taken from the slice passed
+                    // in place of the array argument
+                    continue;
+                }
+                _ => {}
+            }
+            if arg.meaning != CppFfiArgumentMeaning::ReturnValue
+                && arg.meaning != CppFfiArgumentMeaning::HasValue
+                && arg.meaning != CppFfiArgumentMeaning::ExceptionOutput
+            {
+                let mut arg_type = self.rust_final_type(
                     &arg.argument_type,
                     &arg.meaning,
                     function.allocation_place,
                     Some(checks),
                 )?;
+                if let CppFfiArgumentMeaning::Callback(_) = arg.meaning {
+                    arg_type = RustFinalType::new(
+                        arg_type.ffi_type().clone(),
+                        RustToFfiTypeConversion::ClosureToCallback,
+                    )?;
+                }
                 arguments.push(RustFunctionArgument {
                     ffi_index: arg_index,
                     argument_type: arg_type,
@@ -1417,6 +1556,19 @@ impl State<'_, '_> {
                 });
             }
         }
+        let has_array_lengths = function.arguments.iter().any(|arg| {
+            if let CppFfiArgumentMeaning::ArrayLength(_) = arg.meaning {
+                true
+            } else {
+                false
+            }
+        });
+        if has_array_lengths || self.data.config.fixed_array_arguments() {
+            self.apply_array_arguments(item.clone(), &mut arguments)?;
+        }
+        if !self.data.config.ownership_transfer_arguments().is_empty() {
+            self.apply_ownership_transfer_arguments(item.clone(), &mut arguments, checks)?;
+        }
         let mut return_type = if let Some(arg) = function
             .arguments
             .iter()
@@ -1442,6 +1594,45 @@ impl State<'_, '_> {
                 Some(checks),
             )?
         };
+        if self.is_chaining_method(item.clone())? {
+            // allow method chaining by returning the same reference as `self`
+            return_type = RustFinalType::new(
+                return_type.ffi_type().clone(),
+                RustToFfiTypeConversion::RefToPtr {
+                    force_api_is_const: None,
+                    lifetime: None,
+                },
+            )?;
+            if let Some(self_arg) = arguments.iter_mut().find(|arg| arg.name == "self") {
+                if self_arg.argument_type.api_type().lifetime().is_none() {
+                    self_arg.argument_type =
+                        self_arg.argument_type.with_lifetime("l0".to_string())?;
+                }
+                let lifetime = self_arg
+                    .argument_type
+                    .api_type()
+                    .lifetime()
+                    .map(str::to_string);
+                if let Some(lifetime) = lifetime {
+                    return_type = return_type.with_lifetime(lifetime)?;
+                }
+            }
+        }
+        if return_type.conversion() == &RustToFfiTypeConversion::None
+            && return_type.ffi_type() == &RustType::bool()
+            && self.is_success_flag_function(item.clone())?
+        {
+            return_type = RustFinalType::new(
+                return_type.ffi_type().clone(),
+                RustToFfiTypeConversion::BoolToResult,
+            )?;
+        }
+        if let Some(is_utf8) = self.static_string_constant(item.clone())? {
+            return_type = RustFinalType::new(
+                return_type.ffi_type().clone(),
+                RustToFfiTypeConversion::StaticStrToPtr { is_utf8 },
+            )?;
+        }
         if return_type.api_type().is_ref() && return_type.api_type().lifetime().is_none() {
             let mut found = false;
             for arg in &arguments {
@@ -1477,6 +1668,19 @@ impl State<'_, '_> {
             }
         }
 
+        if function
+            .arguments
+            .iter()
+            .any(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionOutput)
+        {
+            return_type = RustFinalType::new(
+                return_type.ffi_type().clone(),
+                RustToFfiTypeConversion::ExceptionToResult(Box::new(
+                    return_type.conversion().clone(),
+                )),
+            )?;
+        }
+
         let mut unnamed_function = UnnamedRustFunction {
             is_public: true,
             arguments: arguments.clone(),
@@ -1514,6 +1718,51 @@ impl State<'_, '_> {
             if cpp_function.operator.as_ref() == Some(&CppOperator::NotEqualTo) {
                 bail!("NotEqualTo is not needed in public API because PartialEq is used");
             }
+            if let Some(operator) = &cpp_function.operator {
+                let is_relational = match operator {
+                    CppOperator::LessThan
+                    | CppOperator::LessThanOrEqualTo
+                    | CppOperator::GreaterThan
+                    | CppOperator::GreaterThanOrEqualTo => true,
+                    _ => false,
+                };
+                if is_relational
+                    && self.has_matching_operator(cpp_function, &CppOperator::ThreeWayComparison)
+                {
+                    bail!(
+                        "relational operators are provided by cpp_core \
+                         based on the three-way comparison"
+                    );
+                }
+            }
+            if cpp_function.operator.as_ref() == Some(&CppOperator::ThreeWayComparison)
+                && !self.has_matching_operator(cpp_function, &CppOperator::EqualTo)
+            {
+                // derive equality from the three-way comparison
+                let mut eq_function = unnamed_function.clone();
+                eq_function.return_type = RustFinalType::new(
+                    eq_function.return_type.ffi_type().clone(),
+                    RustToFfiTypeConversion::OrderingEqToInt,
+                )?;
+                let eq_info = TraitImplInfo::from_operator(&CppOperator::EqualTo)
+                    .ok_or_else(|| err_msg("EqualTo must have trait impl info"))?;
+                match State::process_operator_as_trait_impl(
+                    eq_function,
+                    eq_info,
+                    self.data.db.crate_name(),
+                    trait_types,
+                ) {
+                    Ok(item) => {
+                        results.push(ProcessedFfiItem::Item(RustItem::TraitImpl(item)));
+                    }
+                    Err(err) => {
+                        debug!(
+                            "failed to derive PartialEq from three-way comparison: {}",
+                            err
+                        );
+                    }
+                }
+            }
             if let Some(operator_info) = TraitImplInfo::new(cpp_function) {
                 match State::process_operator_as_trait_impl(
                     unnamed_function.clone(),
@@ -1579,7 +1828,42 @@ impl State<'_, '_> {
             }
         }
 
-        let desired_path = self.generate_rust_path(cpp_path, NameType::ApiFunction(item))?;
+        let desired_path =
+            self.generate_rust_path(cpp_path, NameType::ApiFunction(item.clone()))?;
+        if let CppFfiFunctionKind::Function = &function.kind {
+            let unregister_path = self
+                .data
+                .config
+                .registration_guards()
+                .iter()
+                .find(|(register_path, _)| register_path == cpp_path)
+                .map(|(_, unregister_path)| unregister_path);
+            if let Some(unregister_path) = unregister_path {
+                match self.registration_guard_function(
+                    item,
+                    &unnamed_function,
+                    unregister_path,
+                    checks,
+                ) {
+                    Ok(guard_function) => {
+                        let guard_path = desired_path
+                            .parent()?
+                            .join(format!("{}_guarded", desired_path.last()));
+                        results.push(ProcessedFfiItem::Function(FunctionWithDesiredPath {
+                            function: guard_function,
+                            desired_path: guard_path,
+                        }));
+                    }
+                    Err(err) => {
+                        debug!(
+                            "failed to generate registration guard for {}: {}",
+                            cpp_path.to_cpp_pseudo_code(),
+                            err
+                        );
+                    }
+                }
+            }
+        }
         results.push(ProcessedFfiItem::Function(FunctionWithDesiredPath {
             function: unnamed_function,
             desired_path,
@@ -1587,6 +1871,283 @@ impl State<'_, '_> {
         Ok(results)
     }
 
+    /// Generates a function that calls the registration function `register_function`
+    /// and returns a `cpp_core::Guard` that passes the returned token to
+    /// the matching overload of `unregister_path` when dropped.
+    fn registration_guard_function(
+        &self,
+        item: DbItem<&CppFfiFunction>,
+        register_function: &UnnamedRustFunction,
+        unregister_path: &CppPath,
+        checks: &CppChecks,
+    ) -> Result<UnnamedRustFunction> {
+        let register_ffi_function_path = match &register_function.kind {
+            RustFunctionKind::FfiWrapper(data) => data.ffi_function_path.clone(),
+            _ => bail!("registration function must be an FFI wrapper"),
+        };
+        let token_type = item.item.return_type.ffi_type();
+        if token_type.is_void()
+            || register_function.return_type.conversion() != &RustToFfiTypeConversion::None
+        {
+            bail!("registration function must return a token of a primitive type");
+        }
+        let register_this_type = item
+            .item
+            .arguments
+            .iter()
+            .find(|arg| arg.meaning == CppFfiArgumentMeaning::This)
+            .map(|arg| arg.argument_type.ffi_type().pointer_like_to_target())
+            .transpose()?;
+
+        for unregister_item in self.data.db.ffi_items() {
+            let unregister_function = match unregister_item.item.as_function_ref() {
+                Some(function) => function,
+                None => continue,
+            };
+            let is_matching_path = self
+                .data
+                .db
+                .source_cpp_item(&unregister_item.id)?
+                .and_then(|cpp_item| cpp_item.item.path().cloned())
+                .map_or(false, |path| &path == unregister_path);
+            if !is_matching_path {
+                continue;
+            }
+
+            if !is_matching_unregistration_function(
+                unregister_function,
+                token_type,
+                register_this_type,
+            )? {
+                continue;
+            }
+            if &self.data.db.cpp_checks(&unregister_item.id)? != checks {
+                debug!(
+                    "unregistration function is not available in the same environments: {}",
+                    unregister_item.item.short_text()
+                );
+                continue;
+            }
+
+            let unregister_this_type = unregister_function
+                .arguments
+                .iter()
+                .find(|arg| arg.meaning == CppFfiArgumentMeaning::This)
+                .map(|arg| self.ffi_type_to_rust_ffi_type(arg.argument_type.ffi_type()))
+                .transpose()?;
+            let guard_type = RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("cpp_core::Guard"),
+                generic_arguments: None,
+            });
+            return Ok(UnnamedRustFunction {
+                is_public: true,
+                arguments: register_function.arguments.clone(),
+                return_type: RustFinalType::new(guard_type, RustToFfiTypeConversion::None)?,
+                kind: RustFunctionKind::RegistrationGuard(RustRegistrationGuardData {
+                    register_ffi_function_path,
+                    unregister_ffi_function_path: self
+                        .generate_ffi_function(unregister_function)?
+                        .path,
+                    unregister_this_type,
+                }),
+                is_unsafe: true,
+            });
+        }
+        bail!(
+            "no matching unregistration function found: {}",
+            unregister_path.to_cpp_pseudo_code()
+        );
+    }
+
+    /// Returns true if the source C++ function of `item` was marked with
+    /// `Config::add_chaining_method` (see `is_chaining_method`).
+    fn is_chaining_method(&self, item: DbItem<&CppFfiFunction>) -> Result<bool> {
+        if self.data.config.chaining_methods().is_empty()
+            || item.item.kind != CppFfiFunctionKind::Function
+        {
+            return Ok(false);
+        }
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(&item.id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        Ok(cpp_item.item.as_function_ref().map_or(false, |function| {
+            is_chaining_method(item.item, function, self.data.config.chaining_methods())
+        }))
+    }
+
+    /// Returns true if the source C++ function of `item` was marked with
+    /// `Config::add_success_flag_function`.
+    fn is_success_flag_function(&self, item: DbItem<&CppFfiFunction>) -> Result<bool> {
+        if self.data.config.success_flag_functions().is_empty()
+            || item.item.kind != CppFfiFunctionKind::Function
+        {
+            return Ok(false);
+        }
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(&item.id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        Ok(cpp_item.item.as_function_ref().map_or(false, |function| {
+            self.data
+                .config
+                .success_flag_functions()
+                .contains(&function.path)
+        }))
+    }
+
+    /// Checks if `item` is a getter of a static `const char* const` class field.
+    /// Such fields are assumed to point to strings with static storage duration
+    /// (e.g. string literals). A field that is not `const` itself may be changed
+    /// to point to a temporary string, so it's not treated this way.
+    /// Returns `Some(is_utf8)` for such getters,
+    /// where `is_utf8` is true if the field was marked with
+    /// `Config::add_utf8_string_constant`.
+    fn static_string_constant(&self, item: DbItem<&CppFfiFunction>) -> Result<Option<bool>> {
+        if item.item.kind
+            != (CppFfiFunctionKind::FieldAccessor {
+                accessor_type: CppFieldAccessorType::CopyGetter,
+            })
+        {
+            return Ok(None);
+        }
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(&item.id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        let field = if let Some(field) = cpp_item.item.as_field_ref() {
+            field
+        } else {
+            return Ok(None);
+        };
+        let const_char_ptr =
+            CppType::new_pointer(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Char));
+        if !field.is_static || !field.is_const || field.field_type != const_char_ptr {
+            return Ok(None);
+        }
+        let is_utf8 = self
+            .data
+            .config
+            .utf8_string_constants()
+            .contains(&field.path);
+        Ok(Some(is_utf8))
+    }
+
+    /// Replaces pointer arguments declared as fixed-size arrays in C++
+    /// with slices if their length is passed through an additional FFI argument
+    /// (see `Config::set_array_length_arguments`), or with references to Rust arrays
+    /// if `Config::set_fixed_array_arguments` is enabled.
+    /// `std::initializer_list` arguments always have a length argument,
+    /// so they are replaced with slices as well.
+    fn apply_array_arguments(
+        &self,
+        item: DbItem<&CppFfiFunction>,
+        arguments: &mut [RustFunctionArgument],
+    ) -> Result<()> {
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(&item.id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        let cpp_function = if let Some(function) = cpp_item.item.as_function_ref() {
+            function
+        } else {
+            return Ok(());
+        };
+
+        for arg in arguments {
+            let index = match item.item.arguments[arg.ffi_index].meaning {
+                CppFfiArgumentMeaning::Argument(index) => index,
+                _ => continue,
+            };
+            let has_length = item
+                .item
+                .arguments
+                .iter()
+                .any(|arg| arg.meaning == CppFfiArgumentMeaning::ArrayLength(index));
+            let is_pointer = match &cpp_function.arguments[index].argument_type {
+                CppType::PointerLike { kind, .. } => *kind == CppPointerLikeTypeKind::Pointer,
+                _ => false,
+            };
+            let conversion = if has_length {
+                RustToFfiTypeConversion::SliceToPtr
+            } else if !is_pointer {
+                continue;
+            } else if let Some(size) = cpp_function.fixed_array_argument_size(index) {
+                if !self.data.config.fixed_array_arguments() {
+                    continue;
+                }
+                RustToFfiTypeConversion::ArrayRefToPtr { size }
+            } else {
+                continue;
+            };
+            arg.argument_type =
+                RustFinalType::new(arg.argument_type.ffi_type().clone(), conversion)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces pointer arguments marked with `Config::add_ownership_transfer_argument`
+    /// with `CppBox<T>` arguments that release the ownership when passed to C++.
+    fn apply_ownership_transfer_arguments(
+        &self,
+        item: DbItem<&CppFfiFunction>,
+        arguments: &mut [RustFunctionArgument],
+        checks: &CppChecks,
+    ) -> Result<()> {
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(&item.id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        let cpp_function = if let Some(function) = cpp_item.item.as_function_ref() {
+            function
+        } else {
+            return Ok(());
+        };
+
+        for arg in arguments {
+            let index = match item.item.arguments[arg.ffi_index].meaning {
+                CppFfiArgumentMeaning::Argument(index) => index,
+                _ => continue,
+            };
+            if !is_ownership_transfer_argument(
+                cpp_function,
+                index,
+                self.data.config.ownership_transfer_arguments(),
+            ) {
+                continue;
+            }
+            let ffi_type = item.item.arguments[arg.ffi_index].argument_type.ffi_type();
+            if !self.is_type_deletable(ffi_type, checks)? {
+                debug!(
+                    "ownership transfer argument type is not deletable: {}: {}",
+                    cpp_function.short_text(),
+                    index
+                );
+                continue;
+            }
+            arg.argument_type = RustFinalType::new(
+                arg.argument_type.ffi_type().clone(),
+                RustToFfiTypeConversion::CppBoxToPtr,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Checks if there is an `operator` function that accepts the same operand types
+    /// as `function`. Class member and free function operators are considered
+    /// matching if their operands are the same.
+    fn has_matching_operator(&self, function: &CppFunction, operator: &CppOperator) -> bool {
+        match function.operand_types() {
+            Some(operand_types) => self.operators.contains(&(operator.clone(), operand_types)),
+            None => false,
+        }
+    }
+
     fn find_wrapper_type(&self, cpp_path: &CppPath) -> Result<DbItem<&RustItem>> {
         self.data
             .db
@@ -1752,11 +2313,13 @@ impl State<'_, '_> {
 
                 let name = &field.path.last().name;
                 let function_name = match accessor_type {
-                    CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => {
-                        name.to_string()
-                    }
+                    CppFieldAccessorType::CopyGetter
+                    | CppFieldAccessorType::ConstRefGetter
+                    | CppFieldAccessorType::ArrayPointerGetter => name.to_string(),
                     CppFieldAccessorType::MutRefGetter => format!("{}_mut", name),
-                    CppFieldAccessorType::Setter => format!("set_{}", name),
+                    CppFieldAccessorType::Setter | CppFieldAccessorType::ArrayElementSetter => {
+                        format!("set_{}", name)
+                    }
                 };
                 Some(function_name)
             }
@@ -1842,13 +2405,16 @@ impl State<'_, '_> {
                 .map_if_ok(|item| self.cpp_path_item_to_name(item, &scope.path, &name_type))?
                 .join("_"),
             NameType::ApiFunction(function) => {
-                let s = if let Some(last_name_override) =
+                let mut s = if let Some(last_name_override) =
                     self.special_function_rust_name(function.clone(), &scope.path)?
                 {
                     last_name_override.clone()
                 } else {
                     self.cpp_path_item_to_name(cpp_path.last(), &scope.path, &name_type)?
                 };
+                if function.item.is_heap_variant {
+                    s = format!("{}_boxed", s);
+                }
                 s.to_snake_case()
             }
             NameType::ReceiverFunction { receiver_type } => {
@@ -1928,6 +2494,99 @@ impl State<'_, '_> {
         }
     }
 
+    /// Returns fields of the `#[repr(C)]` Rust struct mirroring the C++ class
+    /// if transparent layout was requested for it and is possible.
+    fn transparent_layout_fields(
+        &self,
+        data: &CppTypeDeclaration,
+    ) -> Result<Option<Vec<RustStructField>>> {
+        if !self
+            .data
+            .config
+            .transparent_layout_types()
+            .contains(&data.path)
+        {
+            return Ok(None);
+        }
+        let path_text = data.path.to_cpp_pseudo_code();
+        let is_pod = match data.kind {
+            CppTypeDeclarationKind::Class { is_pod, .. } => is_pod,
+            CppTypeDeclarationKind::Enum => false,
+        };
+        if !is_pod {
+            debug!(
+                "transparent layout is not possible: not a POD type: {}",
+                path_text
+            );
+            return Ok(None);
+        }
+        let has_bases = self.data.db.all_cpp_items().any(|item| {
+            item.item
+                .as_base_ref()
+                .map_or(false, |base| base.derived_class_type == data.path)
+        });
+        if has_bases {
+            debug!(
+                "transparent layout is not possible: class has bases: {}",
+                path_text
+            );
+            return Ok(None);
+        }
+
+        let mut fields = Vec::new();
+        for item in self.data.db.cpp_items() {
+            let field = if let Some(field) = item.item.as_field_ref() {
+                field
+            } else {
+                continue;
+            };
+            if field.is_static || field.path.parent().ok().as_ref() != Some(&data.path) {
+                continue;
+            }
+            if field.visibility != CppVisibility::Public {
+                debug!(
+                    "transparent layout is not possible: non-public field: {}",
+                    field.path.to_cpp_pseudo_code()
+                );
+                return Ok(None);
+            }
+            match &field.field_type {
+                CppType::BuiltInNumeric(_)
+                | CppType::SpecificNumeric(_)
+                | CppType::PointerSizedInteger { .. } => {}
+                _ => {
+                    debug!(
+                        "transparent layout is not possible: unsupported field type: {}",
+                        field.short_text()
+                    );
+                    return Ok(None);
+                }
+            }
+            let cpp_name = field.path.last().name.clone();
+            fields.push(RustStructField {
+                name: sanitize_rust_identifier(&cpp_name.to_snake_case(), false),
+                field_type: self.ffi_type_to_rust_ffi_type(&field.field_type)?,
+                cpp_name,
+                cpp_type: field.field_type.clone(),
+            });
+        }
+        if fields.is_empty() {
+            debug!(
+                "transparent layout is not possible: no fields: {}",
+                path_text
+            );
+            return Ok(None);
+        }
+        if fields.iter().map(|f| &f.name).unique().count() != fields.len() {
+            debug!(
+                "transparent layout is not possible: field name conflict: {}",
+                path_text
+            );
+            return Ok(None);
+        }
+        Ok(Some(fields))
+    }
+
     #[allow(clippy::useless_let_if_seq)]
     fn process_cpp_class(&self, item: DbItem<&CppTypeDeclaration>) -> Result<Vec<RustItem>> {
         trace!("process_cpp_class: {:?}", item);
@@ -1983,7 +2642,12 @@ impl State<'_, '_> {
         let is_movable = false;
 
         let wrapper_kind;
-        if is_movable {
+        if let Some(fields) = self.transparent_layout_fields(data)? {
+            wrapper_kind = RustWrapperTypeKind::TransparentClassWrapper {
+                cpp_path: data.path.clone(),
+                fields,
+            };
+        } else if is_movable {
             let internal_path = self.generate_rust_path(&data.path, NameType::SizedItem)?;
 
             if internal_path == public_path {
@@ -2068,11 +2732,20 @@ impl State<'_, '_> {
                     Some(&checks),
                 )
             })?;
+            let public_return_type = self.rust_final_type(
+                &wrapper.item.return_type,
+                // the closure passes its result to the slot, so it's converted
+                // in the same way as an argument
+                &CppFfiArgumentMeaning::Argument(0),
+                ReturnValueAllocationPlace::NotApplicable,
+                Some(&checks),
+            )?;
 
             let public_item = RustItem::Struct(RustStruct {
                 is_public: true,
                 kind: RustStructKind::QtSlotWrapper(RustQtSlotWrapper {
                     arguments: public_args,
+                    return_type: public_return_type,
                     raw_slot_wrapper: public_path.clone(),
                 }),
                 path: closure_item_path.clone(),
@@ -2133,7 +2806,9 @@ impl State<'_, '_> {
                     )?;
                     let rust_item = RustItem::Struct(RustStruct {
                         path: rust_path,
-                        kind: RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper),
+                        kind: RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper {
+                            is_flags: self.flag_enums.contains(&data.path),
+                        }),
                         is_public: true,
                         raw_slot_wrapper_data: None,
                     });
@@ -2437,7 +3112,7 @@ impl State<'_, '_> {
     ) -> Result<()> {
         let all_strategies = RustFunctionCaptionStrategy::all();
 
-        for (_group_path, functions) in grouped_functions {
+        for (group_path, functions) in grouped_functions {
             let mut chosen_strategy = None;
             if functions.len() > 1 {
                 trace!("choosing caption strategy for:");
@@ -2468,6 +3143,7 @@ impl State<'_, '_> {
                 }
             }
 
+            let mut final_functions = Vec::new();
             for function in functions {
                 let path = if let Some(strategy) = &chosen_strategy {
                     function.item.apply_strategy(strategy).unwrap()
@@ -2475,8 +3151,379 @@ impl State<'_, '_> {
                     function.item.desired_path
                 };
                 let final_path = self.data.db.make_unique_rust_path(&path);
-                let item = RustItem::Function(function.item.function.with_path(final_path));
-                self.data.db.add_rust_item(Some(function.source_id), item)?;
+                let rust_function = function.item.function.with_path(final_path);
+                let item = RustItem::Function(rust_function.clone());
+                self.data
+                    .db
+                    .add_rust_item(Some(function.source_id.clone()), item)?;
+                final_functions.push(ItemWithSource::new(&function.source_id, rust_function));
+            }
+            if let Err(err) = self.generate_constructor_dispatch(&group_path, &final_functions) {
+                debug!(
+                    "failed to generate constructor dispatch for {}: {}",
+                    group_path.full_name(None),
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the type of an enum variant field holding the value of
+    /// the constructor argument `arg`, or `None` if the argument
+    /// can't be stored in the enum.
+    pub(crate) fn constructor_args_field_type(
+        arg: &RustFunctionArgument,
+    ) -> Result<Option<RustType>> {
+        let field_type = match arg.argument_type.conversion() {
+            RustToFfiTypeConversion::ImplCastInto(conversion) => {
+                RustFinalType::new(arg.argument_type.ffi_type().clone(), (**conversion).clone())?
+                    .api_type()
+                    .clone()
+            }
+            _ => arg.argument_type.api_type().clone(),
+        };
+        match field_type {
+            RustType::ImplTrait(_) | RustType::Closure { .. } => Ok(None),
+            _ if field_type.is_ref() => Ok(None),
+            _ => Ok(Some(field_type)),
+        }
+    }
+
+    /// Generates an enum of constructor arguments and a function dispatching
+    /// on it if `functions` are overloaded constructors of a class marked with
+    /// `Config::add_constructor_enum_class`.
+    fn generate_constructor_dispatch(
+        &mut self,
+        group_path: &RustPath,
+        functions: &[ItemWithSource<RustFunction>],
+    ) -> Result<()> {
+        if self.data.config.constructor_enum_classes().is_empty() || functions.len() < 2 {
+            return Ok(());
+        }
+        let mut class_path = None;
+        let mut variants = Vec::new();
+        let mut return_type = None;
+        let mut is_unsafe = false;
+        for function in functions {
+            let cpp_item = self
+                .data
+                .db
+                .source_cpp_item(&function.source_id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            let cpp_function = match cpp_item.item.as_function_ref() {
+                Some(cpp_function) if cpp_function.is_constructor() => cpp_function,
+                _ => return Ok(()),
+            };
+            let function_class_path = cpp_function.class_path()?;
+            if !self
+                .data
+                .config
+                .constructor_enum_classes()
+                .contains(&function_class_path)
+            {
+                return Ok(());
+            }
+            class_path = Some(function_class_path);
+
+            if !self
+                .data
+                .db
+                .cpp_checks(&function.source_id)?
+                .all_success(self.data.db.environments())
+            {
+                trace!(
+                    "constructor is not available in all environments: {}",
+                    cpp_function.short_text()
+                );
+                continue;
+            }
+            let argument_types = function
+                .item
+                .arguments
+                .iter()
+                .map_if_ok(Self::constructor_args_field_type)?;
+            if argument_types.iter().any(Option::is_none) {
+                trace!(
+                    "constructor arguments can't be stored in enum: {}",
+                    cpp_function.short_text()
+                );
+                continue;
+            }
+            if return_type
+                .as_ref()
+                .map_or(false, |t| t != &function.item.return_type)
+            {
+                bail!("constructors have different return types");
+            }
+            return_type = Some(function.item.return_type.clone());
+            is_unsafe |= function.item.is_unsafe;
+            variants.push(RustConstructorArgsVariant {
+                name: function.item.path.last().to_class_case(),
+                argument_types: argument_types.into_iter().flatten().collect(),
+                function_path: function.item.path.clone(),
+            });
+        }
+
+        let class_path = class_path.ok_or_else(|| err_msg("no constructors"))?;
+        let return_type = match return_type {
+            Some(return_type) if variants.len() > 1 => return_type,
+            _ => bail!("less than two constructors can be stored in enum"),
+        };
+        let class_item_id = self
+            .data
+            .db
+            .cpp_items()
+            .find(|item| {
+                item.item
+                    .as_type_ref()
+                    .map_or(false, |type1| type1.path == class_path)
+            })
+            .ok_or_else(|| err_msg("class not found"))?
+            .id
+            .clone();
+
+        let struct_path = group_path.parent()?;
+        let args_enum_path = self.data.db.make_unique_rust_path(
+            &struct_path
+                .parent()?
+                .join(format!("{}NewArgs", struct_path.last())),
+        );
+        let args_enum = RustItem::Struct(RustStruct {
+            path: args_enum_path.clone(),
+            kind: RustStructKind::ConstructorArgs(RustConstructorArgs {
+                variants: variants.clone(),
+            }),
+            is_public: true,
+            raw_slot_wrapper_data: None,
+        });
+        self.data
+            .db
+            .add_rust_item(Some(class_item_id.clone()), args_enum)?;
+
+        let args_type = RustType::Common(RustCommonType {
+            path: args_enum_path.clone(),
+            generic_arguments: None,
+        });
+        let dispatch_function = RustFunction {
+            is_public: true,
+            is_unsafe,
+            path: self.data.db.make_unique_rust_path(group_path),
+            kind: RustFunctionKind::ConstructorDispatch(RustConstructorDispatchData {
+                args_enum_path,
+                variants,
+            }),
+            arguments: vec![RustFunctionArgument {
+                argument_type: RustFinalType::new(args_type, RustToFfiTypeConversion::None)?,
+                name: "args".to_string(),
+                ffi_index: 0,
+            }],
+            return_type,
+        };
+        self.data
+            .db
+            .add_rust_item(Some(class_item_id), RustItem::Function(dispatch_function))?;
+        Ok(())
+    }
+
+    /// Returns the Rust FFI function generated for the FFI function
+    /// wrapping the C++ function that satisfies `filter`.
+    /// The FFI function must be available in all environments.
+    fn find_variant_ffi_function(
+        &self,
+        filter: impl Fn(&CppFunction) -> bool,
+    ) -> Result<RustFunction> {
+        for ffi_item in self.data.db.ffi_items() {
+            let ffi_function = if let Some(ffi_function) = ffi_item.item.as_function_ref() {
+                ffi_function
+            } else {
+                continue;
+            };
+            let cpp_item = self
+                .data
+                .db
+                .source_cpp_item(&ffi_item.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            if !cpp_item.item.as_function_ref().map_or(false, &filter) {
+                continue;
+            }
+            if !self
+                .data
+                .db
+                .cpp_checks(&ffi_item.id)?
+                .all_success(self.data.db.environments())
+            {
+                bail!(
+                    "function is not available in all environments: {}",
+                    ffi_item.item.short_text()
+                );
+            }
+            return self.generate_ffi_function(ffi_function);
+        }
+        bail!("ffi function not found");
+    }
+
+    /// Generates a Rust enum with a variant for each alternative of
+    /// a C++ `std::variant` type wrapped by `struct_path` and a `to_enum`
+    /// method converting the variant value to the enum.
+    fn generate_variant_enum(
+        &mut self,
+        class_path: &CppPath,
+        struct_path: &RustPath,
+    ) -> Result<()> {
+        let class_type = CppType::Class(class_path.clone());
+        let alternatives = class_type
+            .variant_alternatives()
+            .ok_or_else(|| err_msg("not a std::variant type"))?;
+        let class_ptr_type = CppType::new_pointer(true, class_type.clone());
+
+        let index_path = class_path.join(CppPathItem::from_good_str("index"));
+        let index_function = self.find_variant_ffi_function(|function| {
+            function.path == index_path && function.arguments.is_empty()
+        })?;
+
+        let context = struct_path.parent()?;
+        let mut rust_alternatives = Vec::new();
+        for alternative in alternatives {
+            let get_if_path = CppPath::from_good_str("std").join(CppPathItem {
+                name: "get_if".into(),
+                template_arguments: Some(vec![alternative.clone()]),
+            });
+            let get_if_function = self.find_variant_ffi_function(|function| {
+                function.path == get_if_path
+                    && function.arguments.len() == 1
+                    && function.arguments[0].argument_type == class_ptr_type
+            })?;
+            let target_type = get_if_function
+                .return_type
+                .ffi_type()
+                .pointer_like_to_target()?;
+            let name = target_type
+                .caption(&context, RustTypeCaptionStrategy::LastName)?
+                .to_class_case();
+            if rust_alternatives
+                .iter()
+                .any(|a: &RustVariantAlternative| a.name == name)
+            {
+                bail!("alternatives have the same name: {}", name);
+            }
+            rust_alternatives.push(RustVariantAlternative {
+                name,
+                value_type: RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("cpp_core::Ref"),
+                    generic_arguments: Some(vec![target_type]),
+                }),
+                get_if_ffi_function_path: get_if_function.path,
+            });
+        }
+
+        let class_item_id = self
+            .data
+            .db
+            .cpp_items()
+            .find(|item| {
+                item.item
+                    .as_type_ref()
+                    .map_or(false, |type1| &type1.path == class_path)
+            })
+            .ok_or_else(|| err_msg("class not found"))?
+            .id
+            .clone();
+
+        let enum_path = self
+            .data
+            .db
+            .make_unique_rust_path(&context.join(format!("{}Enum", struct_path.last())));
+        let rust_enum = RustItem::Struct(RustStruct {
+            path: enum_path.clone(),
+            kind: RustStructKind::VariantEnum(RustVariantEnum {
+                variant_type_path: struct_path.clone(),
+                alternatives: rust_alternatives.clone(),
+            }),
+            is_public: true,
+            raw_slot_wrapper_data: None,
+        });
+        self.data
+            .db
+            .add_rust_item(Some(class_item_id.clone()), rust_enum)?;
+
+        let self_type = self.ffi_type_to_rust_ffi_type(&class_ptr_type)?;
+        let enum_type = RustType::Common(RustCommonType {
+            path: enum_path.clone(),
+            generic_arguments: None,
+        });
+        let function = RustFunction {
+            is_public: true,
+            is_unsafe: true,
+            path: self
+                .data
+                .db
+                .make_unique_rust_path(&struct_path.join("to_enum")),
+            kind: RustFunctionKind::VariantToEnum(RustVariantToEnumData {
+                enum_path,
+                index_ffi_function_path: index_function.path,
+                alternatives: rust_alternatives,
+            }),
+            arguments: vec![RustFunctionArgument {
+                argument_type: RustFinalType::new(
+                    self_type,
+                    RustToFfiTypeConversion::RefToPtr {
+                        force_api_is_const: None,
+                        lifetime: None,
+                    },
+                )?,
+                name: "self".to_string(),
+                ffi_index: 0,
+            }],
+            return_type: RustFinalType::new(
+                RustType::new_option(enum_type),
+                RustToFfiTypeConversion::None,
+            )?,
+        };
+        self.data
+            .db
+            .add_rust_item(Some(class_item_id), RustItem::Function(function))?;
+        Ok(())
+    }
+
+    /// Generates Rust enums for all `std::variant` types of the current crate.
+    fn generate_variant_enums(&mut self) -> Result<()> {
+        let variant_paths = self
+            .data
+            .db
+            .cpp_items()
+            .filter_map(|item| item.item.as_type_ref())
+            .filter(|type1| {
+                CppType::Class(type1.path.clone())
+                    .variant_alternatives()
+                    .is_some()
+            })
+            .map(|type1| type1.path.clone())
+            .collect_vec();
+
+        for class_path in variant_paths {
+            let struct_path = self
+                .data
+                .db
+                .find_rust_items_for_cpp_path(&class_path, false)?
+                .filter_map(|item| item.item.as_struct_ref())
+                .find(|rust_struct| rust_struct.kind.is_wrapper_type())
+                .map(|rust_struct| rust_struct.path.clone());
+            let struct_path = if let Some(path) = struct_path {
+                path
+            } else {
+                debug!(
+                    "no rust wrapper for variant type: {}",
+                    class_path.to_cpp_pseudo_code()
+                );
+                continue;
+            };
+            if let Err(err) = self.generate_variant_enum(&class_path, &struct_path) {
+                debug!(
+                    "failed to generate enum for variant type {}: {}",
+                    class_path.to_cpp_pseudo_code(),
+                    err
+                );
             }
         }
         Ok(())
@@ -2484,9 +3531,22 @@ impl State<'_, '_> {
 }
 
 pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
+    let operators = data
+        .db
+        .cpp_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter_map(|function| Some((function.operator.clone()?, function.operand_types()?)))
+        .collect();
+    let flag_enums = enum_groups(data.db)
+        .into_iter()
+        .filter(|group| group.kind == CppEnumKind::Flags)
+        .map(|group| group.enum_path)
+        .collect();
     let mut state = State {
         data,
         special_module_paths: HashMap::new(),
+        operators,
+        flag_enums,
     };
     for &module in &[
         RustSpecialModuleKind::CrateRoot,
@@ -2512,6 +3572,7 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     state.process_cpp_items()?;
     let grouped_functions = state.process_ffi_items()?;
     state.finalize_functions(grouped_functions)?;
+    state.generate_variant_enums()?;
 
     Ok(())
 }
@@ -2618,6 +3679,7 @@ fn operator_function_name(operator: &CppOperator) -> Result<&'static str> {
         LessThan => "lt",
         GreaterThanOrEqualTo => "ge",
         LessThanOrEqualTo => "le",
+        ThreeWayComparison => "cmp",
         LogicalNot => "not",
         LogicalAnd => "and",
         LogicalOr => "or",
@@ -24,14 +24,38 @@ pub struct RustEnumValue {
 pub struct RustQtSlotWrapper {
     /// Argument types of the slot
     pub arguments: Vec<RustFinalType>,
+    /// Return type of the closure (unit for void slots)
+    pub return_type: RustFinalType,
     pub raw_slot_wrapper: RustPath,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustWrapperTypeKind {
-    EnumWrapper,
+    EnumWrapper {
+        /// True if values of the enum are distinct bits
+        /// (see `CppEnumKind::Flags`).
+        is_flags: bool,
+    },
     ImmovableClassWrapper,
-    MovableClassWrapper { sized_type_path: RustPath },
+    MovableClassWrapper {
+        sized_type_path: RustPath,
+    },
+    /// A `#[repr(C)]` struct with the same fields as the C++ class.
+    TransparentClassWrapper {
+        cpp_path: CppPath,
+        fields: Vec<RustStructField>,
+    },
+}
+
+/// A field of a Rust struct mirroring the layout of a C++ class
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustStructField {
+    pub name: String,
+    pub field_type: RustType,
+    /// Name of the C++ field
+    pub cpp_name: String,
+    /// Type of the C++ field
+    pub cpp_type: CppType,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -39,12 +63,53 @@ pub struct RustSizedType {
     pub cpp_path: CppPath,
 }
 
+/// One variant of an enum of constructor arguments
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustConstructorArgsVariant {
+    /// Name of the variant.
+    pub name: String,
+    /// Types of the variant's fields, in order of the constructor's arguments.
+    pub argument_types: Vec<RustType>,
+    /// Rust wrapper of the constructor called for this variant.
+    pub function_path: RustPath,
+}
+
+/// Information about an enum that holds arguments for
+/// one of the overloaded constructors of a class
+/// (see `Config::add_constructor_enum_class`).
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustConstructorArgs {
+    pub variants: Vec<RustConstructorArgsVariant>,
+}
+
+/// One alternative of a C++ `std::variant` type
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustVariantAlternative {
+    /// Name of the Rust enum variant.
+    pub name: String,
+    /// Type of the Rust enum variant's field (`cpp_core::Ref<T>`).
+    pub value_type: RustType,
+    /// Rust FFI function wrapping `std::get_if` for this alternative.
+    pub get_if_ffi_function_path: RustPath,
+}
+
+/// Information about a Rust enum with a variant for each alternative
+/// of a C++ `std::variant` type.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustVariantEnum {
+    /// Path of the wrapper of the `std::variant` type.
+    pub variant_type_path: RustPath,
+    pub alternatives: Vec<RustVariantAlternative>,
+}
+
 /// Information about a Rust type wrapper
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustStructKind {
     WrapperType(RustWrapperTypeKind),
     QtSlotWrapper(RustQtSlotWrapper),
     SizedType(RustSizedType),
+    ConstructorArgs(RustConstructorArgs),
+    VariantEnum(RustVariantEnum),
 }
 
 impl RustStructKind {
@@ -86,6 +151,20 @@ impl RustStructKind {
                     false
                 }
             }
+            RustStructKind::ConstructorArgs(_) => {
+                if let RustStructKind::ConstructorArgs(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
+            RustStructKind::VariantEnum(_) => {
+                if let RustStructKind::VariantEnum(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -142,6 +221,42 @@ pub struct RustFfiWrapperData {
     pub ffi_function_path: RustPath,
 }
 
+/// Information about a function that calls a C++ registration function
+/// and returns a `cpp_core::Guard` that calls the corresponding
+/// unregistration function when dropped.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustRegistrationGuardData {
+    /// Rust FFI function wrapping the registration function.
+    pub register_ffi_function_path: RustPath,
+    /// Rust FFI function wrapping the unregistration function.
+    pub unregister_ffi_function_path: RustPath,
+    /// FFI type of the `this` argument of the unregistration function,
+    /// or `None` if it's not a method.
+    pub unregister_this_type: Option<RustType>,
+}
+
+/// Information about a function that accepts an enum of constructor arguments
+/// and calls the constructor wrapper matching the passed variant.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustConstructorDispatchData {
+    /// Path of the enum of constructor arguments.
+    pub args_enum_path: RustPath,
+    /// Variants of the enum.
+    pub variants: Vec<RustConstructorArgsVariant>,
+}
+
+/// Information about a function that converts a C++ `std::variant` value
+/// to a Rust enum holding a reference to its active alternative.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustVariantToEnumData {
+    /// Path of the Rust enum.
+    pub enum_path: RustPath,
+    /// Rust FFI function wrapping `std::variant::index`.
+    pub index_ffi_function_path: RustPath,
+    /// Alternatives in the order of the variant's template arguments.
+    pub alternatives: Vec<RustVariantAlternative>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct RustSignalOrSlotGetter {
     /// Type of the receiver.
@@ -156,6 +271,9 @@ pub enum RustFunctionKind {
     FfiWrapper(RustFfiWrapperData),
     SignalOrSlotGetter(RustSignalOrSlotGetter),
     FfiFunction,
+    RegistrationGuard(RustRegistrationGuardData),
+    ConstructorDispatch(RustConstructorDispatchData),
+    VariantToEnum(RustVariantToEnumData),
 }
 
 impl RustFunctionKind {
@@ -166,6 +284,16 @@ impl RustFunctionKind {
             }
             RustFunctionKind::SignalOrSlotGetter(_) => "SignalOrSlotGetter".to_string(),
             RustFunctionKind::FfiFunction => "FfiFunction".to_string(),
+            RustFunctionKind::RegistrationGuard(data) => format!(
+                "RegistrationGuard({})",
+                data.register_ffi_function_path.last()
+            ),
+            RustFunctionKind::ConstructorDispatch(data) => {
+                format!("ConstructorDispatch({})", data.args_enum_path.last())
+            }
+            RustFunctionKind::VariantToEnum(data) => {
+                format!("VariantToEnum({})", data.enum_path.last())
+            }
         }
     }
 
@@ -732,6 +860,39 @@ impl RustItem {
                         false
                     }
                 }
+                RustFunctionKind::RegistrationGuard(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::RegistrationGuard(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
+                RustFunctionKind::ConstructorDispatch(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::ConstructorDispatch(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
+                RustFunctionKind::VariantToEnum(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::VariantToEnum(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
             },
             RustItem::Reexport(data) => {
                 if let RustItem::Reexport(other) = other {
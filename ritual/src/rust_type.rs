@@ -1,6 +1,7 @@
+use crate::cpp_ffi_data::DurationPeriod;
 use crate::rust_info::RustTypeCaptionStrategy;
 use itertools::Itertools;
-use ritual_common::errors::{bail, Error, Result};
+use ritual_common::errors::{bail, err_msg, Error, Result};
 use ritual_common::string_utils::CaseOperations;
 use ritual_common::utils::MapIfOk;
 use serde_derive::{Deserialize, Serialize};
@@ -162,6 +163,30 @@ pub enum RustToFfiTypeConversion {
     QFlagsToUInt {
         api_type: RustType,
     },
+    /// `std::cmp::Ordering` to `c_int` (-1, 0 or 1)
+    OrderingToInt,
+    /// `std::time::Duration` to the tick count of a C++ duration type
+    DurationToTicks {
+        period: DurationPeriod,
+    },
+    /// `bool` (true if compared values are equal) to
+    /// `c_int` result of a three-way comparison
+    OrderingEqToInt,
+    /// `Result<(), ()>` (`Ok` if the C++ function succeeded) to `bool`
+    BoolToResult,
+    /// `Option<&'static CStr>` (or `Option<&'static str>` if `is_utf8` is true)
+    /// to `*const c_char` pointing to a string with static storage duration
+    /// (`None` if the pointer is null)
+    StaticStrToPtr {
+        is_utf8: bool,
+    },
+    /// `&[T; N]` to `*const T` (or similar mutable types)
+    ArrayRefToPtr {
+        size: usize,
+    },
+    /// `&[T]` to `*const T` (or similar mutable types) and an additional
+    /// length FFI argument (see `CppFfiArgumentMeaning::ArrayLength`)
+    SliceToPtr,
     /// `()` to any type
     UnitToAnything,
     /// Primitive to another primitive using `as`
@@ -170,6 +195,16 @@ pub enum RustToFfiTypeConversion {
     },
     /// Rust public type has an additional reference (`&`)
     RefTo(Box<RustToFfiTypeConversion>),
+    /// `Option<T>` to the value of the FFI return type and an additional
+    /// `*mut bool` FFI argument indicating presence of the value
+    OptionalToValue(Box<RustToFfiTypeConversion>),
+    /// `Result<T, cpp_core::CppException>` to the value of the FFI return type
+    /// and an additional exception message buffer FFI argument
+    /// (see `CppFfiArgumentMeaning::ExceptionOutput`)
+    ExceptionToResult(Box<RustToFfiTypeConversion>),
+    /// Closure to an `extern "C"` callback (`Option<extern "C" fn(..., *mut c_void)>`)
+    /// and an additional user data FFI argument (see `CppFfiArgumentMeaning::Callback`)
+    ClosureToCallback,
     ImplCastInto(Box<RustToFfiTypeConversion>),
 }
 
@@ -285,11 +320,111 @@ impl RustFinalType {
             }
             RustToFfiTypeConversion::QFlagsToUInt { api_type }
             | RustToFfiTypeConversion::AsCast { api_type } => api_type.clone(),
+            RustToFfiTypeConversion::OrderingToInt => RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::cmp::Ordering"),
+                generic_arguments: None,
+            }),
+            RustToFfiTypeConversion::OrderingEqToInt => RustType::bool(),
+            RustToFfiTypeConversion::BoolToResult => RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::result::Result"),
+                generic_arguments: Some(vec![RustType::unit(), RustType::unit()]),
+            }),
+            RustToFfiTypeConversion::StaticStrToPtr { is_utf8 } => {
+                let target = if *is_utf8 {
+                    RustType::Primitive("str".to_string())
+                } else {
+                    RustType::Common(RustCommonType {
+                        path: RustPath::from_good_str("std::ffi::CStr"),
+                        generic_arguments: None,
+                    })
+                };
+                RustType::new_option(
+                    RustType::new_reference(true, target).with_lifetime("static".to_string()),
+                )
+            }
+            RustToFfiTypeConversion::DurationToTicks { .. } => RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::time::Duration"),
+                generic_arguments: None,
+            }),
+            RustToFfiTypeConversion::ArrayRefToPtr { size } => {
+                if let RustType::PointerLike {
+                    kind: RustPointerLikeTypeKind::Pointer,
+                    is_const,
+                    target,
+                } = &ffi_type
+                {
+                    RustType::new_reference(
+                        *is_const,
+                        RustType::Array {
+                            element: target.clone(),
+                            size: *size,
+                        },
+                    )
+                } else {
+                    bail!("not a pointer type");
+                }
+            }
+            RustToFfiTypeConversion::SliceToPtr => {
+                if let RustType::PointerLike {
+                    kind: RustPointerLikeTypeKind::Pointer,
+                    is_const,
+                    target,
+                } = &ffi_type
+                {
+                    RustType::new_reference(
+                        *is_const,
+                        RustType::Slice {
+                            element: target.clone(),
+                        },
+                    )
+                } else {
+                    bail!("not a pointer type");
+                }
+            }
             RustToFfiTypeConversion::UnitToAnything => RustType::unit(),
             RustToFfiTypeConversion::RefTo(conversion) => {
                 let intermediate = RustFinalType::new(ffi_type.clone(), (**conversion).clone())?;
                 RustType::new_reference(true, intermediate.api_type)
             }
+            RustToFfiTypeConversion::OptionalToValue(conversion) => {
+                let intermediate = RustFinalType::new(ffi_type.clone(), (**conversion).clone())?;
+                RustType::new_option(intermediate.api_type)
+            }
+            RustToFfiTypeConversion::ExceptionToResult(conversion) => {
+                let intermediate = RustFinalType::new(ffi_type.clone(), (**conversion).clone())?;
+                RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("std::result::Result"),
+                    generic_arguments: Some(vec![
+                        intermediate.api_type,
+                        RustType::Common(RustCommonType {
+                            path: RustPath::from_good_str("cpp_core::CppException"),
+                            generic_arguments: None,
+                        }),
+                    ]),
+                })
+            }
+            RustToFfiTypeConversion::ClosureToCallback => {
+                let function_pointer = ffi_type
+                    .as_common()?
+                    .generic_arguments
+                    .iter()
+                    .flatten()
+                    .next()
+                    .ok_or_else(|| err_msg("expected Option<fn> for ClosureToCallback"))?;
+                if let RustType::FunctionPointer {
+                    return_type,
+                    arguments,
+                } = function_pointer
+                {
+                    // the last argument is the user data
+                    RustType::Closure {
+                        return_type: return_type.clone(),
+                        arguments: arguments[..arguments.len().saturating_sub(1)].to_vec(),
+                    }
+                } else {
+                    bail!("expected function pointer for ClosureToCallback");
+                }
+            }
             RustToFfiTypeConversion::ImplCastInto(conversion) => {
                 let intermediate = RustFinalType::new(ffi_type.clone(), (**conversion).clone())?;
                 let trait_type = RustCommonType {
@@ -465,6 +600,25 @@ pub enum RustType {
         target: Box<RustType>,
     },
     ImplTrait(RustCommonType),
+    /// A closure type (`impl FnMut(arguments) -> return_type + Send + 'static`).
+    Closure {
+        /// Return type of the closure.
+        return_type: Box<RustType>,
+        /// Argument types of the closure.
+        arguments: Vec<RustType>,
+    },
+    /// A fixed-size array type (`[T; N]`).
+    Array {
+        /// Type of the array's items.
+        element: Box<RustType>,
+        /// Number of items.
+        size: usize,
+    },
+    /// A slice type (`[T]`). Only used as the target of a reference.
+    Slice {
+        /// Type of the slice's items.
+        element: Box<RustType>,
+    },
 }
 
 impl RustType {
@@ -593,6 +747,11 @@ impl RustType {
                 name
             }
             RustType::FunctionPointer { .. } => "fn".to_string(),
+            RustType::Closure { .. } => "closure".to_string(),
+            RustType::Array { element, size } => {
+                format!("array_{}_{}", size, element.caption(context, strategy)?)
+            }
+            RustType::Slice { element } => format!("slice_{}", element.caption(context, strategy)?),
             RustType::ImplTrait(trait_type) => {
                 if trait_type.path == RustPath::from_good_str("cpp_core::CastInto") {
                     trait_type
@@ -687,7 +846,10 @@ impl RustType {
                 return_type.is_unsafe_argument()
                     || arguments.iter().any(RustType::is_unsafe_argument)
             }
-            RustType::ImplTrait(_) => true,
+            RustType::ImplTrait(_) | RustType::Closure { .. } => true,
+            RustType::Array { element, .. } | RustType::Slice { element } => {
+                element.is_unsafe_argument()
+            }
         }
     }
 
@@ -794,6 +956,46 @@ impl RustType {
                     false
                 }
             }
+            RustType::Closure {
+                return_type: self_return_type,
+                arguments: self_arguments,
+            } => {
+                if let RustType::Closure {
+                    return_type,
+                    arguments,
+                } = other
+                {
+                    self_return_type.can_be_same_as(return_type)
+                        && self_arguments.len() == arguments.len()
+                        && self_arguments
+                            .iter()
+                            .zip(arguments)
+                            .all(|(a, b)| a.can_be_same_as(b))
+                } else {
+                    false
+                }
+            }
+            RustType::Array { element, size } => {
+                if let RustType::Array {
+                    element: other_element,
+                    size: other_size,
+                } = other
+                {
+                    size == other_size && element.can_be_same_as(other_element)
+                } else {
+                    false
+                }
+            }
+            RustType::Slice { element } => {
+                if let RustType::Slice {
+                    element: other_element,
+                } = other
+                {
+                    element.can_be_same_as(other_element)
+                } else {
+                    false
+                }
+            }
         }
     }
 }
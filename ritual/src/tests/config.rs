@@ -0,0 +1,133 @@
+use crate::config::{
+    Config, CrateDependencyKind, CrateDependencySource, CrateProperties, UnresolvedConfigPath,
+};
+use crate::cpp_data::{CppItem, CppPath, CppTypeDeclaration, CppTypeDeclarationKind};
+use crate::cpp_type::{CppBuiltInNumericType, CppType};
+use crate::database::{Database, DatabaseClient, IndexedDatabase};
+use crate::tests::cpp_method::empty_regular_method;
+use ritual_common::{FeatureMacro, ReadOnly};
+use std::path::PathBuf;
+
+#[test]
+fn unresolved_paths() {
+    let mut db = DatabaseClient::new(
+        IndexedDatabase::new(Database::empty("ritual_test_config".into()), PathBuf::new()),
+        ReadOnly::new(Vec::new()),
+    );
+    db.add_cpp_item(
+        None,
+        CppItem::Type(CppTypeDeclaration {
+            path: CppPath::from_good_str("ns::Class1"),
+            kind: CppTypeDeclarationKind::Class {
+                is_pod: false,
+                is_polymorphic: false,
+            },
+        }),
+    )
+    .unwrap();
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("ns::func1");
+    db.add_cpp_item(None, CppItem::Function(function)).unwrap();
+
+    let mut config = Config::new(CrateProperties::new("ritual_test_config", "0.0.0"));
+    assert!(config.unresolved_paths(&db).is_empty());
+
+    config.add_thread_safe_function(CppPath::from_good_str("ns::func1"));
+    config.add_thread_safe_function(CppPath::from_good_str("ns::func2"));
+    config.add_success_flag_function(CppPath::from_good_str("ns::func1"));
+    config.add_success_flag_function(CppPath::from_good_str("ns::func3"));
+    config.add_constructor_enum_class(CppPath::from_good_str("ns::Class1"));
+    config.add_constructor_enum_class(CppPath::from_good_str("ns::Class3"));
+    config.add_utf8_string_constant(CppPath::from_good_str("ns::Class1::NAME"));
+    config.add_benchmark_function(CppPath::from_good_str("ns::func1"));
+    config.add_benchmark_function(CppPath::from_good_str("ns::func4"));
+    config.add_return_type_template_instantiation(
+        CppPath::from_good_str("ns::func1"),
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
+    );
+    config.add_return_type_template_instantiation(
+        CppPath::from_good_str("ns::func5"),
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
+    );
+    config.add_feature_macro(
+        FeatureMacro::new("RITUAL_F1", "f1"),
+        vec![
+            CppPath::from_good_str("ns"),
+            CppPath::from_good_str("ns::Class2"),
+        ],
+    );
+    assert_eq!(
+        config.unresolved_paths(&db),
+        vec![
+            UnresolvedConfigPath {
+                setting: "add_thread_safe_function",
+                path: CppPath::from_good_str("ns::func2"),
+            },
+            UnresolvedConfigPath {
+                setting: "add_success_flag_function",
+                path: CppPath::from_good_str("ns::func3"),
+            },
+            UnresolvedConfigPath {
+                setting: "add_constructor_enum_class",
+                path: CppPath::from_good_str("ns::Class3"),
+            },
+            UnresolvedConfigPath {
+                setting: "add_utf8_string_constant",
+                path: CppPath::from_good_str("ns::Class1::NAME"),
+            },
+            UnresolvedConfigPath {
+                setting: "add_benchmark_function",
+                path: CppPath::from_good_str("ns::func4"),
+            },
+            UnresolvedConfigPath {
+                setting: "add_return_type_template_instantiation",
+                path: CppPath::from_good_str("ns::func5"),
+            },
+            UnresolvedConfigPath {
+                setting: "add_feature_macro",
+                path: CppPath::from_good_str("ns::Class2"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn git_dependencies() {
+    let mut properties = CrateProperties::new("ritual_test_config", "0.0.0");
+    let source = CrateDependencySource::Git {
+        url: "https://example.com/repo.git".into(),
+        rev: "0123abc".into(),
+    };
+    properties
+        .add_dependency("dep1", CrateDependencyKind::Normal, source.clone())
+        .unwrap();
+    properties
+        .add_build_dependency("dep2", source.clone())
+        .unwrap();
+    // the database of a ritual dependency can't be fetched from git
+    assert!(properties
+        .add_dependency("dep3", CrateDependencyKind::Ritual, source)
+        .is_err());
+    assert_eq!(properties.dependencies().len(), 1);
+    assert_eq!(properties.build_dependencies().len(), 1);
+}
+
+#[test]
+fn include_directives() {
+    let mut config = Config::new(CrateProperties::new("ritual_test_config", "0.0.0"));
+    config.add_include_directive("a.h");
+    config.add_system_include_directive("QtCore/QObject");
+    config.add_include_directive("b.h");
+    assert_eq!(
+        config.include_directives(),
+        &[
+            PathBuf::from("a.h"),
+            PathBuf::from("QtCore/QObject"),
+            PathBuf::from("b.h")
+        ]
+    );
+    assert_eq!(
+        config.include_directives_code().unwrap(),
+        "#include \"a.h\"\n#include <QtCore/QObject>\n#include \"b.h\""
+    );
+}
@@ -0,0 +1,90 @@
+use crate::cpp_checks::{feature_condition, item_feature_condition, Condition};
+use crate::cpp_data::{CppItem, CppPath};
+use crate::cpp_function::CppFunctionArgument;
+use crate::cpp_type::CppType;
+use crate::tests::cpp_method::empty_regular_method;
+use ritual_common::FeatureMacro;
+
+#[test]
+fn condition_and() {
+    let version = Condition::CppLibraryVersion("5.11.0".into());
+    assert_eq!(Condition::True.and(version.clone()), version);
+    assert_eq!(version.clone().and(Condition::True), version);
+    assert_eq!(version.clone().and(Condition::False), Condition::False);
+    assert_eq!(
+        version
+            .clone()
+            .and(Condition::Not(Box::new(Condition::False))),
+        Condition::And(vec![version, Condition::Not(Box::new(Condition::False))])
+    );
+}
+
+#[test]
+fn feature_condition_by_path() {
+    let ssl = FeatureMacro::new("RITUAL_SSL", "ssl");
+    let feature_macros = vec![(ssl.clone(), vec![CppPath::from_good_str("QSslSocket")])];
+
+    assert_eq!(
+        feature_condition(&CppPath::from_good_str("QSslSocket"), &feature_macros),
+        Condition::Feature(ssl.clone())
+    );
+    assert_eq!(
+        feature_condition(
+            &CppPath::from_good_str("QSslSocket::encrypt"),
+            &feature_macros
+        ),
+        Condition::Feature(ssl)
+    );
+    assert_eq!(
+        feature_condition(&CppPath::from_good_str("QSslSocketX"), &feature_macros),
+        Condition::True
+    );
+    assert_eq!(
+        feature_condition(&CppPath::from_good_str("QTcpSocket"), &feature_macros),
+        Condition::True
+    );
+}
+
+#[test]
+fn feature_env_var_name() {
+    let feature = FeatureMacro::new("RITUAL_NETWORK_PROXY", "network-proxy");
+    assert_eq!(feature.cargo_env_var_name(), "CARGO_FEATURE_NETWORK_PROXY");
+}
+
+#[test]
+fn feature_condition_by_involved_types() {
+    let ssl = FeatureMacro::new("RITUAL_SSL", "ssl");
+    let feature_macros = vec![(ssl.clone(), vec![CppPath::from_good_str("QSslSocket")])];
+
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("QNetworkReply::ignoreSslErrors");
+    assert_eq!(
+        item_feature_condition(&CppItem::Function(method.clone()), &feature_macros),
+        Condition::True
+    );
+
+    method.arguments.push(CppFunctionArgument {
+        argument_type: CppType::new_pointer(
+            false,
+            CppType::Class(CppPath::from_good_str("QSslSocket")),
+        ),
+        name: "socket".into(),
+        has_default_value: false,
+    });
+    assert_eq!(
+        item_feature_condition(&CppItem::Function(method), &feature_macros),
+        Condition::Feature(ssl)
+    );
+}
+
+#[test]
+fn negated_feature_macro() {
+    let feature = FeatureMacro::new("RITUAL_SSL", "ssl");
+    assert!(feature.is_cpp_macro_defined(true));
+    assert!(!feature.is_cpp_macro_defined(false));
+
+    let feature = FeatureMacro::negated("QT_NO_SSL", "ssl");
+    assert!(feature.is_negated);
+    assert!(!feature.is_cpp_macro_defined(true));
+    assert!(feature.is_cpp_macro_defined(false));
+}
@@ -0,0 +1,661 @@
+use crate::cpp_code_generator::{
+    arguments_values, array_length_checks, c_function_signature, c_type_code, cast_argument,
+    catch_exceptions, convert_slot_return_value, cpp_file_names, duration_period_checks,
+    function_signature, owned_argument_guards, partition, pointer_sized_integer_size_check,
+    protected_shim, transparent_layout_checks, with_initializer_lists,
+};
+use crate::cpp_data::{CppItem, CppPath, CppPathItem, CppVisibility};
+use crate::cpp_ffi_data::*;
+use crate::cpp_ffi_generator::{
+    add_array_length_arguments, apply_duration_types, apply_initializer_list_arguments,
+    to_ffi_method, FfiNameProvider, NewFfiFunctionKind,
+};
+use crate::cpp_function::{
+    CppCallingConvention, CppFunction, CppFunctionArgument, CppFunctionKind, CppFunctionMemberData,
+    CppFunctionPurity, ReturnValueAllocationPlace,
+};
+use crate::cpp_type::*;
+use crate::rust_info::RustStructField;
+use crate::rust_type::{RustCommonType, RustPath, RustType};
+use crate::tests::cpp_method::{empty_membership, empty_regular_method};
+use std::collections::BTreeSet;
+
+fn constructor_with_value_argument(is_ffi_const: bool) -> CppFfiFunction {
+    let class_type = CppType::Class(CppPath::from_good_str("Class1"));
+    CppFfiFunction {
+        arguments: vec![
+            CppFfiFunctionArgument {
+                name: "arg1".to_string(),
+                argument_type: CppFfiType::new(
+                    CppType::Class(CppPath::from_good_str("Movable1")),
+                    CppToFfiTypeConversion::ValueToPointer { is_ffi_const },
+                )
+                .unwrap(),
+                meaning: CppFfiArgumentMeaning::Argument(0),
+            },
+            CppFfiFunctionArgument {
+                name: "arg2".to_string(),
+                argument_type: CppFfiType::new(
+                    CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+                    CppToFfiTypeConversion::NoChange,
+                )
+                .unwrap(),
+                meaning: CppFfiArgumentMeaning::Argument(1),
+            },
+            CppFfiFunctionArgument {
+                name: "output".to_string(),
+                argument_type: CppFfiType::new(
+                    class_type,
+                    CppToFfiTypeConversion::ValueToPointer {
+                        is_ffi_const: false,
+                    },
+                )
+                .unwrap(),
+                meaning: CppFfiArgumentMeaning::ReturnValue,
+            },
+        ],
+        return_type: CppFfiType::void(),
+        allocation_place: ReturnValueAllocationPlace::Stack,
+        path: CppPath::from_good_str("ctr_test_ffi_Class1_Class1"),
+        kind: CppFfiFunctionKind::Function,
+        is_thread_safe: false,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_heap_variant: false,
+    }
+}
+
+#[test]
+fn constructor_moves_owned_value_argument() {
+    let function = constructor_with_value_argument(false);
+    assert_eq!(
+        arguments_values(&function, true).unwrap(),
+        "std::move(*arg1), arg2"
+    );
+}
+
+#[test]
+fn constructor_copies_borrowed_value_argument() {
+    let function = constructor_with_value_argument(true);
+    assert_eq!(arguments_values(&function, true).unwrap(), "*arg1, arg2");
+}
+
+#[test]
+fn move_constructor_moves_rvalue_reference_argument() {
+    let mut function = constructor_with_value_argument(false);
+    function.arguments[0].argument_type = CppFfiType::new(
+        CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::RValueReference,
+            is_const: false,
+            target: Box::new(CppType::Class(CppPath::from_good_str("Movable1"))),
+        },
+        CppToFfiTypeConversion::RValueReferenceToPointer,
+    )
+    .unwrap();
+    assert_eq!(
+        function.arguments[0].argument_type.ffi_type(),
+        &CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("Movable1")))
+    );
+    assert_eq!(
+        arguments_values(&function, true).unwrap(),
+        "std::move(*arg1), arg2"
+    );
+    assert_eq!(
+        owned_argument_guards(&function, true).unwrap(),
+        "std::unique_ptr<Movable1> arg1_owner(arg1);\n  "
+    );
+}
+
+#[test]
+fn generated_constructor_moves_value_argument() {
+    let mut membership = empty_membership();
+    membership.kind = CppFunctionKind::Constructor;
+    let mut constructor = empty_regular_method();
+    constructor.path = CppPath::from_good_str("Class1::Class1");
+    constructor.member = Some(membership);
+    constructor.arguments = vec![
+        CppFunctionArgument {
+            name: "arg1".to_string(),
+            argument_type: CppType::Class(CppPath::from_good_str("Movable1")),
+            has_default_value: false,
+        },
+        CppFunctionArgument {
+            name: "arg2".to_string(),
+            argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+            has_default_value: false,
+        },
+    ];
+    let function = to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: constructor,
+        },
+        &[],
+        &mut FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        function.arguments[0].argument_type.conversion(),
+        &CppToFfiTypeConversion::ValueToPointer {
+            is_ffi_const: false
+        }
+    );
+    assert_eq!(
+        arguments_values(&function, true).unwrap(),
+        "std::move(*arg1), arg2"
+    );
+    assert_eq!(
+        owned_argument_guards(&function, true).unwrap(),
+        "std::unique_ptr<Movable1> arg1_owner(arg1);\n  "
+    );
+    assert_eq!(owned_argument_guards(&function, false).unwrap(), "");
+}
+
+#[test]
+fn regular_function_copies_value_argument() {
+    let function = constructor_with_value_argument(false);
+    assert_eq!(arguments_values(&function, false).unwrap(), "*arg1, arg2");
+}
+
+#[test]
+fn transparent_layout() {
+    let fields = vec![
+        RustStructField {
+            name: "x".to_string(),
+            field_type: RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::os::raw::c_int"),
+                generic_arguments: None,
+            }),
+            cpp_name: "x".to_string(),
+            cpp_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        },
+        RustStructField {
+            name: "y_pos".to_string(),
+            field_type: RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::os::raw::c_double"),
+                generic_arguments: None,
+            }),
+            cpp_name: "yPos".to_string(),
+            cpp_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
+        },
+    ];
+    let code = transparent_layout_checks(&CppPath::from_good_str("ns::Point"), &fields).unwrap();
+    assert!(code.starts_with("struct ritual_layout_ns_Point {\n  int x;\n  double yPos;\n};\n"));
+    assert!(code.contains("static_assert(sizeof(ritual_layout_ns_Point) == sizeof(ns::Point), "));
+    assert!(code.contains("static_assert(alignof(ritual_layout_ns_Point) == alignof(ns::Point), "));
+    assert!(code.contains(
+        "static_assert(offsetof(ritual_layout_ns_Point, yPos) == offsetof(ns::Point, yPos), "
+    ));
+}
+
+#[test]
+fn function_signature_calling_convention() {
+    let mut function = constructor_with_value_argument(false);
+    let signature = function_signature(&function).unwrap();
+    assert!(signature.starts_with("void ctr_test_ffi_Class1_Class1("));
+
+    function.calling_convention = Some(CppCallingConvention::Stdcall);
+    let signature = function_signature(&function).unwrap();
+    assert!(signature.starts_with("void __stdcall ctr_test_ffi_Class1_Class1("));
+}
+
+#[test]
+fn function_signature_purity() {
+    let mut function = constructor_with_value_argument(false);
+    function.purity = Some(CppFunctionPurity::Pure);
+    let signature = function_signature(&function).unwrap();
+    assert!(signature.starts_with("RITUAL_PURE void ctr_test_ffi_Class1_Class1("));
+}
+
+#[test]
+fn function_signature_noexcept() {
+    let mut function = constructor_with_value_argument(false);
+    assert!(!function_signature(&function).unwrap().ends_with("noexcept"));
+
+    function.is_noexcept = true;
+    assert!(function_signature(&function)
+        .unwrap()
+        .ends_with(") noexcept"));
+}
+
+#[test]
+fn c_header_signature() {
+    let mut function = constructor_with_value_argument(true);
+    function.is_noexcept = true;
+    function.purity = Some(CppFunctionPurity::Pure);
+    let mut opaque_structs = BTreeSet::new();
+    assert_eq!(
+        c_function_signature(&function, &mut opaque_structs).unwrap(),
+        "void ctr_test_ffi_Class1_Class1(const struct Movable1* arg1, int arg2, \
+         struct Class1* output)"
+    );
+    assert_eq!(
+        opaque_structs.into_iter().collect::<Vec<_>>(),
+        vec!["Class1".to_string(), "Movable1".to_string()]
+    );
+}
+
+#[test]
+fn c_header_types() {
+    let mut opaque_structs = BTreeSet::new();
+    let int64 = CppType::SpecificNumeric(CppSpecificNumericType {
+        path: CppPath::from_good_str("qint64"),
+        bits: 64,
+        kind: CppSpecificNumericTypeKind::Integer { is_signed: true },
+    });
+    assert_eq!(
+        c_type_code(&int64, None, &mut opaque_structs).unwrap(),
+        "int64_t"
+    );
+    let enum_type = CppType::Enum {
+        path: CppPath::from_good_str("ns::E"),
+    };
+    assert_eq!(
+        c_type_code(&enum_type, None, &mut opaque_structs).unwrap(),
+        "int"
+    );
+    let class_type = CppType::Class(CppPath::from_good_str("ns::C"));
+    assert!(c_type_code(&class_type, None, &mut opaque_structs).is_err());
+    assert!(c_type_code(
+        &CppType::new_reference(true, class_type.clone()),
+        None,
+        &mut opaque_structs
+    )
+    .is_err());
+    assert_eq!(
+        c_type_code(
+            &CppType::new_pointer(false, class_type),
+            None,
+            &mut opaque_structs
+        )
+        .unwrap(),
+        "struct ns_C*"
+    );
+    assert!(opaque_structs.contains("ns_C"));
+}
+
+#[test]
+fn exception_catching_body() {
+    assert_eq!(
+        catch_exceptions("return f(arg1);\n", "exception", false),
+        "try {\n    return f(arg1);\n  } catch (const std::exception& e) {\n    \
+         ritual_write_exception(exception, e.what());\n  \
+         } catch (...) {\n    \
+         ritual_write_exception(exception, \"unknown C++ exception\");\n  \
+         }\n  return {};\n"
+    );
+    assert!(catch_exceptions("f();\n", "exception", true).ends_with("}\n"));
+}
+
+#[test]
+fn slot_return_value() {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let int_type = CppFfiType::new(int, CppToFfiTypeConversion::NoChange).unwrap();
+    assert_eq!(
+        convert_slot_return_value(&int_type, "m_func(m_data)".to_string()).unwrap(),
+        "m_func(m_data)"
+    );
+
+    let flags = CppType::Class(CppPath::from_item(CppPathItem {
+        name: "QFlags".into(),
+        template_arguments: Some(vec![CppType::Enum {
+            path: CppPath::from_good_str("Qt::AlignmentFlag"),
+        }]),
+    }));
+    let flags_type = CppFfiType::new(flags, CppToFfiTypeConversion::QFlagsToInt).unwrap();
+    assert_eq!(
+        convert_slot_return_value(&flags_type, "m_func(m_data)".to_string()).unwrap(),
+        "QFlags< Qt::AlignmentFlag >(m_func(m_data))"
+    );
+
+    let string = CppType::Class(CppPath::from_good_str("QString"));
+    let string_type = CppFfiType::new(
+        string,
+        CppToFfiTypeConversion::ValueToPointer {
+            is_ffi_const: false,
+        },
+    )
+    .unwrap();
+    assert!(convert_slot_return_value(&string_type, "m_func(m_data)".to_string()).is_err());
+}
+
+#[test]
+fn pointer_sized_integer_size() {
+    let size_t = CppType::PointerSizedInteger {
+        path: CppPath::from_good_str("size_t"),
+        is_signed: false,
+    };
+    assert_eq!(
+        pointer_sized_integer_size_check(&size_t).unwrap(),
+        "printf(\"const _: [(); %zu] = [(); ::std::mem::size_of::<usize>()];\\n\", \
+         sizeof(size_t));"
+    );
+
+    let ptrdiff_t = CppType::PointerSizedInteger {
+        path: CppPath::from_good_str("std::ptrdiff_t"),
+        is_signed: true,
+    };
+    assert_eq!(
+        pointer_sized_integer_size_check(&ptrdiff_t).unwrap(),
+        "printf(\"const _: [(); %zu] = [(); ::std::mem::size_of::<isize>()];\\n\", \
+         sizeof(std::ptrdiff_t));"
+    );
+
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    assert!(pointer_sized_integer_size_check(&int).is_err());
+}
+
+#[test]
+fn protected_function_shim() {
+    let function = CppFunction {
+        path: CppPath::from_good_str("ns::Class1::update"),
+        member: Some(CppFunctionMemberData {
+            is_virtual: false,
+            is_pure_virtual: false,
+            is_const: false,
+            is_static: false,
+            visibility: CppVisibility::Protected,
+            is_signal: false,
+            is_slot: false,
+            kind: CppFunctionKind::Regular,
+        }),
+        operator: None,
+        return_type: CppType::Void,
+        arguments: vec![],
+        allows_variadic_arguments: false,
+        cast: None,
+        declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_deleted: false,
+    };
+    let mut method = constructor_with_value_argument(false);
+    method.path = CppPath::from_good_str("ctr_test_ffi_ns_Class1_update");
+    assert_eq!(
+        protected_shim(&method, &CppItem::Function(function)).unwrap(),
+        "struct ritual_shim_ctr_test_ffi_ns_Class1_update : public ns::Class1 {\n  \
+         using ns::Class1::update;\n};\n"
+    );
+}
+
+#[test]
+fn protected_constructor_shim() {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("ns::Class1::Class1");
+    function.member = Some(CppFunctionMemberData {
+        kind: CppFunctionKind::Constructor,
+        visibility: CppVisibility::Protected,
+        ..empty_membership()
+    });
+    let method = constructor_with_value_argument(false);
+    assert_eq!(
+        protected_shim(&method, &CppItem::Function(function)).unwrap(),
+        "struct ritual_shim_ctr_test_ffi_Class1_Class1 : public ns::Class1 {\n  \
+         template<typename... Args>\n  \
+         ritual_shim_ctr_test_ffi_Class1_Class1(Args&&... args) : \
+         ns::Class1(std::forward<Args>(args)...) {}\n\
+         };\n\
+         static_assert(sizeof(ritual_shim_ctr_test_ffi_Class1_Class1) == sizeof(ns::Class1), \
+         \"unexpected shim size\");\n"
+    );
+}
+
+#[test]
+fn cpp_file_partitioning() {
+    assert_eq!(cpp_file_names(1), vec!["file1.cpp"]);
+    assert_eq!(
+        cpp_file_names(3),
+        vec!["file1.cpp", "file2.cpp", "file3.cpp"]
+    );
+
+    let items = (0..10).collect::<Vec<i32>>();
+    let chunks = partition(&items, 4);
+    assert_eq!(
+        chunks,
+        vec![&[0, 1, 2][..], &[3, 4, 5][..], &[6, 7][..], &[8, 9][..]]
+    );
+    assert_eq!(partition(&items, 1), vec![&items[..]]);
+
+    let chunks = partition(&items[..2], 3);
+    assert_eq!(chunks, vec![&[0][..], &[1][..], &[][..]]);
+}
+
+#[test]
+fn cast_through_base_chain() {
+    // struct A { virtual ~A(); };
+    // struct B { virtual ~B(); };
+    // struct C : A, B {};
+    // struct D : C {};
+    // struct E : D {};
+    let class_ptr = |name: &str, is_const: bool| {
+        CppType::new_pointer(is_const, CppType::Class(CppPath::from_good_str(name)))
+    };
+    let static_cast = |is_unsafe: bool, base_chain: &[&str]| CppCast::Static {
+        is_unsafe,
+        base_index: None,
+        base_chain: base_chain
+            .iter()
+            .map(|name| CppPath::from_good_str(name))
+            .collect(),
+    };
+
+    let direct = CppCast::Static {
+        is_unsafe: false,
+        base_index: Some(1),
+        base_chain: Vec::new(),
+    };
+    assert_eq!(
+        cast_argument(&direct, &class_ptr("C", false), "ptr".to_string()).unwrap(),
+        "ptr"
+    );
+    assert_eq!(
+        cast_argument(&CppCast::Dynamic, &class_ptr("B", false), "ptr".to_string()).unwrap(),
+        "ptr"
+    );
+
+    // D* -> B*
+    assert_eq!(
+        cast_argument(
+            &static_cast(false, &["C"]),
+            &class_ptr("D", false),
+            "ptr".to_string()
+        )
+        .unwrap(),
+        "static_cast<C*>(ptr)"
+    );
+    // E* -> B*
+    assert_eq!(
+        cast_argument(
+            &static_cast(false, &["D", "C"]),
+            &class_ptr("E", true),
+            "ptr".to_string()
+        )
+        .unwrap(),
+        "static_cast<const C*>(static_cast<const D*>(ptr))"
+    );
+    // B* -> E*
+    assert_eq!(
+        cast_argument(
+            &static_cast(true, &["D", "C"]),
+            &class_ptr("B", false),
+            "ptr".to_string()
+        )
+        .unwrap(),
+        "static_cast<D*>(static_cast<C*>(ptr))"
+    );
+}
+
+#[test]
+fn array_length_check() {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("get_matrix");
+    function.arguments = vec![CppFunctionArgument {
+        name: "out".to_string(),
+        argument_type: CppType::new_pointer(
+            false,
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Float),
+        ),
+        has_default_value: false,
+    }];
+    function.declaration_code = Some("void get_matrix ( float out [ 16 ] )".to_string());
+    let mut ffi_function = to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: function.clone(),
+        },
+        &[],
+        &mut FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(array_length_checks(&ffi_function, &function).unwrap(), "");
+
+    add_array_length_arguments(&mut ffi_function, &function).unwrap();
+    assert_eq!(
+        array_length_checks(&ffi_function, &function).unwrap(),
+        "if (out_len < 16) std::abort();\n  "
+    );
+
+    ffi_function.arguments.push(CppFfiFunctionArgument {
+        name: "exception".to_string(),
+        argument_type: CppFfiType::new(
+            CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Char)),
+            CppToFfiTypeConversion::NoChange,
+        )
+        .unwrap(),
+        meaning: CppFfiArgumentMeaning::ExceptionOutput,
+    });
+    assert_eq!(
+        array_length_checks(&ffi_function, &function).unwrap(),
+        "if (out_len < 16) throw std::length_error(\"array argument out is too short\");\n  "
+    );
+}
+
+fn initializer_list_function(element_type: CppType) -> CppFunction {
+    let mut list_path = CppPath::from_good_str("std::initializer_list");
+    list_path.last_mut().template_arguments = Some(vec![element_type]);
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("set_values");
+    function.arguments = vec![CppFunctionArgument {
+        name: "values".to_string(),
+        argument_type: CppType::new_reference(true, CppType::Class(list_path)),
+        has_default_value: false,
+    }];
+    function
+}
+
+#[test]
+fn initializer_list_argument() {
+    let function = initializer_list_function(CppType::BuiltInNumeric(CppBuiltInNumericType::Int));
+    let mut ffi_function = to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: function.clone(),
+        },
+        &[],
+        &mut FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    apply_initializer_list_arguments(&mut ffi_function).unwrap();
+    assert_eq!(ffi_function.arguments.len(), 2);
+    assert_eq!(
+        ffi_function.arguments[0].argument_type.conversion(),
+        &CppToFfiTypeConversion::InitializerListToPointer
+    );
+    assert_eq!(
+        ffi_function.arguments[0].argument_type.ffi_type(),
+        &CppType::new_pointer(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Int))
+    );
+    assert_eq!(ffi_function.arguments[1].name, "values_len");
+    assert_eq!(
+        ffi_function.arguments[1].meaning,
+        CppFfiArgumentMeaning::ArrayLength(0)
+    );
+
+    assert_eq!(
+        array_length_checks(&ffi_function, &function).unwrap(),
+        "if (values_len > RITUAL_MAX_INITIALIZER_LIST_SIZE) std::abort();\n  "
+    );
+    let call = format!(
+        "set_values({})",
+        arguments_values(&ffi_function, false).unwrap()
+    );
+    assert_eq!(
+        with_initializer_lists(&ffi_function, call).unwrap(),
+        "ritual_with_initializer_list(values, values_len, \
+         [&](std::initializer_list<int> values_list) { return set_values(values_list); })"
+    );
+}
+
+#[test]
+fn initializer_list_of_classes() {
+    let function = initializer_list_function(CppType::Class(CppPath::from_good_str("QString")));
+    let mut ffi_function = to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: function,
+        },
+        &[],
+        &mut FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert!(apply_initializer_list_arguments(&mut ffi_function).is_err());
+}
+
+#[test]
+fn duration_types() {
+    let milliseconds = CppType::Class(CppPath::from_good_str("std::chrono::milliseconds"));
+    let seconds = CppType::Class(CppPath::from_good_str("std::chrono::seconds"));
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("set_interval");
+    function.return_type = seconds.clone();
+    function.arguments = vec![CppFunctionArgument {
+        name: "interval".to_string(),
+        argument_type: CppType::new_reference(true, milliseconds.clone()),
+        has_default_value: false,
+    }];
+    let mut ffi_function = to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: function.clone(),
+        },
+        &[],
+        &mut FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    let milli = DurationPeriod::new(1, 1000).unwrap();
+    let one = DurationPeriod::new(1, 1).unwrap();
+    apply_duration_types(
+        &mut ffi_function,
+        &function,
+        &[
+            (CppPath::from_good_str("std::chrono::milliseconds"), milli),
+            (CppPath::from_good_str("std::chrono::seconds"), one),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(ffi_function.arguments.len(), 1);
+    assert_eq!(
+        ffi_function.arguments[0].argument_type.conversion(),
+        &CppToFfiTypeConversion::DurationToTicks { period: milli }
+    );
+    assert_eq!(
+        ffi_function.return_type.conversion(),
+        &CppToFfiTypeConversion::DurationToTicks { period: one }
+    );
+    assert_eq!(ffi_function.return_type.original_type(), &seconds);
+    assert_eq!(
+        ffi_function.allocation_place,
+        ReturnValueAllocationPlace::NotApplicable
+    );
+
+    assert_eq!(
+        duration_period_checks(&ffi_function).unwrap(),
+        "static_assert(std::ratio_equal<std::chrono::seconds::period, std::ratio<1, 1>>::value, \
+         \"unexpected period of std::chrono::seconds\");\n  \
+         static_assert(std::ratio_equal<std::chrono::milliseconds::period, \
+         std::ratio<1, 1000>>::value, \"unexpected period of std::chrono::milliseconds\");\n  "
+    );
+}
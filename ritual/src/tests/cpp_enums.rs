@@ -0,0 +1,87 @@
+use crate::cpp_data::{CppEnumValue, CppPath};
+use crate::cpp_enums::{enum_value_aliases, group_enum_values, CppEnumKind};
+
+fn value(path: &str, value: i64) -> CppEnumValue {
+    CppEnumValue {
+        path: CppPath::from_good_str(path),
+        value,
+    }
+}
+
+#[test]
+fn enum_groups() {
+    let values = vec![
+        value("Qt::AlignmentFlag::AlignLeft", 1),
+        value("Qt::Key::Key_A", 0),
+        value("Qt::AlignmentFlag::AlignRight", 2),
+        value("Qt::Key::Key_B", 1),
+        value("Qt::AlignmentFlag::AlignLeading", 1),
+        value("Qt::AlignmentFlag::AlignHCenter", 4),
+        value("Qt::AlignmentFlag::AlignHorizontal_Mask", 7),
+        value("Qt::Key::Key_C", 2),
+        value("Qt::Other::Value1", 5),
+        value("Qt::Other::Value2", -3),
+    ];
+    let groups = group_enum_values(&values);
+    assert_eq!(groups.len(), 3);
+
+    assert_eq!(
+        groups[0].enum_path,
+        CppPath::from_good_str("Qt::AlignmentFlag")
+    );
+    assert_eq!(groups[0].kind, CppEnumKind::Flags);
+    let names = groups[0]
+        .values
+        .iter()
+        .map(|value| value.value.path.last().name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "AlignLeft",
+            "AlignRight",
+            "AlignLeading",
+            "AlignHCenter",
+            "AlignHorizontal_Mask"
+        ]
+    );
+    assert_eq!(groups[0].values[2].alias_of, Some(0));
+    assert!(groups[0]
+        .values
+        .iter()
+        .enumerate()
+        .all(|(index, value)| index == 2 || value.alias_of.is_none()));
+    assert_eq!(groups[0].unique_values().count(), 4);
+
+    assert_eq!(groups[1].enum_path, CppPath::from_good_str("Qt::Key"));
+    assert_eq!(groups[1].kind, CppEnumKind::Sequential);
+    assert_eq!(groups[1].values.len(), 3);
+
+    assert_eq!(groups[2].enum_path, CppPath::from_good_str("Qt::Other"));
+    assert_eq!(groups[2].kind, CppEnumKind::Other);
+}
+
+#[test]
+fn enum_aliases() {
+    let values = vec![
+        value("E::A", 1),
+        value("E::B", 1),
+        value("E::C", 2),
+        value("E::D", 1),
+    ];
+    let aliases = enum_value_aliases(&values);
+    assert_eq!(
+        aliases,
+        vec![(&values[1], &values[0]), (&values[3], &values[0])]
+    );
+
+    let groups = group_enum_values(&values);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].aliases().collect::<Vec<_>>(), aliases);
+    assert_eq!(
+        groups[0].unique_values().collect::<Vec<_>>(),
+        vec![&values[0], &values[2]]
+    );
+
+    assert!(enum_value_aliases(&values[..1]).is_empty());
+}
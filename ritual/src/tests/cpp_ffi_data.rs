@@ -1,3 +1,4 @@
+use crate::cpp_data::CppPath;
 use crate::cpp_ffi_data::*;
 use crate::cpp_type::*;
 
@@ -11,6 +12,18 @@ fn argument_meaning() {
 
     let a3 = CppFfiArgumentMeaning::ReturnValue;
     assert!(!a3.is_argument());
+
+    let a4 = CppFfiArgumentMeaning::Callback(0);
+    assert!(a4.is_argument());
+
+    let a5 = CppFfiArgumentMeaning::CallbackUserData(1);
+    assert!(a5.is_argument());
+
+    let a6 = CppFfiArgumentMeaning::ArrayLength(0);
+    assert!(!a6.is_argument());
+
+    let a7 = CppFfiArgumentMeaning::ExceptionOutput;
+    assert!(!a7.is_argument());
 }
 
 #[test]
@@ -68,3 +81,28 @@ fn cpp_ffi_type_void() {
     assert!(t.ffi_type().is_void());
     assert_eq!(t.conversion(), &CppToFfiTypeConversion::NoChange);
 }
+
+#[test]
+fn argument_duration() {
+    let duration_type = CppType::Class(CppPath::from_good_str("std::chrono::milliseconds"));
+    let arg = CppFfiFunctionArgument {
+        name: "timeout".to_string(),
+        argument_type: CppFfiType::new(
+            CppType::new_reference(true, duration_type.clone()),
+            CppToFfiTypeConversion::DurationToTicks {
+                period: DurationPeriod::new(1, 1000).unwrap(),
+            },
+        )
+        .unwrap(),
+        meaning: CppFfiArgumentMeaning::Argument(0),
+    };
+
+    assert_eq!(arg.to_cpp_code().unwrap(), "long long timeout");
+    assert_eq!(
+        arg.argument_type.original_type(),
+        &CppType::new_reference(true, duration_type)
+    );
+
+    assert!(DurationPeriod::new(0, 1).is_err());
+    assert!(DurationPeriod::new(1, 0).is_err());
+}
@@ -0,0 +1,692 @@
+use crate::cpp_data::{
+    CppBaseSpecifier, CppClassField, CppItem, CppNamespace, CppPath, CppPathItem,
+    CppTypeDeclaration, CppTypeDeclarationKind, CppVisibility,
+};
+use crate::cpp_ffi_data::{
+    CppCast, CppFfiFunctionKind, CppFfiItem, CppFieldAccessorType, CppToFfiTypeConversion,
+};
+use crate::cpp_ffi_generator::{
+    abstract_classes, conversion_failures, destructible_types_without_constructors,
+    excluded_dependencies, generate_ffi_items, generate_field_accessors, is_allowed_item,
+    is_reachable_item, reachable_types, to_ffi_method, FfiGeneratorOptions, FfiNameProvider,
+    NewFfiFunctionKind,
+};
+use crate::cpp_function::{CppFunctionArgument, CppFunctionKind};
+use crate::cpp_type::{CppBuiltInNumericType, CppTemplateParameter, CppType};
+use crate::tests::cpp_method::{empty_membership, empty_regular_method};
+use std::collections::HashSet;
+
+#[test]
+fn stable_names() {
+    let mut provider = FfiNameProvider::with_stable_suffixes("ctr_test_ffi".into(), HashSet::new());
+    let path1 = provider.create_path("func", "void ns::func()");
+    assert_eq!(path1, CppPath::from_good_str("ctr_test_ffi_func_b8c02339"));
+    let path2 = provider.create_path("func", "void ns::func(int)");
+    assert_ne!(path1, path2);
+
+    // the name doesn't depend on the order of creation
+    let mut provider = FfiNameProvider::with_stable_suffixes("ctr_test_ffi".into(), HashSet::new());
+    assert_eq!(provider.create_path("func", "void ns::func(int)"), path2);
+    assert_eq!(provider.create_path("func", "void ns::func()"), path1);
+}
+
+#[test]
+fn stable_names_collision() {
+    let mut provider = FfiNameProvider::with_stable_suffixes("ctr_test_ffi".into(), HashSet::new());
+    let path1 = provider.create_path("func", "void ns::func()");
+    let path2 = provider.create_path("func", "void ns::func()");
+    assert_eq!(
+        path2,
+        CppPath::from_good_str(&format!("{}1", path1.last().name))
+    );
+}
+
+#[test]
+fn testing_names() {
+    let mut provider = FfiNameProvider::testing();
+    assert_eq!(
+        provider.create_path("func", "void ns::func()"),
+        CppPath::from_good_str("_func")
+    );
+    assert_eq!(
+        provider.create_path("func", "void ns::func(int)"),
+        CppPath::from_good_str("_func1")
+    );
+}
+
+#[test]
+fn peek_path() {
+    let mut provider = FfiNameProvider::with_stable_suffixes("ctr_test_ffi".into(), HashSet::new());
+    let peeked = provider.peek_path("func", "void ns::func()");
+    assert_eq!(provider.peek_path("func", "void ns::func()"), peeked);
+    assert_eq!(provider.create_path("func", "void ns::func()"), peeked);
+    assert_ne!(provider.peek_path("func", "void ns::func()"), peeked);
+
+    let mut provider = FfiNameProvider::testing();
+    provider.create_path("func", "void ns::func()");
+    let peeked = provider.peek_path("func", "void ns::func()");
+    assert_eq!(peeked, CppPath::from_good_str("_func1"));
+    assert_eq!(provider.create_path("func", "void ns::func()"), peeked);
+}
+
+#[test]
+fn field_accessor_name_collision() {
+    let mut provider = FfiNameProvider::testing();
+    let field = CppClassField {
+        path: CppPath::from_good_str("Class1::text"),
+        field_type: CppType::Class(CppPath::from_good_str("Class2")),
+        visibility: CppVisibility::Public,
+        is_static: false,
+        is_const: false,
+        array_size: None,
+    };
+    generate_field_accessors(&field, &[], &mut provider).unwrap();
+
+    assert_eq!(
+        provider.register_name_kind("Class1_text_mut", "void Class1::text_mut()", false),
+        Some(field.short_text())
+    );
+    // the collision is only reported once
+    assert_eq!(
+        provider.register_name_kind("Class1_text_mut", "void Class1::text_mut()", false),
+        None
+    );
+    assert_eq!(
+        provider.register_name_kind("Class1_text_mut", "void Class1::text_mut() const", false),
+        None
+    );
+    assert_eq!(
+        provider.register_name_kind("Class1_text_len", "int Class1::text_len()", false),
+        None
+    );
+    assert_eq!(
+        provider.register_name_kind("Class1_text_len", "int Class1::text_len() const", false),
+        None
+    );
+}
+
+fn field_accessor_types(field: &CppClassField) -> Vec<CppFieldAccessorType> {
+    generate_field_accessors(field, &[], &mut FfiNameProvider::testing())
+        .unwrap()
+        .into_iter()
+        .map(|item| match item {
+            CppFfiItem::Function(function) => match function.kind {
+                CppFfiFunctionKind::FieldAccessor { accessor_type } => accessor_type,
+                _ => panic!("field accessor expected"),
+            },
+            _ => panic!("function expected"),
+        })
+        .collect()
+}
+
+#[test]
+fn protected_field_accessors() {
+    let mut field = CppClassField {
+        path: CppPath::from_good_str("Class1::count"),
+        field_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        visibility: CppVisibility::Protected,
+        is_static: false,
+        is_const: false,
+        array_size: None,
+    };
+    assert_eq!(
+        field_accessor_types(&field),
+        vec![
+            CppFieldAccessorType::CopyGetter,
+            CppFieldAccessorType::Setter
+        ]
+    );
+
+    field.is_const = true;
+    assert_eq!(
+        field_accessor_types(&field),
+        vec![CppFieldAccessorType::CopyGetter]
+    );
+
+    field.field_type = CppType::Class(CppPath::from_good_str("Class2"));
+    assert_eq!(
+        field_accessor_types(&field),
+        vec![CppFieldAccessorType::ConstRefGetter]
+    );
+
+    field.visibility = CppVisibility::Private;
+    assert!(field_accessor_types(&field).is_empty());
+}
+
+#[test]
+fn const_public_field_has_no_setter() {
+    let field = CppClassField {
+        path: CppPath::from_good_str("Class1::id"),
+        field_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        visibility: CppVisibility::Public,
+        is_static: false,
+        is_const: true,
+        array_size: None,
+    };
+    assert_eq!(
+        field_accessor_types(&field),
+        vec![CppFieldAccessorType::CopyGetter]
+    );
+}
+
+#[test]
+fn array_field_accessors() {
+    let mut field = CppClassField {
+        path: CppPath::from_good_str("Class1::values"),
+        field_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
+        visibility: CppVisibility::Public,
+        is_static: false,
+        is_const: false,
+        array_size: Some(4),
+    };
+    let accessors = generate_field_accessors(&field, &[], &mut FfiNameProvider::testing()).unwrap();
+    assert_eq!(accessors.len(), 2);
+    let getter = accessors[0].as_function_ref().unwrap();
+    assert_eq!(
+        getter.kind,
+        CppFfiFunctionKind::FieldAccessor {
+            accessor_type: CppFieldAccessorType::ArrayPointerGetter,
+        }
+    );
+    assert_eq!(
+        getter.return_type.ffi_type(),
+        &CppType::new_pointer(
+            false,
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Double)
+        )
+    );
+    let setter = accessors[1].as_function_ref().unwrap();
+    assert_eq!(
+        setter.kind,
+        CppFfiFunctionKind::FieldAccessor {
+            accessor_type: CppFieldAccessorType::ArrayElementSetter,
+        }
+    );
+    let argument_names = setter
+        .arguments
+        .iter()
+        .map(|arg| arg.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(argument_names, vec!["this_ptr", "index", "value"]);
+
+    field.is_const = true;
+    assert_eq!(
+        field_accessor_types(&field),
+        vec![CppFieldAccessorType::ArrayPointerGetter]
+    );
+}
+
+fn fixture_items() -> Vec<CppItem> {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let mut items = Vec::new();
+    for name in &["func", "ns::func", "func2"] {
+        let mut function = empty_regular_method();
+        function.path = CppPath::from_good_str(name);
+        items.push(CppItem::Function(function.clone()));
+
+        // fails before the name is requested
+        function.allows_variadic_arguments = true;
+        items.push(CppItem::Function(function.clone()));
+
+        // fails after the name is requested
+        function.allows_variadic_arguments = false;
+        function.arguments.push(CppFunctionArgument {
+            name: "arg".to_string(),
+            argument_type: CppType::TemplateParameter(CppTemplateParameter {
+                nested_level: 0,
+                index: 0,
+                name: "T".to_string(),
+            }),
+            has_default_value: false,
+        });
+        items.push(CppItem::Function(function.clone()));
+
+        function.arguments[0].argument_type = int.clone();
+        function.return_type = int.clone();
+        items.push(CppItem::Function(function));
+    }
+    items.push(CppItem::Type(CppTypeDeclaration {
+        path: CppPath::from_good_str("Class1"),
+        kind: CppTypeDeclarationKind::Class {
+            is_pod: false,
+            is_polymorphic: false,
+        },
+    }));
+    for name in &["Class1::func", "Class1::func_mut"] {
+        items.push(CppItem::ClassField(CppClassField {
+            path: CppPath::from_good_str(name),
+            field_type: CppType::Class(CppPath::from_good_str("Class2")),
+            visibility: CppVisibility::Public,
+            is_static: false,
+            is_const: false,
+            array_size: None,
+        }));
+    }
+    items
+}
+
+#[test]
+fn thread_safe_functions() {
+    let mut thread = empty_regular_method();
+    thread.path = CppPath::from_good_str("Class1::thread");
+    thread.member = Some(empty_membership());
+    let mut other = thread.clone();
+    other.path = CppPath::from_good_str("Class1::other");
+    let items = vec![CppItem::Function(thread), CppItem::Function(other)];
+    let items = items.iter().collect::<Vec<_>>();
+
+    let thread_safe_functions = [CppPath::from_good_str("Class1::thread")];
+    let options = FfiGeneratorOptions {
+        movable_types: &[],
+        heap_return_variants: false,
+        thread_safe_functions: &thread_safe_functions,
+        duration_types: &[],
+        callback_arguments: &[],
+        catch_exceptions: false,
+        array_length_arguments: false,
+    };
+    let is_thread_safe =
+        generate_ffi_items(&items, &options, &mut FfiNameProvider::testing(), false)
+            .into_iter()
+            .map(|result| {
+                let ffi_items = result.unwrap().unwrap();
+                assert_eq!(ffi_items.len(), 1);
+                ffi_items[0].as_function_ref().unwrap().is_thread_safe
+            })
+            .collect::<Vec<_>>();
+    assert_eq!(is_thread_safe, vec![true, false]);
+}
+
+#[test]
+fn conversion_failure_report() {
+    let mut good = empty_regular_method();
+    good.path = CppPath::from_good_str("Class1::good");
+    good.member = Some(empty_membership());
+    let mut bad = good.clone();
+    bad.path = CppPath::from_good_str("Class1::bad");
+    bad.arguments.push(CppFunctionArgument {
+        argument_type: CppType::TemplateParameter(CppTemplateParameter {
+            nested_level: 0,
+            index: 0,
+            name: "T".into(),
+        }),
+        name: "value".into(),
+        has_default_value: false,
+    });
+    let items = vec![
+        CppItem::Function(good),
+        CppItem::Function(bad),
+        CppItem::Namespace(CppNamespace {
+            path: CppPath::from_good_str("ns"),
+        }),
+    ];
+    let items = items.iter().collect::<Vec<_>>();
+    let options = FfiGeneratorOptions {
+        movable_types: &[],
+        heap_return_variants: false,
+        thread_safe_functions: &[],
+        duration_types: &[],
+        callback_arguments: &[],
+        catch_exceptions: false,
+        array_length_arguments: false,
+    };
+    let results = generate_ffi_items(&items, &options, &mut FfiNameProvider::testing(), false);
+    let failures = conversion_failures(&items, &results);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, CppPath::from_good_str("Class1::bad"));
+    assert!(failures[0]
+        .1
+        .contains("template parameters cannot be expressed in FFI"));
+}
+
+#[test]
+fn parallel_generation() {
+    let items = fixture_items();
+    let items = items.iter().collect::<Vec<_>>();
+    let options = FfiGeneratorOptions {
+        movable_types: &[],
+        heap_return_variants: true,
+        thread_safe_functions: &[],
+        duration_types: &[],
+        callback_arguments: &[],
+        catch_exceptions: true,
+        array_length_arguments: true,
+    };
+    let generate = |parallel| {
+        generate_ffi_items(&items, &options, &mut FfiNameProvider::testing(), parallel)
+            .into_iter()
+            .map(|result| format!("{:?}", result))
+            .collect::<Vec<_>>()
+    };
+    let serial = generate(false);
+    assert_eq!(serial.len(), items.len());
+    assert_eq!(generate(true), serial);
+
+    // the names match names assigned by the generator without splitting the passes
+    let mut provider = FfiNameProvider::testing();
+    let expected = items
+        .iter()
+        .map(|item| {
+            let result = match item {
+                CppItem::Function(function) => to_ffi_method(
+                    NewFfiFunctionKind::Function {
+                        cpp_function: function.clone(),
+                    },
+                    &[],
+                    &mut provider,
+                    None,
+                )
+                .map(|function| vec![function.path]),
+                CppItem::ClassField(field) => generate_field_accessors(field, &[], &mut provider)
+                    .map(|items| items.into_iter().map(|item| item.path().clone()).collect()),
+                _ => return None,
+            };
+            Some(result.ok())
+        })
+        .collect::<Vec<_>>();
+    let actual = generate_ffi_items(&items, &options, &mut FfiNameProvider::testing(), true)
+        .into_iter()
+        .map(|result| {
+            result.map(|result| {
+                result
+                    .ok()
+                    .map(|items| items.iter().map(|item| item.path().clone()).collect())
+            })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+    // the name reserved by the failed function is not reused
+    assert!(actual.contains(&Some(Some(vec![CppPath::from_good_str("_func2")]))));
+}
+
+#[test]
+fn qflags_return_type() {
+    // Qt::Alignment is a typedef of QFlags<Qt::AlignmentFlag>
+    let enum_path = CppPath::from_good_str("Qt::AlignmentFlag");
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("alignment");
+    function.return_type = CppType::Class(CppPath::from_item(CppPathItem {
+        name: "QFlags".into(),
+        template_arguments: Some(vec![CppType::Enum {
+            path: enum_path.clone(),
+        }]),
+    }));
+
+    let ffi_function = to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: function,
+        },
+        &[],
+        &mut FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        ffi_function.return_type.conversion(),
+        &CppToFfiTypeConversion::QFlagsToInt
+    );
+    assert_eq!(
+        ffi_function.return_type.ffi_type(),
+        &CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+    );
+    assert!(ffi_function.arguments.is_empty());
+    assert_eq!(
+        ffi_function.return_type.qflags_enum_path().unwrap(),
+        &enum_path
+    );
+}
+
+#[test]
+fn abstract_class_detection() {
+    let virtual_function = |path: &str, is_pure_virtual: bool| {
+        let mut function = empty_regular_method();
+        function.path = CppPath::from_good_str(path);
+        let mut membership = empty_membership();
+        membership.is_virtual = true;
+        membership.is_pure_virtual = is_pure_virtual;
+        function.member = Some(membership);
+        CppItem::Function(function)
+    };
+    let base = |derived: &str, base: &str| {
+        CppItem::ClassBase(CppBaseSpecifier {
+            base_class_type: CppPath::from_good_str(base),
+            base_index: 0,
+            is_virtual: false,
+            visibility: CppVisibility::Public,
+            derived_class_type: CppPath::from_good_str(derived),
+        })
+    };
+    let items = vec![
+        virtual_function("Shape::area", true),
+        virtual_function("Shape::name", true),
+        // overrides only one of the functions
+        base("Polygon", "Shape"),
+        virtual_function("Polygon::name", false),
+        // overrides the remaining function
+        base("Square", "Polygon"),
+        virtual_function("Square::area", false),
+        virtual_function("Other::run", false),
+    ];
+    let items = items.iter().collect::<Vec<_>>();
+    let expected = vec![
+        CppPath::from_good_str("Shape"),
+        CppPath::from_good_str("Polygon"),
+    ]
+    .into_iter()
+    .collect::<HashSet<_>>();
+    assert_eq!(abstract_classes(&items), expected);
+}
+
+#[test]
+fn destructors_without_constructors() {
+    let member_function = |path: &str, kind: CppFunctionKind| {
+        let mut function = empty_regular_method();
+        function.path = CppPath::from_good_str(path);
+        let mut membership = empty_membership();
+        membership.kind = kind;
+        function.member = Some(membership);
+        function
+    };
+    let constructed_destructor =
+        member_function("Constructed::~Constructed", CppFunctionKind::Destructor);
+    let constructor = member_function("Constructed::Constructed", CppFunctionKind::Constructor);
+    let returned_destructor = member_function("Returned::~Returned", CppFunctionKind::Destructor);
+    let mut factory = empty_regular_method();
+    factory.path = CppPath::from_good_str("create_returned");
+    factory.return_type =
+        CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("Returned")));
+    let private_destructor = member_function("Private::~Private", CppFunctionKind::Destructor);
+    let base_destructor = member_function("Base::~Base", CppFunctionKind::Destructor);
+    let private_base_destructor =
+        member_function("PrivateBase::~PrivateBase", CppFunctionKind::Destructor);
+
+    let functions = vec![
+        &constructed_destructor,
+        &constructor,
+        &returned_destructor,
+        &factory,
+        &private_destructor,
+        &base_destructor,
+        &private_base_destructor,
+    ];
+    let base = |base: &str, derived: &str, visibility: CppVisibility| CppBaseSpecifier {
+        base_class_type: CppPath::from_good_str(base),
+        base_index: 0,
+        is_virtual: false,
+        visibility,
+        derived_class_type: CppPath::from_good_str(derived),
+    };
+    let middle_base = base("Middle", "Constructed", CppVisibility::Public);
+    let top_base = base("Base", "Middle", CppVisibility::Public);
+    let private_base = base("PrivateBase", "Returned", CppVisibility::Private);
+    let bases = vec![&middle_base, &top_base, &private_base];
+
+    assert_eq!(
+        destructible_types_without_constructors(&functions, &[]),
+        vec![
+            CppPath::from_good_str("Private"),
+            CppPath::from_good_str("Base"),
+            CppPath::from_good_str("PrivateBase"),
+        ]
+    );
+    assert_eq!(
+        destructible_types_without_constructors(&functions, &bases),
+        vec![
+            CppPath::from_good_str("Private"),
+            CppPath::from_good_str("PrivateBase"),
+        ]
+    );
+}
+
+#[test]
+fn allowed_path_prefixes() {
+    let prefixes = vec![CppPath::from_good_str("ns::Allowed")];
+    let class_ptr =
+        |name: &str| CppType::new_pointer(false, CppType::Class(CppPath::from_good_str(name)));
+
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("ns::Allowed::get_other");
+    method.member = Some(empty_membership());
+    method.return_type = class_ptr("ns::Other");
+    let method = CppItem::Function(method);
+
+    let mut other_method = empty_regular_method();
+    other_method.path = CppPath::from_good_str("ns::Other::get");
+    other_method.member = Some(empty_membership());
+    let other_method = CppItem::Function(other_method);
+
+    assert!(is_allowed_item(&method, &prefixes));
+    assert!(!is_allowed_item(&other_method, &prefixes));
+    assert!(is_allowed_item(&other_method, &[]));
+
+    let base = |derived: &str, base: &str| {
+        CppItem::ClassBase(CppBaseSpecifier {
+            base_class_type: CppPath::from_good_str(base),
+            base_index: 0,
+            is_virtual: false,
+            visibility: CppVisibility::Public,
+            derived_class_type: CppPath::from_good_str(derived),
+        })
+    };
+    assert!(is_allowed_item(
+        &base("ns::Allowed::Nested", "ns::Allowed"),
+        &prefixes
+    ));
+    assert!(!is_allowed_item(
+        &base("ns::Allowed", "ns::Other"),
+        &prefixes
+    ));
+
+    let mut cast = empty_regular_method();
+    cast.path = CppPath::from_item(CppPathItem {
+        name: "static_cast".into(),
+        template_arguments: Some(vec![class_ptr("ns::Other")]),
+    });
+    cast.return_type = class_ptr("ns::Other");
+    cast.arguments = vec![CppFunctionArgument {
+        name: "ptr".to_string(),
+        argument_type: class_ptr("ns::Allowed"),
+        has_default_value: false,
+    }];
+    cast.cast = Some(CppCast::Static {
+        is_unsafe: false,
+        base_index: Some(0),
+        base_chain: Vec::new(),
+    });
+    assert!(!is_allowed_item(&CppItem::Function(cast), &prefixes));
+
+    assert_eq!(
+        excluded_dependencies(&[&method], &prefixes),
+        vec![CppPath::from_good_str("ns::Other")]
+    );
+    assert!(excluded_dependencies(&[&method], &[]).is_empty());
+}
+
+#[test]
+fn reachable_types_from_root_classes() {
+    let vec_value2 = CppPath::from_items(vec![CppPathItem {
+        name: "Vec".into(),
+        template_arguments: Some(vec![CppType::Class(CppPath::from_good_str("Value2"))]),
+    }]);
+    let vec_t = CppPath::from_items(vec![CppPathItem {
+        name: "Vec".into(),
+        template_arguments: Some(vec![CppType::TemplateParameter(CppTemplateParameter {
+            nested_level: 0,
+            index: 0,
+            name: "T".into(),
+        })]),
+    }]);
+
+    let mut root_value = empty_regular_method();
+    root_value.path = CppPath::from_good_str("Root::value");
+    root_value.member = Some(empty_membership());
+    root_value.return_type = CppType::Class(CppPath::from_good_str("Value"));
+
+    let mut root_values = root_value.clone();
+    root_values.path = CppPath::from_good_str("Root::values");
+    root_values.return_type = CppType::Class(vec_value2.clone());
+
+    let mut unrelated_root = root_value.clone();
+    unrelated_root.path = CppPath::from_good_str("Unrelated::root");
+    unrelated_root.return_type = CppType::Class(CppPath::from_good_str("Root"));
+
+    // the template itself is not used by the root classes, only its instantiation
+    let mut vec_get = root_value.clone();
+    vec_get.path = vec_t.join(CppPathItem::from_good_str("get"));
+    vec_get.return_type = CppType::Class(CppPath::from_good_str("Unrelated"));
+
+    let mut vec_first = root_value.clone();
+    vec_first.path = vec_value2.join(CppPathItem::from_good_str("first"));
+    vec_first.return_type = CppType::Class(CppPath::from_good_str("Element"));
+
+    let items = vec![
+        CppItem::Function(root_value),
+        CppItem::Function(root_values),
+        CppItem::Function(unrelated_root),
+        CppItem::Function(vec_get),
+        CppItem::Function(vec_first),
+        CppItem::ClassField(CppClassField {
+            path: CppPath::from_good_str("Value::kind"),
+            field_type: CppType::Enum {
+                path: CppPath::from_good_str("Kind"),
+            },
+            visibility: CppVisibility::Public,
+            is_static: false,
+            is_const: false,
+            array_size: None,
+        }),
+        CppItem::ClassBase(CppBaseSpecifier {
+            base_class_type: CppPath::from_good_str("Base"),
+            base_index: 0,
+            is_virtual: false,
+            visibility: CppVisibility::Public,
+            derived_class_type: CppPath::from_good_str("Value"),
+        }),
+    ];
+    let items = items.iter().collect::<Vec<_>>();
+
+    let reachable = reachable_types(&items, &[CppPath::from_good_str("Root")]);
+    let mut names = reachable
+        .iter()
+        .map(CppPath::to_cpp_pseudo_code)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "Base",
+            "Element",
+            "Kind",
+            "Root",
+            "Value",
+            "Value2",
+            "Vec<Value2>"
+        ]
+    );
+
+    let wrapped = items
+        .iter()
+        .map(|item| is_reachable_item(item, &reachable))
+        .collect::<Vec<_>>();
+    assert_eq!(wrapped, vec![true, true, false, false, true, true, true]);
+}
@@ -0,0 +1,35 @@
+use crate::cpp_data::CppPath;
+use crate::cpp_function::{CppFunction, CppFunctionArgument};
+use crate::cpp_inheritance::is_hidden_in_derived;
+use crate::cpp_type::{CppBuiltInNumericType, CppType};
+use crate::tests::cpp_method::{empty_membership, empty_regular_method};
+
+fn method(path: &str, arguments: Vec<CppType>) -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str(path);
+    function.member = Some(empty_membership());
+    function.arguments = arguments
+        .into_iter()
+        .enumerate()
+        .map(|(index, argument_type)| CppFunctionArgument {
+            name: format!("arg{}", index),
+            argument_type,
+            has_default_value: false,
+        })
+        .collect();
+    function
+}
+
+#[test]
+fn hidden_base_overload() {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let double = CppType::BuiltInNumeric(CppBuiltInNumericType::Double);
+    let base_foo_int = method("Base::foo", vec![int]);
+    let base_bar = method("Base::bar", vec![]);
+    let derived_foo_double = method("Derived::foo", vec![double]);
+
+    let derived_functions = vec![&derived_foo_double];
+    assert!(is_hidden_in_derived(&base_foo_int, &derived_functions));
+    assert!(!is_hidden_in_derived(&base_bar, &derived_functions));
+    assert!(!is_hidden_in_derived(&base_foo_int, &[]));
+}
@@ -1,9 +1,14 @@
+use crate::config::{Config, CrateProperties};
 use crate::cpp_data::*;
 use crate::cpp_ffi_data::*;
 use crate::cpp_ffi_generator::NewFfiFunctionKind;
 use crate::cpp_function::*;
+use crate::cpp_operator::CppOperator;
 use crate::cpp_type::*;
+use crate::database::{Database, DatabaseClient, IndexedDatabase};
 use itertools::Itertools;
+use ritual_common::ReadOnly;
+use std::path::PathBuf;
 
 #[test]
 fn cpp_method_kind() {
@@ -42,6 +47,10 @@ pub fn empty_regular_method() -> CppFunction {
         allows_variadic_arguments: false,
         operator: None,
         declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_deleted: false,
         cast: None,
     }
 }
@@ -177,6 +186,7 @@ fn to_ffi(function: &CppFunction, force_stack: Option<CppPath>) -> CppFfiFunctio
         },
         &movable_types,
         &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+        None,
     )
     .unwrap()
 }
@@ -404,6 +414,51 @@ fn c_signature_constructor() {
     );
 }
 
+#[test]
+fn c_signature_move_constructor() {
+    let class_type = CppType::Class(CppPath::from_good_str("MyClass"));
+    let mut method1 = empty_regular_method();
+    method1.path = CppPath::from_good_str("MyClass::MyClass");
+    method1.member = Some({
+        let mut info = empty_membership();
+        info.kind = CppFunctionKind::Constructor;
+        info
+    });
+    method1.arguments.push(CppFunctionArgument {
+        argument_type: CppType::PointerLike {
+            kind: CppPointerLikeTypeKind::RValueReference,
+            is_const: false,
+            target: Box::new(class_type.clone()),
+        },
+        name: "other".to_string(),
+        has_default_value: false,
+    });
+
+    assert!(method1.is_move_constructor());
+    assert!(!method1.is_copy_constructor());
+
+    let r_stack = to_ffi(&method1, Some(CppPath::from_good_str("MyClass")));
+    assert_eq!(r_stack.arguments.len(), 2);
+    assert_eq!(r_stack.arguments[0].name, "other");
+    assert_eq!(
+        r_stack.arguments[0].argument_type.ffi_type(),
+        &CppType::new_pointer(false, class_type.clone()),
+    );
+    assert_eq!(
+        r_stack.arguments[0].argument_type.conversion(),
+        &CppToFfiTypeConversion::RValueReferenceToPointer
+    );
+    assert_eq!(
+        r_stack.arguments[1].meaning,
+        CppFfiArgumentMeaning::ReturnValue
+    );
+
+    let mut copy_constructor = method1.clone();
+    copy_constructor.arguments[0].argument_type = CppType::new_reference(true, class_type);
+    assert!(!copy_constructor.is_move_constructor());
+    assert!(copy_constructor.is_copy_constructor());
+}
+
 #[test]
 fn c_signature_destructor() {
     let mut method1 = empty_regular_method();
@@ -453,6 +508,23 @@ fn c_signature_destructor() {
     assert!(r_heap.return_type.ffi_type().is_void());
 }
 
+#[test]
+fn destructor_ffi_caption() {
+    let mut method1 = empty_regular_method();
+    method1.path = CppPath::from_good_str("ns::MyClass::~MyClass");
+    method1.member = Some({
+        let mut info = empty_membership();
+        info.kind = CppFunctionKind::Destructor;
+        info
+    });
+
+    let r_heap = to_ffi(&method1, None);
+    assert_eq!(r_heap.path.to_cpp_code().unwrap(), "_ns_MyClass_delete");
+
+    let r_stack = to_ffi(&method1, Some(CppPath::from_good_str("ns::MyClass")));
+    assert_eq!(r_stack.path.to_cpp_code().unwrap(), "_ns_MyClass_drop");
+}
+
 #[test]
 fn c_signature_method_returning_class() {
     let mut method1 = empty_regular_method();
@@ -623,9 +695,734 @@ fn short_text1() {
         allows_variadic_arguments: false,
         cast: None,
         declaration_code: None,
+        calling_convention: None,
+        purity: None,
+        is_noexcept: false,
+        is_deleted: false,
     };
     assert_eq!(
         method.short_text(),
         "protected int Class1::method1(int arg1, double arg2 = …) const"
     );
 }
+
+#[test]
+fn normalized_signature() {
+    let mut int_overload = empty_regular_method();
+    int_overload.path = CppPath::from_good_str("Class1::set");
+    int_overload.member = Some(empty_membership());
+    int_overload.arguments = vec![CppFunctionArgument {
+        argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        name: "value".to_string(),
+        has_default_value: true,
+    }];
+    let mut string_overload = int_overload.clone();
+    string_overload.arguments[0].argument_type =
+        CppType::new_reference(true, CppType::Class(CppPath::from_good_str("QString")));
+    if let Some(member) = &mut string_overload.member {
+        member.is_const = true;
+    }
+
+    assert_eq!(int_overload.normalized_signature(), "Class1::set(int)");
+    assert_eq!(
+        string_overload.normalized_signature(),
+        "Class1::set(const QString&) const"
+    );
+
+    let denied = "Class1::set( const QString & ) const";
+    assert!(string_overload.matches_signature(denied));
+    assert!(!int_overload.matches_signature(denied));
+    assert!(!string_overload.matches_signature("Class1::set(const QString&)"));
+}
+
+#[test]
+fn fixed_array_argument_size() {
+    let mut method = empty_regular_method();
+    method.declaration_code = Some(
+        "void getMatrix ( float out [ 16 ] , QMap < int , int > map , int * ptr = 0 , \
+         char name [ 8 ] = nullptr )"
+            .to_string(),
+    );
+    assert_eq!(method.fixed_array_argument_size(0), Some(16));
+    assert_eq!(method.fixed_array_argument_size(1), None);
+    assert_eq!(method.fixed_array_argument_size(2), None);
+    assert_eq!(method.fixed_array_argument_size(3), Some(8));
+    assert_eq!(method.fixed_array_argument_size(4), None);
+
+    method.declaration_code = Some(
+        "std :: function < void ( int ) > operator ( ) ( QMap < int , QList < int >> map , \
+         bool less = 1 < 2 , QMap < int , int > other = QMap < int , int > ( ) , \
+         float out [ 4 ] )"
+            .to_string(),
+    );
+    assert_eq!(method.fixed_array_argument_size(0), None);
+    assert_eq!(method.fixed_array_argument_size(1), None);
+    assert_eq!(method.fixed_array_argument_size(2), None);
+    assert_eq!(method.fixed_array_argument_size(3), Some(4));
+    assert_eq!(method.fixed_array_argument_size(4), None);
+
+    method.declaration_code = None;
+    assert_eq!(method.fixed_array_argument_size(0), None);
+}
+
+#[test]
+fn array_arguments_have_length() {
+    let mut method = empty_regular_method();
+    let float_ptr =
+        CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Float));
+    let char_ptr = CppType::new_pointer(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Char));
+    method.arguments = vec![
+        CppFunctionArgument {
+            name: "out".to_string(),
+            argument_type: float_ptr.clone(),
+            has_default_value: false,
+        },
+        CppFunctionArgument {
+            name: "ptr".to_string(),
+            argument_type: float_ptr.clone(),
+            has_default_value: false,
+        },
+        CppFunctionArgument {
+            name: "name".to_string(),
+            argument_type: char_ptr.clone(),
+            has_default_value: false,
+        },
+    ];
+    method.declaration_code = Some(
+        "void getMatrix ( float out [ 16 ] , float * ptr , const char name [ 8 ] )".to_string(),
+    );
+
+    let mut r = to_ffi(&method, None);
+    let meanings = |r: &CppFfiFunction| {
+        r.arguments
+            .iter()
+            .map(|arg| arg.meaning.clone())
+            .collect_vec()
+    };
+    // the length is only added if enabled in the config
+    assert_eq!(
+        meanings(&r),
+        vec![
+            CppFfiArgumentMeaning::Argument(0),
+            CppFfiArgumentMeaning::Argument(1),
+            CppFfiArgumentMeaning::Argument(2),
+        ]
+    );
+
+    crate::cpp_ffi_generator::add_array_length_arguments(&mut r, &method).unwrap();
+    assert_eq!(
+        meanings(&r),
+        vec![
+            CppFfiArgumentMeaning::Argument(0),
+            CppFfiArgumentMeaning::ArrayLength(0),
+            CppFfiArgumentMeaning::Argument(1),
+            CppFfiArgumentMeaning::Argument(2),
+            CppFfiArgumentMeaning::ArrayLength(2),
+        ]
+    );
+    assert_eq!(r.arguments[0].argument_type.ffi_type(), &float_ptr);
+    assert_eq!(r.arguments[1].name, "out_len");
+    assert_eq!(
+        r.arguments[1].argument_type.ffi_type(),
+        &CppType::PointerSizedInteger {
+            path: CppPath::from_good_str("size_t"),
+            is_signed: false,
+        }
+    );
+    assert_eq!(r.arguments[3].argument_type.ffi_type(), &char_ptr);
+    assert_eq!(r.arguments[4].name, "name_len");
+}
+
+#[test]
+fn noexcept_propagation() {
+    let mut method = empty_regular_method();
+    method.is_noexcept = true;
+    method.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    assert!(to_ffi(&method, None).is_noexcept);
+
+    // copying the argument can throw
+    method.arguments = vec![CppFunctionArgument {
+        name: "arg1".to_string(),
+        argument_type: CppType::Class(CppPath::from_good_str("QString")),
+        has_default_value: false,
+    }];
+    assert!(!to_ffi(&method, None).is_noexcept);
+
+    // allocating the return value can throw
+    method.arguments.clear();
+    method.return_type = CppType::Class(CppPath::from_good_str("QString"));
+    assert!(!to_ffi(&method, None).is_noexcept);
+
+    method.is_noexcept = false;
+    method.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    assert!(!to_ffi(&method, None).is_noexcept);
+}
+
+#[test]
+fn exception_output() {
+    let mut method = empty_regular_method();
+    method.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let mut r = to_ffi(&method, None);
+    crate::cpp_ffi_generator::add_exception_output(&mut r, &method).unwrap();
+    assert_eq!(r.arguments.len(), 1);
+    assert_eq!(
+        r.arguments[0].meaning,
+        CppFfiArgumentMeaning::ExceptionOutput
+    );
+    assert_eq!(
+        r.arguments[0].argument_type.ffi_type(),
+        &CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Char))
+    );
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+    );
+
+    method.is_noexcept = true;
+    let mut r = to_ffi(&method, None);
+    crate::cpp_ffi_generator::add_exception_output(&mut r, &method).unwrap();
+    assert!(r.arguments.is_empty());
+}
+
+#[test]
+fn deduced_return_type() {
+    let mut method1 = empty_regular_method();
+    assert!(!method1.has_deduced_return_type());
+
+    method1.declaration_code = Some("int f ( int x )".to_string());
+    assert!(!method1.has_deduced_return_type());
+
+    method1.declaration_code = Some("auto f ( int x ) -> int".to_string());
+    assert!(method1.has_deduced_return_type());
+
+    method1.declaration_code = Some("auto f ( T x )".to_string());
+    assert!(method1.has_deduced_return_type());
+
+    method1.declaration_code = Some("decltype ( T ( ) + U ( ) ) f ( T x , U y )".to_string());
+    assert!(method1.has_deduced_return_type());
+
+    method1.declaration_code = Some("void f ( auto_ptr < int > x )".to_string());
+    assert!(!method1.has_deduced_return_type());
+}
+
+#[test]
+fn virtual_base_forces_heap_allocation() {
+    let mut db = DatabaseClient::new(
+        IndexedDatabase::new(
+            Database::empty("ritual_test_relocation".into()),
+            PathBuf::new(),
+        ),
+        ReadOnly::new(Vec::new()),
+    );
+    db.add_cpp_item(
+        None,
+        CppItem::ClassBase(CppBaseSpecifier {
+            base_class_type: CppPath::from_good_str("Base"),
+            base_index: 0,
+            is_virtual: true,
+            visibility: CppVisibility::Public,
+            derived_class_type: CppPath::from_good_str("Derived"),
+        }),
+    )
+    .unwrap();
+    db.add_cpp_item(
+        None,
+        CppItem::ClassField(CppClassField {
+            path: CppPath::from_good_str("Holder::derived"),
+            field_type: CppType::Class(CppPath::from_good_str("Derived")),
+            visibility: CppVisibility::Public,
+            is_static: false,
+            is_const: false,
+            array_size: None,
+        }),
+    )
+    .unwrap();
+
+    let movable_types = crate::cpp_ffi_generator::remove_non_relocatable_types(
+        &db,
+        vec![
+            CppPath::from_good_str("Derived"),
+            CppPath::from_good_str("Holder"),
+            CppPath::from_good_str("Other"),
+            CppPath::from_good_str("Forced"),
+        ],
+        &[CppPath::from_good_str("Forced")],
+    );
+    assert_eq!(movable_types, vec![CppPath::from_good_str("Other")]);
+
+    let mut method1 = empty_regular_method();
+    method1.return_type = CppType::Class(CppPath::from_good_str("Derived"));
+    let r = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1,
+        },
+        &movable_types,
+        &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert!(r.arguments.is_empty());
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("Derived"))),
+    );
+    assert_eq!(r.allocation_place, ReturnValueAllocationPlace::Heap);
+}
+
+#[test]
+fn heterogeneous_comparison_operand_types() {
+    let class_a = CppType::Class(CppPath::from_good_str("A"));
+    let class_b = CppType::Class(CppPath::from_good_str("B"));
+
+    let mut free_operator = empty_regular_method();
+    free_operator.path = CppPath::from_good_str("operator==");
+    free_operator.operator = Some(CppOperator::EqualTo);
+    free_operator.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Bool);
+    free_operator.arguments = vec![
+        CppFunctionArgument {
+            argument_type: CppType::new_reference(true, class_a.clone()),
+            name: "a".to_string(),
+            has_default_value: false,
+        },
+        CppFunctionArgument {
+            argument_type: CppType::new_reference(true, class_b.clone()),
+            name: "b".to_string(),
+            has_default_value: false,
+        },
+    ];
+    assert_eq!(
+        free_operator.operand_types(),
+        Some(vec![class_a.clone(), class_b.clone()])
+    );
+
+    let mut member_operator = free_operator.clone();
+    // `CppPath::from_str` can't parse the name of this operator
+    member_operator.path = CppPath::from_items(vec![
+        CppPathItem {
+            name: "A".into(),
+            template_arguments: None,
+        },
+        CppPathItem {
+            name: "operator<=>".into(),
+            template_arguments: None,
+        },
+    ]);
+    member_operator.operator = Some(CppOperator::ThreeWayComparison);
+    member_operator.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    member_operator.arguments.remove(0);
+    assert_eq!(
+        member_operator.operand_types(),
+        free_operator.operand_types()
+    );
+    assert!(!member_operator.argument_types_equal(&free_operator));
+
+    assert_eq!(empty_regular_method().operand_types(), None);
+}
+
+#[test]
+fn ffi_function_purity() {
+    let mut method1 = empty_regular_method();
+    method1.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    method1.purity = Some(CppFunctionPurity::Const);
+    let to_ffi = |method: &CppFunction| {
+        crate::cpp_ffi_generator::to_ffi_method(
+            NewFfiFunctionKind::Function {
+                cpp_function: method.clone(),
+            },
+            &[],
+            &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+            None,
+        )
+        .unwrap()
+    };
+    assert_eq!(to_ffi(&method1).purity, Some(CppFunctionPurity::Const));
+
+    // the wrapper reads the object through `this_ptr`
+    method1.path = CppPath::from_good_str("Class1::method1");
+    method1.member = Some(empty_membership());
+    assert_eq!(to_ffi(&method1).purity, Some(CppFunctionPurity::Pure));
+
+    // the wrapper allocates the return value
+    method1.return_type = CppType::Class(CppPath::from_good_str("Class2"));
+    assert_eq!(to_ffi(&method1).purity, None);
+}
+
+#[test]
+fn optional_return_type() {
+    let mut path = CppPath::from_good_str("std::optional");
+    path.last_mut().template_arguments =
+        Some(vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Int)]);
+    let mut method1 = empty_regular_method();
+    method1.return_type = CppType::Class(path);
+    assert_eq!(
+        method1.return_type.optional_value_type(),
+        Some(&CppType::BuiltInNumeric(CppBuiltInNumericType::Int))
+    );
+
+    let r = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1.clone(),
+        },
+        &[],
+        &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+    );
+    assert_eq!(
+        r.return_type.conversion(),
+        &CppToFfiTypeConversion::OptionalToValue
+    );
+    assert_eq!(
+        r.allocation_place,
+        ReturnValueAllocationPlace::NotApplicable
+    );
+    assert_eq!(r.arguments.len(), 1);
+    assert_eq!(r.arguments[0].meaning, CppFfiArgumentMeaning::HasValue);
+    assert_eq!(
+        r.arguments[0].argument_type.ffi_type(),
+        &CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Bool))
+    );
+
+    let mut path = CppPath::from_good_str("std::optional");
+    path.last_mut().template_arguments =
+        Some(vec![CppType::Class(CppPath::from_good_str("Class1"))]);
+    method1.return_type = CppType::Class(path);
+    assert!(crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1,
+        },
+        &[],
+        &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+        None,
+    )
+    .is_err());
+}
+
+#[test]
+fn variadic_function_does_not_reserve_name() {
+    let mut name_provider = crate::cpp_ffi_generator::FfiNameProvider::testing();
+    let mut method1 = empty_regular_method();
+    method1.allows_variadic_arguments = true;
+    let r = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1.clone(),
+        },
+        &[],
+        &mut name_provider,
+        None,
+    );
+    assert!(r.is_err());
+
+    method1.allows_variadic_arguments = false;
+    let r = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1,
+        },
+        &[],
+        &mut name_provider,
+        None,
+    )
+    .unwrap();
+    assert_eq!(r.path, CppPath::from_good_str("_empty"));
+}
+
+#[test]
+fn const_reference_return_type() {
+    let qstring = CppType::Class(CppPath::from_good_str("QString"));
+    let mut method1 = empty_regular_method();
+    method1.return_type = CppType::new_reference(true, qstring.clone());
+    let r = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1,
+        },
+        &[CppPath::from_good_str("QString")],
+        &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert!(r.arguments.is_empty());
+    assert_eq!(
+        r.return_type.conversion(),
+        &CppToFfiTypeConversion::ReferenceToPointer
+    );
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::new_pointer(true, qstring)
+    );
+    assert_eq!(
+        r.allocation_place,
+        ReturnValueAllocationPlace::NotApplicable
+    );
+}
+
+#[test]
+fn conversion_operator() {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let mut method1 = empty_regular_method();
+    method1.path = CppPath::from_good_str("MyClass::operator int");
+    method1.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    method1.operator = Some(CppOperator::Conversion(int.clone()));
+    method1.return_type = int.clone();
+    let r = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1,
+        },
+        &[],
+        &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(r.path, CppPath::from_good_str("_MyClass_convert_to_int"));
+    assert_eq!(r.arguments.len(), 1);
+    assert_eq!(r.arguments[0].meaning, CppFfiArgumentMeaning::This);
+    assert_eq!(r.return_type.ffi_type(), &int);
+}
+
+#[test]
+fn heap_variant_of_movable_return_type() {
+    let class_type = CppType::Class(CppPath::from_good_str("Movable1"));
+    let mut method1 = empty_regular_method();
+    method1.return_type = class_type.clone();
+    let movable_types = vec![CppPath::from_good_str("Movable1")];
+    let mut name_provider = crate::cpp_ffi_generator::FfiNameProvider::testing();
+
+    let stack = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1.clone(),
+        },
+        &movable_types,
+        &mut name_provider,
+        None,
+    )
+    .unwrap();
+    assert_eq!(stack.allocation_place, ReturnValueAllocationPlace::Stack);
+    assert_eq!(stack.arguments.len(), 1);
+    assert!(!stack.is_heap_variant);
+
+    let heap = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method1,
+        },
+        &movable_types,
+        &mut name_provider,
+        Some(ReturnValueAllocationPlace::Heap),
+    )
+    .unwrap();
+    assert_eq!(heap.path, CppPath::from_good_str("_empty_heap"));
+    assert_eq!(heap.allocation_place, ReturnValueAllocationPlace::Heap);
+    assert!(heap.arguments.is_empty());
+    assert_eq!(
+        heap.return_type.ffi_type(),
+        &CppType::new_pointer(false, class_type.clone())
+    );
+    assert!(heap.is_heap_variant);
+
+    // conversion operators keep their caption
+    let mut method2 = empty_regular_method();
+    method2.path = CppPath::from_good_str("MyClass::operator Movable1");
+    method2.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    method2.operator = Some(CppOperator::Conversion(class_type.clone()));
+    method2.return_type = class_type;
+    let heap2 = crate::cpp_ffi_generator::to_ffi_method(
+        NewFfiFunctionKind::Function {
+            cpp_function: method2,
+        },
+        &movable_types,
+        &mut name_provider,
+        Some(ReturnValueAllocationPlace::Heap),
+    )
+    .unwrap();
+    assert_eq!(
+        heap2.path,
+        CppPath::from_good_str("_MyClass_convert_to_Movable1_heap")
+    );
+}
+
+#[test]
+fn movable_type_overrides() {
+    let movable_types = crate::cpp_ffi_generator::apply_movable_type_overrides(
+        vec![CppPath::from_good_str("Parsed1")],
+        &[
+            CppPath::from_good_str("Allowed1"),
+            CppPath::from_good_str("Denied1"),
+        ],
+        &[
+            CppPath::from_good_str("Parsed1"),
+            CppPath::from_good_str("Denied1"),
+        ],
+    );
+    assert_eq!(movable_types, vec![CppPath::from_good_str("Allowed1")]);
+
+    let ffi_for = |path: &str, kind: CppFunctionKind| {
+        let mut method = empty_regular_method();
+        method.path = CppPath::from_good_str(&format!("{}::method", path));
+        if kind == CppFunctionKind::Regular {
+            method.return_type = CppType::Class(CppPath::from_good_str(path));
+        }
+        method.member = Some(CppFunctionMemberData {
+            kind,
+            ..empty_membership()
+        });
+        crate::cpp_ffi_generator::to_ffi_method(
+            NewFfiFunctionKind::Function {
+                cpp_function: method,
+            },
+            &movable_types,
+            &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+            None,
+        )
+        .unwrap()
+    };
+
+    // denied types are allocated on the heap
+    for &path in &["Parsed1", "Denied1"] {
+        let getter = ffi_for(path, CppFunctionKind::Regular);
+        assert_eq!(getter.allocation_place, ReturnValueAllocationPlace::Heap);
+        assert_eq!(
+            getter.return_type.ffi_type(),
+            &CppType::new_pointer(false, CppType::Class(CppPath::from_good_str(path))),
+        );
+        let destructor = ffi_for(path, CppFunctionKind::Destructor);
+        assert_eq!(
+            destructor.allocation_place,
+            ReturnValueAllocationPlace::Heap
+        );
+    }
+
+    let getter = ffi_for("Allowed1", CppFunctionKind::Regular);
+    assert_eq!(getter.allocation_place, ReturnValueAllocationPlace::Stack);
+    let destructor = ffi_for("Allowed1", CppFunctionKind::Destructor);
+    assert_eq!(
+        destructor.allocation_place,
+        ReturnValueAllocationPlace::Stack
+    );
+}
+
+#[test]
+fn allocation_place_hook() {
+    let mut db = DatabaseClient::new(
+        IndexedDatabase::new(
+            Database::empty("ritual_test_allocation_place".into()),
+            PathBuf::new(),
+        ),
+        ReadOnly::new(Vec::new()),
+    );
+    for name in &["Movable1", "Movable2", "Class1"] {
+        db.add_cpp_item(
+            None,
+            CppItem::Type(CppTypeDeclaration {
+                path: CppPath::from_good_str(name),
+                kind: CppTypeDeclarationKind::Class {
+                    is_pod: false,
+                    is_polymorphic: false,
+                },
+            }),
+        )
+        .unwrap();
+    }
+    let hook = |path: &CppPath| match path.to_cpp_pseudo_code().as_str() {
+        "Movable1" => Some(ReturnValueAllocationPlace::Heap),
+        "Class1" => Some(ReturnValueAllocationPlace::Stack),
+        _ => None,
+    };
+    let movable_types = crate::cpp_ffi_generator::apply_allocation_place_hook(
+        &db,
+        vec![
+            CppPath::from_good_str("Movable1"),
+            CppPath::from_good_str("Movable2"),
+        ],
+        &hook,
+    );
+    assert_eq!(
+        movable_types,
+        vec![
+            CppPath::from_good_str("Movable2"),
+            CppPath::from_good_str("Class1"),
+        ]
+    );
+
+    // the hook can't force stack allocation for non-relocatable types
+    db.add_cpp_item(
+        None,
+        CppItem::ClassBase(CppBaseSpecifier {
+            base_class_type: CppPath::from_good_str("Movable2"),
+            base_index: 0,
+            is_virtual: true,
+            visibility: CppVisibility::Public,
+            derived_class_type: CppPath::from_good_str("Class1"),
+        }),
+    )
+    .unwrap();
+    let mut config = Config::new(CrateProperties::new(
+        "ritual_test_allocation_place",
+        "0.0.0",
+    ));
+    config.add_movable_type(CppPath::from_good_str("Movable1"));
+    config.add_movable_type(CppPath::from_good_str("Movable2"));
+    config.set_allocation_place_hook(hook);
+    assert_eq!(
+        crate::cpp_ffi_generator::stack_allocated_types(&db, &config),
+        vec![CppPath::from_good_str("Movable2")]
+    );
+}
+
+#[test]
+fn equality_operator_wrapper() {
+    let class_a = CppType::Class(CppPath::from_good_str("A"));
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("A::operator==");
+    method.operator = Some(CppOperator::EqualTo);
+    method.member = Some(CppFunctionMemberData {
+        is_virtual: false,
+        is_pure_virtual: false,
+        is_const: true,
+        is_static: false,
+        visibility: CppVisibility::Public,
+        is_signal: false,
+        is_slot: false,
+        kind: CppFunctionKind::Regular,
+    });
+    method.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Bool);
+    method.arguments = vec![CppFunctionArgument {
+        argument_type: CppType::new_reference(true, class_a.clone()),
+        name: "other".to_string(),
+        has_default_value: false,
+    }];
+
+    // the Rust generator implements `PartialEq` based on the operator
+    // of the source C++ function, so the wrapper is a regular FFI function
+    let r = to_ffi(&method, None);
+    assert_eq!(r.kind, CppFfiFunctionKind::Function);
+    assert_eq!(r.arguments.len(), 2);
+    assert_eq!(r.arguments[0].meaning, CppFfiArgumentMeaning::This);
+    assert_eq!(
+        r.arguments[0].argument_type.ffi_type(),
+        &CppType::new_pointer(true, class_a.clone())
+    );
+    assert_eq!(r.arguments[1].meaning, CppFfiArgumentMeaning::Argument(0));
+    assert_eq!(
+        r.arguments[1].argument_type.ffi_type(),
+        &CppType::new_pointer(true, class_a)
+    );
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)
+    );
+    assert_eq!(
+        r.allocation_place,
+        ReturnValueAllocationPlace::NotApplicable
+    );
+}
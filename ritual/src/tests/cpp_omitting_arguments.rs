@@ -0,0 +1,48 @@
+use crate::cpp_function::CppFunctionArgument;
+use crate::cpp_omitting_arguments::omit_default_arguments;
+use crate::cpp_type::{CppBuiltInNumericType, CppType};
+use crate::tests::cpp_method::empty_regular_method;
+
+fn argument(name: &str, has_default_value: bool) -> CppFunctionArgument {
+    CppFunctionArgument {
+        argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        name: name.to_string(),
+        has_default_value,
+    }
+}
+
+fn argument_names(arguments: &[CppFunctionArgument]) -> Vec<&str> {
+    arguments.iter().map(|arg| arg.name.as_str()).collect()
+}
+
+#[test]
+fn trailing_default_arguments() {
+    let mut function = empty_regular_method();
+    function.arguments = vec![
+        argument("a", false),
+        argument("b", true),
+        argument("c", true),
+    ];
+    let functions = omit_default_arguments(&function).unwrap();
+    assert_eq!(functions.len(), 2);
+    assert_eq!(argument_names(&functions[0].arguments), vec!["a", "b"]);
+    assert_eq!(argument_names(&functions[1].arguments), vec!["a"]);
+}
+
+#[test]
+fn no_default_arguments() {
+    let mut function = empty_regular_method();
+    function.arguments = vec![argument("a", false)];
+    assert!(omit_default_arguments(&function).unwrap().is_empty());
+}
+
+#[test]
+fn non_trailing_default_arguments() {
+    let mut function = empty_regular_method();
+    function.arguments = vec![
+        argument("a", true),
+        argument("b", false),
+        argument("c", true),
+    ];
+    assert!(omit_default_arguments(&function).is_err());
+}
@@ -7,3 +7,11 @@ fn info1() {
     assert_eq!(info.arguments_count, 2);
     assert_eq!(info.allows_variadic_arguments, false);
 }
+
+#[test]
+fn info_three_way_comparison() {
+    let info = CppOperator::ThreeWayComparison.info();
+    assert_eq!(info.function_name_suffix.unwrap(), "<=>");
+    assert_eq!(info.arguments_count, 2);
+    assert!(CppOperator::all().contains(&CppOperator::ThreeWayComparison));
+}
@@ -100,10 +100,27 @@ fn simple_func() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("int func1 ( int x )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
 }
 
+#[test]
+fn pure_and_const_functions() {
+    let data = run_parser(
+        "
+        [[gnu::pure]] int func1(int x);
+        [[gnu::const]] int func2(int x);
+        ",
+    );
+    assert_eq!(data.methods.len(), 2);
+    assert_eq!(data.methods[0].purity, Some(CppFunctionPurity::Pure));
+    assert_eq!(data.methods[1].purity, Some(CppFunctionPurity::Const));
+}
+
 #[test]
 fn simple_func_with_default_value() {
     let data = run_parser(
@@ -130,6 +147,10 @@ fn simple_func_with_default_value() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("bool func1 ( int x = 42 )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
 }
@@ -195,6 +216,10 @@ fn functions_with_class_arg() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("bool func1 ( Magic x )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
     assert_eq!(
@@ -215,6 +240,10 @@ fn functions_with_class_arg() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("bool func1 ( Magic * x )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
     assert_eq!(
@@ -235,10 +264,154 @@ fn functions_with_class_arg() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("bool func2 ( const Magic & )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
 }
 
+#[test]
+fn const_and_protected_fields() {
+    let data = run_parser(
+        "
+        class Magic {
+        public:
+            const int a;
+        protected:
+            int b;
+        };
+        ",
+    );
+    assert_eq!(data.fields.len(), 2);
+    assert_eq!(data.fields[0].path, CppPath::from_good_str("Magic::a"));
+    assert_eq!(
+        data.fields[0].field_type,
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+    );
+    assert_eq!(data.fields[0].visibility, CppVisibility::Public);
+    assert_eq!(data.fields[0].is_const, true);
+
+    assert_eq!(data.fields[1].path, CppPath::from_good_str("Magic::b"));
+    assert_eq!(data.fields[1].visibility, CppVisibility::Protected);
+    assert_eq!(data.fields[1].is_const, false);
+}
+
+#[test]
+fn array_field() {
+    let data = run_parser(
+        "
+        class Magic {
+        public:
+            double values[4];
+            int count;
+        };
+        ",
+    );
+    assert_eq!(data.fields.len(), 2);
+    assert_eq!(data.fields[0].path, CppPath::from_good_str("Magic::values"));
+    assert_eq!(
+        data.fields[0].field_type,
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
+    );
+    assert_eq!(data.fields[0].array_size, Some(4));
+    assert_eq!(data.fields[1].array_size, None);
+}
+
+#[test]
+fn polymorphic_classes() {
+    let data = run_parser(
+        "
+        class Plain {
+        public:
+            int method();
+        };
+        class Interface {
+        public:
+            virtual ~Interface();
+        };
+        class Impl : public Plain, public Interface {};
+        class PlainDerived : public Plain {};
+        ",
+    );
+    assert_eq!(data.types.len(), 4);
+    let is_polymorphic = |name: &str| {
+        data.types
+            .iter()
+            .find(|t| t.path == CppPath::from_good_str(name))
+            .unwrap()
+            .kind
+            .is_polymorphic()
+    };
+    assert!(!is_polymorphic("Plain"));
+    assert!(is_polymorphic("Interface"));
+    assert!(is_polymorphic("Impl"));
+    assert!(!is_polymorphic("PlainDerived"));
+}
+
+#[test]
+fn noexcept_functions() {
+    let data = run_parser(
+        "
+        int func1() noexcept;
+        int func2() noexcept(true);
+        int func3() noexcept(false);
+        int func4() throw();
+        int func5();
+        template<typename T>
+        void func6(T value) noexcept(noexcept(T(value)));
+        void func7(bool value = noexcept(true));
+        struct Functor {
+            int operator()() const noexcept;
+            bool operator<(const Functor& other) const;
+        };
+        ",
+    );
+    let is_noexcept = |name: &str| {
+        data.methods
+            .iter()
+            .find(|m| m.path.to_cpp_pseudo_code() == name)
+            .unwrap()
+            .is_noexcept
+    };
+    assert!(is_noexcept("func1"));
+    assert!(is_noexcept("func2"));
+    assert!(!is_noexcept("func3"));
+    assert!(is_noexcept("func4"));
+    assert!(!is_noexcept("func5"));
+    assert!(!is_noexcept("func6"));
+    assert!(!is_noexcept("func7"));
+    assert!(is_noexcept("Functor::operator()"));
+    assert!(!is_noexcept("Functor::operator<"));
+}
+
+#[test]
+fn deleted_functions() {
+    let data = run_parser(
+        "
+        class NonCopyable {
+        public:
+            NonCopyable();
+            NonCopyable(const NonCopyable& other) = delete;
+            NonCopyable& operator=(const NonCopyable& other) = delete;
+        };
+        void func1(int) = delete;
+        void func1(double);
+        ",
+    );
+    assert_eq!(data.methods.iter().filter(|m| m.is_deleted).count(), 3);
+    assert!(data
+        .methods
+        .iter()
+        .filter(|m| m.is_copy_constructor())
+        .all(|m| m.is_deleted));
+    assert!(data
+        .methods
+        .iter()
+        .any(|m| m.path == CppPath::from_good_str("func1") && !m.is_deleted));
+}
+
 #[test]
 fn free_operator() {
     let data = run_parser(
@@ -288,6 +461,10 @@ fn variadic_func() {
             allows_variadic_arguments: true,
             cast: None,
             declaration_code: Some("int my_printf ( const char * format , ... )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
 }
@@ -335,6 +512,10 @@ fn free_template_func() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("template < typename T > T abs ( T value )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
 }
@@ -376,6 +557,10 @@ fn free_func_operator_sub() {
                 allows_variadic_arguments: false,
                 cast: None,
                 declaration_code: Some("C1 operator - ( C1 a , C1 b )".to_string()),
+                calling_convention: None,
+                purity: None,
+                is_noexcept: false,
+                is_deleted: false,
             }
         );
     }
@@ -425,6 +610,10 @@ fn simple_class_method() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("int func1 ( int x )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
 }
@@ -619,6 +808,10 @@ fn template_class_method() {
             allows_variadic_arguments: false,
             cast: None,
             declaration_code: Some("T get ( int index )".to_string()),
+            calling_convention: None,
+            purity: None,
+            is_noexcept: false,
+            is_deleted: false,
         }
     );
     assert_eq!(
@@ -1169,6 +1362,36 @@ fn function_pointer() {
     assert_eq!(arg1.has_default_value, false);
 }
 
+#[test]
+fn member_function_pointer() {
+    let data = run_parser(
+        "
+        class A {
+        public:
+            int get(bool) const;
+        };
+        void call(A* object, int (A::*getter)(bool) const);
+        ",
+    );
+    let function = data
+        .methods
+        .iter()
+        .find(|m| m.path == CppPath::from_good_str("call"))
+        .unwrap();
+    assert_eq!(function.arguments.len(), 2);
+    let arg1 = &function.arguments[1];
+    assert_eq!(arg1.name, "getter");
+    assert_eq!(
+        arg1.argument_type,
+        CppType::MemberFunctionPointer(CppMemberFunctionPointerType {
+            class_path: CppPath::from_good_str("A"),
+            is_const: true,
+            return_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Int)),
+            arguments: vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)],
+        })
+    );
+}
+
 #[test]
 fn template_conversion_operator() {
     let data = run_parser(
@@ -0,0 +1,58 @@
+use crate::cpp_data::{CppPath, CppPathItem};
+use crate::cpp_function::{CppFunction, CppFunctionArgument};
+use crate::cpp_template_instantiator::instantiate_return_type;
+use crate::cpp_type::{CppBuiltInNumericType, CppTemplateParameter, CppType};
+use crate::tests::cpp_method::{empty_membership, empty_regular_method};
+
+fn template_parameter() -> CppType {
+    CppType::TemplateParameter(CppTemplateParameter {
+        nested_level: 0,
+        index: 0,
+        name: "T".into(),
+    })
+}
+
+fn get_function() -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("ns::Class1").join(CppPathItem {
+        name: "get".into(),
+        template_arguments: Some(vec![template_parameter()]),
+    });
+    function.member = Some(empty_membership());
+    function.return_type = template_parameter();
+    function
+}
+
+#[test]
+fn return_type_instantiation() {
+    let double = CppType::BuiltInNumeric(CppBuiltInNumericType::Double);
+    let function = instantiate_return_type(&get_function(), &double).unwrap();
+    assert_eq!(
+        function.path,
+        CppPath::from_good_str("ns::Class1").join(CppPathItem {
+            name: "get".into(),
+            template_arguments: Some(vec![double.clone()]),
+        })
+    );
+    assert_eq!(function.return_type, double);
+    assert!(function.arguments.is_empty());
+    assert_eq!(function.member, Some(empty_membership()));
+}
+
+#[test]
+fn return_type_instantiation_inferable() {
+    let mut function = get_function();
+    function.arguments = vec![CppFunctionArgument {
+        name: "value".into(),
+        argument_type: template_parameter(),
+        has_default_value: false,
+    }];
+    let double = CppType::BuiltInNumeric(CppBuiltInNumericType::Double);
+    assert!(instantiate_return_type(&function, &double).is_err());
+}
+
+#[test]
+fn return_type_instantiation_not_template() {
+    let double = CppType::BuiltInNumeric(CppBuiltInNumericType::Double);
+    assert!(instantiate_return_type(&empty_regular_method(), &double).is_err());
+}
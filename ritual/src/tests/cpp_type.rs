@@ -1,9 +1,9 @@
 use crate::cpp_data::{CppPath, CppPathItem};
 use crate::cpp_ffi_data::CppToFfiTypeConversion;
-use crate::cpp_ffi_generator::ffi_type;
+use crate::cpp_ffi_generator::{ffi_type, to_ffi_type};
 use crate::cpp_type::{
-    CppBuiltInNumericType, CppFunctionPointerType, CppSpecificNumericType,
-    CppSpecificNumericTypeKind, CppTemplateParameter, CppType, CppTypeRole,
+    CppBuiltInNumericType, CppFunctionPointerType, CppMemberFunctionPointerType,
+    CppSpecificNumericType, CppSpecificNumericTypeKind, CppTemplateParameter, CppType, CppTypeRole,
 };
 
 fn assert_type_to_ffi_unchanged(t: &CppType) {
@@ -360,6 +360,44 @@ fn function1() {
     assert_type_to_ffi_unchanged(&type1);
 }
 
+#[test]
+fn member_function_pointer() {
+    let type1 = CppType::MemberFunctionPointer(CppMemberFunctionPointerType {
+        class_path: CppPath::from_good_str("QObject"),
+        is_const: true,
+        return_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Int)),
+        arguments: vec![
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+            CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)),
+        ],
+    });
+    assert_eq!(type1.is_function_pointer(), false);
+    assert_eq!(type1.is_member_function_pointer(), true);
+    assert!(type1.to_cpp_code(None).is_err());
+    assert_eq!(
+        type1.to_cpp_code(Some("")).unwrap(),
+        "int (QObject::*)(int, bool*) const"
+    );
+    assert_eq!(
+        type1.to_cpp_code(Some("func")).unwrap(),
+        "int (QObject::*func)(int, bool*) const"
+    );
+    assert_eq!(type1.ascii_caption(), "QObject_member_fn");
+
+    for role in &[CppTypeRole::NotReturnType, CppTypeRole::ReturnType] {
+        let ffi1 = ffi_type(&type1, *role).unwrap();
+        assert_eq!(ffi1.original_type(), &type1);
+        assert_eq!(
+            ffi1.ffi_type().to_cpp_code(None).unwrap(),
+            "ritual_member_function_pointer"
+        );
+        assert_eq!(
+            ffi1.conversion(),
+            &CppToFfiTypeConversion::MemberFunctionPointerToStruct
+        );
+    }
+}
+
 #[test]
 fn instantiate1() {
     let type1 = CppType::new_reference(
@@ -380,3 +418,72 @@ fn instantiate1() {
         )
     );
 }
+
+#[test]
+fn initializer_list() {
+    let mut path = CppPath::from_good_str("std::initializer_list");
+    path.last_mut().template_arguments =
+        Some(vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Int)]);
+    let type1 = CppType::Class(path);
+    assert!(type1.is_initializer_list());
+    assert_eq!(
+        CppType::new_reference(true, type1.clone()).initializer_list_element_type(),
+        Some(&CppType::BuiltInNumeric(CppBuiltInNumericType::Int))
+    );
+    assert!(CppType::new_reference(true, type1.clone()).is_initializer_list());
+    assert!(!CppType::new_pointer(true, type1).is_initializer_list());
+    assert!(!CppType::Class(CppPath::from_good_str("std::vector")).is_initializer_list());
+}
+
+#[test]
+fn variant_alternatives() {
+    let alternatives = vec![
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        CppType::Class(CppPath::from_good_str("QString")),
+    ];
+    let mut path = CppPath::from_good_str("std::variant");
+    path.last_mut().template_arguments = Some(alternatives.clone());
+    let type1 = CppType::Class(path);
+    assert_eq!(type1.variant_alternatives(), Some(&alternatives[..]));
+    assert_eq!(
+        CppType::new_pointer(true, type1).variant_alternatives(),
+        None
+    );
+    assert_eq!(
+        CppType::Class(CppPath::from_good_str("std::variant")).variant_alternatives(),
+        None
+    );
+}
+
+#[test]
+fn ffi_type_const_override() {
+    let class_type = CppType::Class(CppPath::from_good_str("QPoint"));
+    for role in &[CppTypeRole::NotReturnType, CppTypeRole::ReturnType] {
+        assert_eq!(
+            to_ffi_type(&class_type, *role, None).unwrap(),
+            ffi_type(&class_type, *role).unwrap()
+        );
+        for &is_const in &[false, true] {
+            let ffi = to_ffi_type(&class_type, *role, Some(is_const)).unwrap();
+            assert_eq!(
+                ffi.ffi_type(),
+                &CppType::new_pointer(is_const, class_type.clone())
+            );
+            assert_eq!(
+                ffi.conversion(),
+                &CppToFfiTypeConversion::ValueToPointer {
+                    is_ffi_const: is_const
+                }
+            );
+        }
+    }
+
+    // the override only affects classes passed by value
+    let reference = CppType::new_reference(true, class_type.clone());
+    let ffi = to_ffi_type(&reference, CppTypeRole::NotReturnType, Some(false)).unwrap();
+    assert_eq!(ffi.ffi_type(), &CppType::new_pointer(true, class_type));
+    assert_eq!(
+        ffi.conversion(),
+        &CppToFfiTypeConversion::ReferenceToPointer
+    );
+}
@@ -0,0 +1,51 @@
+use crate::cpp_data::{CppPath, CppPathItem};
+use crate::cpp_type::{CppBuiltInNumericType, CppTemplateParameter, CppType};
+use crate::cpp_variants::variant_functions;
+
+fn variant_path(alternatives: Vec<CppType>) -> CppPath {
+    let mut path = CppPath::from_good_str("std::variant");
+    path.last_mut().template_arguments = Some(alternatives);
+    path
+}
+
+#[test]
+fn variant_index_and_get_if_functions() {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let string = CppType::Class(CppPath::from_good_str("QString"));
+    let path = variant_path(vec![int.clone(), string.clone(), int]);
+    let functions = variant_functions(&path);
+
+    // `std::get_if<int>` is ambiguous because `int` is present twice
+    assert_eq!(functions.len(), 2);
+    assert_eq!(
+        functions[0].path,
+        path.join(CppPathItem::from_good_str("index"))
+    );
+    assert!(functions[0].member.as_ref().unwrap().is_const);
+    assert!(functions[0].arguments.is_empty());
+
+    assert_eq!(functions[1].path.to_templateless_string(), "std::get_if");
+    assert_eq!(
+        functions[1].path.last().template_arguments,
+        Some(vec![string.clone()])
+    );
+    assert_eq!(functions[1].return_type, CppType::new_pointer(true, string));
+    assert_eq!(functions[1].arguments.len(), 1);
+    assert_eq!(
+        functions[1].arguments[0].argument_type,
+        CppType::new_pointer(true, CppType::Class(path))
+    );
+}
+
+#[test]
+fn non_concrete_variants() {
+    assert!(variant_functions(&CppPath::from_good_str("QVariant")).is_empty());
+    assert!(variant_functions(&CppPath::from_good_str("std::variant")).is_empty());
+
+    let template_parameter = CppType::TemplateParameter(CppTemplateParameter {
+        nested_level: 0,
+        index: 0,
+        name: "T".to_string(),
+    });
+    assert!(variant_functions(&variant_path(vec![template_parameter])).is_empty());
+}
@@ -0,0 +1,179 @@
+use crate::config::CrateProperties;
+use crate::crate_writer::{
+    bench_table, criterion_dependency_toml, git_dependency_toml, lib_table, package_table, readme,
+    recursive_merge_toml,
+};
+use ritual_common::toml;
+
+#[test]
+fn git_dependency() {
+    let value = git_dependency_toml("https://example.com/repo.git", "0123abc");
+    let table = value.as_table().unwrap();
+    assert_eq!(table.len(), 2);
+    assert_eq!(
+        table.get("git"),
+        Some(&toml::Value::String("https://example.com/repo.git".into()))
+    );
+    assert_eq!(
+        table.get("rev"),
+        Some(&toml::Value::String("0123abc".into()))
+    );
+    assert!(table.get("version").is_none());
+}
+
+#[test]
+fn merge_toml_arrays() {
+    let array = |values: &[&str]| {
+        toml::Value::Array(
+            values
+                .iter()
+                .map(|value| toml::Value::String(value.to_string()))
+                .collect(),
+        )
+    };
+    let a = array(&["a", "b", "a"]);
+    let b = array(&["c", "b", "c", "d"]);
+    assert_eq!(
+        recursive_merge_toml(a.clone(), b.clone(), false),
+        array(&["a", "b", "a", "c", "b", "c", "d"])
+    );
+    assert_eq!(
+        recursive_merge_toml(a, b, true),
+        array(&["a", "b", "a", "c", "d"])
+    );
+
+    let mut features = toml::value::Table::new();
+    features.insert("ritual_rustdoc".into(), array(&["f1"]));
+    let mut table = toml::value::Table::new();
+    table.insert("features".into(), toml::Value::Table(features));
+    let merged = recursive_merge_toml(
+        toml::Value::Table(table.clone()),
+        toml::Value::Table(table),
+        true,
+    );
+    assert_eq!(merged["features"]["ritual_rustdoc"], array(&["f1"]));
+}
+
+#[test]
+fn edition() {
+    let mut properties = CrateProperties::new("ritual_test_crate", "0.0.0");
+    assert_eq!(
+        package_table(&properties)["edition"],
+        toml::Value::String("2018".into())
+    );
+    properties.set_edition("2021");
+    let package = package_table(&properties);
+    assert_eq!(package["edition"], toml::Value::String("2021".into()));
+
+    // the edition from custom fields wins
+    let table = |package: toml::value::Table| {
+        let mut table = toml::value::Table::new();
+        table.insert("package".into(), toml::Value::Table(package));
+        toml::Value::Table(table)
+    };
+    let mut custom_package = toml::value::Table::new();
+    custom_package.insert("edition".into(), toml::Value::String("2024".into()));
+    let merged = recursive_merge_toml(table(package), table(custom_package), true);
+    assert_eq!(
+        merged["package"]["edition"],
+        toml::Value::String("2024".into())
+    );
+    assert_eq!(
+        merged["package"]["name"],
+        toml::Value::String("ritual_test_crate".into())
+    );
+}
+
+#[test]
+fn crate_types() {
+    let mut properties = CrateProperties::new("ritual_test_crate", "0.0.0");
+    assert!(lib_table(&properties).is_none());
+
+    properties.add_crate_type("rlib");
+    properties.add_crate_type("cdylib");
+    let lib = lib_table(&properties).unwrap();
+    assert_eq!(lib.as_table().unwrap().len(), 1);
+    assert_eq!(
+        lib["crate-type"],
+        toml::Value::Array(vec![
+            toml::Value::String("rlib".into()),
+            toml::Value::String("cdylib".into()),
+        ])
+    );
+}
+
+#[test]
+fn generated_readme() {
+    let properties = CrateProperties::new("qt_core", "0.4.1");
+    let text = readme(&properties, Some("Qt"), Some("5.13.0"));
+    assert!(text.starts_with("# qt_core\n"));
+    assert!(text.contains("Rust bindings to Qt 5.13.0, generated by"));
+    assert!(text.contains("(https://docs.rs/qt_core/0.4.1)"));
+
+    let text = readme(&properties, None, None);
+    assert!(text.contains("Rust bindings to a C++ library, generated by"));
+}
+
+#[test]
+fn package_metadata() {
+    let mut properties = CrateProperties::new("ritual_test_crate", "0.0.0");
+    let package = package_table(&properties);
+    for key in &["authors", "license", "repository", "description"] {
+        assert!(package.get(*key).is_none());
+    }
+
+    properties.add_author("Author 1 <author1@example.com>");
+    properties.set_license("MIT OR Apache-2.0");
+    properties.set_repository("https://example.com/repo");
+    properties.set_description("Test crate");
+    let package = package_table(&properties);
+    assert_eq!(
+        package["authors"],
+        toml::Value::Array(vec![toml::Value::String(
+            "Author 1 <author1@example.com>".into()
+        )])
+    );
+    assert_eq!(
+        package["license"],
+        toml::Value::String("MIT OR Apache-2.0".into())
+    );
+    assert_eq!(
+        package["repository"],
+        toml::Value::String("https://example.com/repo".into())
+    );
+    assert_eq!(
+        package["description"],
+        toml::Value::String("Test crate".into())
+    );
+
+    // custom fields take precedence
+    let mut custom_package = toml::value::Table::new();
+    custom_package.insert("license".into(), toml::Value::String("MIT".into()));
+    let mut a = toml::value::Table::new();
+    a.insert("package".into(), package.into());
+    let mut b = toml::value::Table::new();
+    b.insert("package".into(), custom_package.into());
+    let merged = recursive_merge_toml(a.into(), b.into(), true);
+    assert_eq!(
+        merged["package"]["license"],
+        toml::Value::String("MIT".into())
+    );
+}
+
+#[test]
+fn benchmarks_target() {
+    let criterion = criterion_dependency_toml();
+    assert_eq!(criterion["optional"], toml::Value::Boolean(true));
+
+    let bench = bench_table();
+    assert_eq!(
+        bench["name"],
+        toml::Value::String("ritual_benchmarks".into())
+    );
+    assert_eq!(bench["harness"], toml::Value::Boolean(false));
+    // normal builds must not depend on criterion
+    assert_eq!(
+        bench["required-features"],
+        toml::Value::Array(vec![toml::Value::String("ritual_benchmarks".into())])
+    );
+}
@@ -0,0 +1,48 @@
+use crate::doc_formatter::overload_arguments_doc;
+use crate::rust_info::{RustFfiWrapperData, RustFunction, RustFunctionArgument, RustFunctionKind};
+use crate::rust_type::{
+    RustCommonType, RustFinalType, RustPath, RustToFfiTypeConversion, RustType,
+};
+
+fn argument(name: &str, rust_type: RustType, ffi_index: usize) -> RustFunctionArgument {
+    RustFunctionArgument {
+        argument_type: RustFinalType::new(rust_type, RustToFfiTypeConversion::None).unwrap(),
+        name: name.to_string(),
+        ffi_index,
+    }
+}
+
+#[test]
+fn overload_arguments() {
+    let string_ptr = RustType::new_pointer(
+        true,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("test::QString"),
+            generic_arguments: None,
+        }),
+    );
+    let mut function = RustFunction {
+        is_public: true,
+        is_unsafe: true,
+        path: RustPath::from_good_str("test::QWidget::set_text1"),
+        kind: RustFunctionKind::FfiWrapper(RustFfiWrapperData {
+            ffi_function_path: RustPath::from_good_str("test::ffi::ctr_test_ffi_QWidget_setText1"),
+        }),
+        arguments: vec![
+            argument("self", RustType::new_pointer(false, RustType::unit()), 0),
+            argument("text", string_ptr, 1),
+            argument("index", RustType::Primitive("i32".into()), 2),
+        ],
+        return_type: RustFinalType::new(RustType::unit(), RustToFfiTypeConversion::None).unwrap(),
+    };
+    assert_eq!(
+        overload_arguments_doc(&function, "test"),
+        "This overload takes `text: *const crate::QString`, `index: i32`."
+    );
+
+    function.arguments.truncate(1);
+    assert_eq!(
+        overload_arguments_doc(&function, "test"),
+        "This overload takes no arguments."
+    );
+}
@@ -1,7 +1,20 @@
 #![allow(clippy::cognitive_complexity)]
 
+mod config;
+mod cpp_checks;
+mod cpp_code_generator;
+mod cpp_enums;
 mod cpp_ffi_data;
+mod cpp_ffi_generator;
+mod cpp_inheritance;
 mod cpp_method;
+mod cpp_omitting_arguments;
 mod cpp_operator;
 mod cpp_parser;
+mod cpp_template_instantiator;
 mod cpp_type;
+mod cpp_variants;
+mod crate_writer;
+mod doc_formatter;
+mod rust_code_generator;
+mod rust_generator;
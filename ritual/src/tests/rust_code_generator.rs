@@ -0,0 +1,56 @@
+use crate::config::CallbackPanicPolicy;
+use crate::rust_code_generator::{callback_call_code, rust_type_to_code};
+use crate::rust_type::{
+    RustCommonType, RustFinalType, RustPath, RustToFfiTypeConversion, RustType,
+};
+
+#[test]
+fn closure_to_callback() {
+    let user_data = RustType::new_pointer(
+        false,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("std::ffi::c_void"),
+            generic_arguments: None,
+        }),
+    );
+    let callback = RustType::FunctionPointer {
+        return_type: Box::new(RustType::bool()),
+        arguments: vec![RustType::Primitive("i32".into()), user_data],
+    };
+    let closure = RustFinalType::new(
+        RustType::new_option(callback),
+        RustToFfiTypeConversion::ClosureToCallback,
+    )
+    .unwrap();
+    assert_eq!(
+        closure.api_type(),
+        &RustType::Closure {
+            return_type: Box::new(RustType::bool()),
+            arguments: vec![RustType::Primitive("i32".into())],
+        }
+    );
+    // the callback may be called from another thread
+    assert_eq!(
+        rust_type_to_code(closure.api_type(), None),
+        "impl FnMut(i32) -> bool + Send + 'static"
+    );
+}
+
+#[test]
+fn callback_panic_policy() {
+    let abort = callback_call_code("func(arg0)", CallbackPanicPolicy::Abort);
+    assert_eq!(
+        abort,
+        "match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| func(arg0))) {\n\
+         Ok(value) => value,\n\
+         Err(_) => ::std::process::abort(),\n\
+         }"
+    );
+
+    // the result of the closure is returned from the callback
+    let log = callback_call_code("func(arg0)", CallbackPanicPolicy::LogAndSwallow);
+    assert!(log.contains("Ok(value) => value,"));
+    assert!(log.contains("eprintln!"));
+    assert!(log.contains("Default::default()"));
+    assert!(!log.contains("abort"));
+}
@@ -0,0 +1,290 @@
+use crate::cpp_data::CppPath;
+use crate::cpp_ffi_data::CppFfiFunction;
+use crate::cpp_ffi_generator::NewFfiFunctionKind;
+use crate::cpp_function::{CppFunction, CppFunctionArgument, CppFunctionMemberData};
+use crate::cpp_operator::CppOperator;
+use crate::cpp_type::{CppBuiltInNumericType, CppType};
+use crate::rust_generator::{
+    is_chaining_method, is_matching_unregistration_function, is_ownership_transfer_argument, State,
+    TraitImplInfo,
+};
+use crate::rust_info::{
+    RustFfiWrapperData, RustFunctionArgument, RustFunctionKind, UnnamedRustFunction,
+};
+use crate::rust_type::{
+    RustCommonType, RustFinalType, RustPath, RustToFfiTypeConversion, RustType,
+};
+use crate::tests::cpp_method::{empty_membership, empty_regular_method};
+
+fn class_type(path: &str) -> RustType {
+    RustType::Common(RustCommonType {
+        path: RustPath::from_good_str(path),
+        generic_arguments: None,
+    })
+}
+
+#[test]
+fn heterogeneous_equality_operator() {
+    // operator==(const A&, const B&)
+    let function = UnnamedRustFunction {
+        is_public: true,
+        is_unsafe: false,
+        kind: RustFunctionKind::FfiWrapper(RustFfiWrapperData {
+            ffi_function_path: RustPath::from_good_str("test::ffi::ctr_test_ffi_operator_eq"),
+        }),
+        arguments: vec![
+            RustFunctionArgument {
+                argument_type: RustFinalType::new(
+                    RustType::new_pointer(true, class_type("test::A")),
+                    RustToFfiTypeConversion::UtilsRefToPtr {
+                        force_api_is_const: None,
+                    },
+                )
+                .unwrap(),
+                name: "a".to_string(),
+                ffi_index: 0,
+            },
+            RustFunctionArgument {
+                argument_type: RustFinalType::new(
+                    RustType::new_pointer(true, class_type("test::B")),
+                    RustToFfiTypeConversion::ImplCastInto(Box::new(
+                        RustToFfiTypeConversion::UtilsRefToPtr {
+                            force_api_is_const: None,
+                        },
+                    )),
+                )
+                .unwrap(),
+                name: "b".to_string(),
+                ffi_index: 1,
+            },
+        ],
+        return_type: RustFinalType::new(RustType::bool(), RustToFfiTypeConversion::None).unwrap(),
+    };
+    let info = TraitImplInfo::from_operator(&CppOperator::EqualTo).unwrap();
+    let trait_impl = State::process_operator_as_trait_impl(function, info, "test", &[]).unwrap();
+
+    let ref_b = RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("cpp_core::Ref"),
+        generic_arguments: Some(vec![class_type("test::B")]),
+    });
+    assert_eq!(trait_impl.target_type, class_type("test::A"));
+    assert_eq!(
+        trait_impl.trait_type,
+        RustCommonType {
+            path: RustPath::from_good_str("std::cmp::PartialEq"),
+            generic_arguments: Some(vec![ref_b.clone()]),
+        }
+    );
+    assert_eq!(trait_impl.parent_path, RustPath::from_good_str("test"));
+    assert_eq!(trait_impl.functions.len(), 1);
+    let eq = &trait_impl.functions[0];
+    assert_eq!(eq.path, RustPath::from_good_str("std::cmp::PartialEq::eq"));
+    assert_eq!(eq.arguments[0].name, "self");
+    assert_eq!(
+        eq.arguments[0].argument_type.api_type(),
+        &RustType::new_reference(true, class_type("test::A"))
+    );
+    assert_eq!(
+        eq.arguments[1].argument_type.api_type(),
+        &RustType::new_reference(true, ref_b)
+    );
+}
+
+#[test]
+fn chaining_methods() {
+    let class_a = CppType::Class(CppPath::from_good_str("A"));
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("A::setFoo");
+    method.member = Some(empty_membership());
+    method.return_type = CppType::new_reference(false, class_a);
+
+    let to_ffi = |method: &CppFunction| {
+        crate::cpp_ffi_generator::to_ffi_method(
+            NewFfiFunctionKind::Function {
+                cpp_function: method.clone(),
+            },
+            &[],
+            &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+            None,
+        )
+        .unwrap()
+    };
+    let configured = [CppPath::from_good_str("A::setFoo")];
+
+    assert!(is_chaining_method(&to_ffi(&method), &method, &configured));
+    // the return type alone doesn't prove that `*this` is returned
+    assert!(!is_chaining_method(&to_ffi(&method), &method, &[]));
+
+    let mut next = method.clone();
+    next.path = CppPath::from_good_str("A::next");
+    assert!(!is_chaining_method(&to_ffi(&next), &next, &configured));
+
+    let mut const_method = method.clone();
+    const_method.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    assert!(!is_chaining_method(
+        &to_ffi(&const_method),
+        &const_method,
+        &configured
+    ));
+
+    let mut other_type = method.clone();
+    other_type.return_type =
+        CppType::new_reference(false, CppType::Class(CppPath::from_good_str("B")));
+    assert!(!is_chaining_method(
+        &to_ffi(&other_type),
+        &other_type,
+        &configured
+    ));
+}
+
+#[test]
+fn constructor_args_field_types() {
+    let class_ptr = RustType::new_pointer(true, class_type("test::A"));
+    let field_type = |conversion: RustToFfiTypeConversion, ffi_type: RustType| {
+        let arg = RustFunctionArgument {
+            argument_type: RustFinalType::new(ffi_type, conversion).unwrap(),
+            name: "arg".to_string(),
+            ffi_index: 0,
+        };
+        State::constructor_args_field_type(&arg).unwrap()
+    };
+
+    assert_eq!(
+        field_type(
+            RustToFfiTypeConversion::None,
+            RustType::Primitive("i32".into())
+        ),
+        Some(RustType::Primitive("i32".into()))
+    );
+    // `impl CastInto<Ref<A>>` arguments are stored as `Ref<A>`
+    assert_eq!(
+        field_type(
+            RustToFfiTypeConversion::ImplCastInto(Box::new(
+                RustToFfiTypeConversion::UtilsRefToPtr {
+                    force_api_is_const: None,
+                },
+            )),
+            class_ptr.clone()
+        ),
+        Some(RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("cpp_core::Ref"),
+            generic_arguments: Some(vec![class_type("test::A")]),
+        }))
+    );
+    // references can't be stored without a lifetime
+    assert_eq!(
+        field_type(
+            RustToFfiTypeConversion::RefToPtr {
+                force_api_is_const: None,
+                lifetime: None,
+            },
+            class_ptr
+        ),
+        None
+    );
+}
+
+#[test]
+fn ownership_transfer_arguments() {
+    let class_a = CppType::Class(CppPath::from_good_str("A"));
+    let argument = |argument_type: CppType| CppFunctionArgument {
+        argument_type,
+        name: "arg".to_string(),
+        has_default_value: false,
+    };
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("Parent::addChild");
+    method.member = Some(empty_membership());
+    method.arguments = vec![
+        argument(CppType::new_pointer(false, class_a.clone())),
+        argument(CppType::new_pointer(true, class_a.clone())),
+        argument(CppType::new_reference(false, class_a)),
+    ];
+    let marked = (0..4)
+        .map(|index| (CppPath::from_good_str("Parent::addChild"), index))
+        .collect::<Vec<_>>();
+
+    assert!(is_ownership_transfer_argument(&method, 0, &marked));
+    assert!(!is_ownership_transfer_argument(&method, 0, &[]));
+    assert!(!is_ownership_transfer_argument(
+        &method,
+        0,
+        &[(CppPath::from_good_str("Parent::removeChild"), 0)]
+    ));
+    // only mutable pointers can be passed as `CppBox`
+    assert!(!is_ownership_transfer_argument(&method, 1, &marked));
+    assert!(!is_ownership_transfer_argument(&method, 2, &marked));
+    assert!(!is_ownership_transfer_argument(&method, 3, &marked));
+}
+
+#[test]
+fn unregistration_functions() {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let observable = CppType::Class(CppPath::from_good_str("Observable"));
+    let argument = |argument_type: CppType| CppFunctionArgument {
+        argument_type,
+        name: "arg".to_string(),
+        has_default_value: false,
+    };
+    let ffi_unregister = |path: &str, is_method: bool, arguments: Vec<CppType>| {
+        let mut function = empty_regular_method();
+        function.path = CppPath::from_good_str(path);
+        if is_method {
+            function.member = Some(empty_membership());
+        }
+        function.arguments = arguments.into_iter().map(argument).collect();
+        crate::cpp_ffi_generator::to_ffi_method(
+            NewFfiFunctionKind::Function {
+                cpp_function: function,
+            },
+            &[],
+            &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+            None,
+        )
+        .unwrap()
+    };
+    let is_matching = |function: CppFfiFunction, this_type: Option<&CppType>| {
+        is_matching_unregistration_function(&function, &int, this_type).unwrap()
+    };
+
+    assert!(is_matching(
+        ffi_unregister("Observable::unsubscribe", true, vec![int.clone()]),
+        Some(&observable)
+    ));
+    assert!(is_matching(
+        ffi_unregister("unsubscribe", false, vec![int.clone()]),
+        None
+    ));
+    // the unregistration function must be a method of the same class
+    assert!(!is_matching(
+        ffi_unregister("unsubscribe", false, vec![int.clone()]),
+        Some(&observable)
+    ));
+    assert!(!is_matching(
+        ffi_unregister("Other::unsubscribe", true, vec![int.clone()]),
+        Some(&observable)
+    ));
+    // the token must be the only argument
+    assert!(!is_matching(
+        ffi_unregister(
+            "Observable::unsubscribe",
+            true,
+            vec![
+                int.clone(),
+                CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)
+            ]
+        ),
+        Some(&observable)
+    ));
+    assert!(!is_matching(
+        ffi_unregister(
+            "Observable::unsubscribe",
+            true,
+            vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Double)]
+        ),
+        Some(&observable)
+    ));
+}
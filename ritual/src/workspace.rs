@@ -117,6 +117,9 @@ impl Workspace {
                     CrateDependencySource::CurrentWorkspace => {
                         self.database_path(dependency.name())
                     }
+                    CrateDependencySource::Git { .. } => {
+                        bail!("cannot use Git for ritual dependencies");
+                    }
                 };
 
                 cache.get(path, dependency.name(), true, false)
@@ -155,8 +158,9 @@ impl Workspace {
         for item in read_dir(self.path.join("out"))? {
             let item = item?;
             let path = item.path().join("Cargo.toml");
-            if path.exists() {
-                let dir_name = os_string_into_string(item.file_name())?;
+            let dir_name = os_string_into_string(item.file_name())?;
+            // hidden directories are temporary directories of the crate writer
+            if path.exists() && !dir_name.starts_with('.') {
                 members.push(toml::Value::String(format!("out/{}", dir_name)));
             }
         }
@@ -10,6 +10,43 @@
 // placement new statements require this
 #include <new>
 
+// std::move requires this
+#include <utility>
+
+// owned arguments are deleted through std::unique_ptr
+#include <memory>
+
+// offsetof requires this
+#include <cstddef>
+
+// catching exceptions requires this
+#include <exception>
+#include <cstring>
+
+// array length checks require this
+#include <stdexcept>
+#include <cstdlib>
+
+// initializer list arguments require this
+#include <initializer_list>
+
+// checks of periods of duration types require this
+#include <ratio>
+
+// The build script defines one of `RITUAL_EXPECTED_STD_LIB_*` macros if
+// the crate was generated against a known C++ standard library. Layout of
+// standard library types differs between implementations, so the wrappers
+// can't be used with a different implementation.
+#if defined(RITUAL_EXPECTED_STD_LIB_LIBSTDCXX) && !defined(__GLIBCXX__)
+    #error "The crate was generated against libstdc++, but a different C++ standard library is used"
+#endif
+#if defined(RITUAL_EXPECTED_STD_LIB_LIBCXX) && !defined(_LIBCPP_VERSION)
+    #error "The crate was generated against libc++, but a different C++ standard library is used"
+#endif
+#if defined(RITUAL_EXPECTED_STD_LIB_MSVC_STL) && !defined(_CPPLIB_VER)
+    #error "The crate was generated against MSVC standard library, but a different C++ standard library is used"
+#endif
+
 // original C++ library includes generated by cpp_to_rust
 {include_directives_code}
 
@@ -19,6 +56,14 @@
     #define RITUAL_EXPORT
 #endif
 
+#if defined(__GNUC__) || defined(__clang__)
+    #define RITUAL_PURE __attribute__((pure))
+    #define RITUAL_CONST __attribute__((const))
+#else
+    #define RITUAL_PURE
+    #define RITUAL_CONST
+#endif
+
 
 // Calls destructor of `T` class. This template function
 // is necessary because it's not possible to use `x->~T()`
@@ -28,5 +73,100 @@ void ritual_call_destructor(T* x) {{
     x->~T();
 }}
 
+// Returns the value of `std::optional` (or default value if it's empty)
+// and writes presence of the value to `has_value`.
+template<typename T>
+typename T::value_type ritual_optional_value(const T& value, bool* has_value) {{
+    *has_value = value.has_value();
+    return value.has_value() ? *value : typename T::value_type();
+}}
+
+// Converts a result of a three-way comparison (`operator<=>`)
+// to -1, 0 or 1.
+template<typename T>
+int ritual_ordering_to_int(T value) {{
+    return value < 0 ? -1 : (value > 0 ? 1 : 0);
+}}
+
+// Maximal number of elements of an `std::initializer_list` argument.
+// Must match the size accepted by `ritual_with_initializer_list`
+// and `MAX_INITIALIZER_LIST_SIZE` in the generator.
+#define RITUAL_MAX_INITIALIZER_LIST_SIZE 16
+
+// Calls `f` with an `std::initializer_list` containing the first `len`
+// elements of `d`. Elements of `std::initializer_list` can only be specified
+// in a braced list, so there is a separate list for each supported length.
+// The FFI wrapper checks `len` before calling this function.
+template<typename T, typename F>
+auto ritual_with_initializer_list(const T* d, size_t len, F f)
+    -> decltype(f(std::initializer_list<T>())) {{
+    switch (len) {{
+    case 0: return f(std::initializer_list<T>());
+    case 1: return f({{d[0]}});
+    case 2: return f({{d[0], d[1]}});
+    case 3: return f({{d[0], d[1], d[2]}});
+    case 4: return f({{d[0], d[1], d[2], d[3]}});
+    case 5: return f({{d[0], d[1], d[2], d[3], d[4]}});
+    case 6: return f({{d[0], d[1], d[2], d[3], d[4], d[5]}});
+    case 7: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6]}});
+    case 8: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]}});
+    case 9: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8]}});
+    case 10: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9]}});
+    case 11: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9], d[10]}});
+    case 12: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9], d[10], d[11]}});
+    case 13: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9], d[10], d[11], d[12]}});
+    case 14: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9], d[10], d[11], d[12], d[13]}});
+    case 15: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9], d[10], d[11], d[12], d[13], d[14]}});
+    case 16: return f({{d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7], d[8], d[9], d[10], d[11], d[12], d[13], d[14], d[15]}});
+    default: std::abort();
+    }}
+}}
+
+// Size of the buffer receiving the message of a caught exception.
+// Must match `cpp_core::EXCEPTION_BUFFER_SIZE`.
+#define RITUAL_EXCEPTION_BUFFER_SIZE 256
+
+// Writes `message` of a caught exception to `buffer`,
+// truncating it if necessary. An empty buffer means that no exception
+// was thrown, so a placeholder is written if the message is empty.
+inline void ritual_write_exception(char* buffer, const char* message) {{
+    if (!message || !message[0]) {{
+        message = "unknown C++ exception";
+    }}
+    strncpy(buffer, message, RITUAL_EXCEPTION_BUFFER_SIZE - 1);
+    buffer[RITUAL_EXCEPTION_BUFFER_SIZE - 1] = 0;
+}}
+
+// Size of `ritual_member_function_pointer`'s data.
+// Must match `cpp_core::MEMBER_FUNCTION_POINTER_SIZE`.
+#define RITUAL_MEMBER_FUNCTION_POINTER_SIZE 32
+
+// Opaque representation of a pointer to member function
+// that can be passed through FFI by value.
+struct ritual_member_function_pointer {{
+    unsigned char data[RITUAL_MEMBER_FUNCTION_POINTER_SIZE];
+}};
+
+// Copies bytes of a pointer to member function to `ritual_member_function_pointer`.
+template<typename T>
+ritual_member_function_pointer ritual_from_member_function_pointer(T value) {{
+    static_assert(sizeof(T) <= RITUAL_MEMBER_FUNCTION_POINTER_SIZE,
+        "pointer to member function is too large");
+    ritual_member_function_pointer result;
+    memset(result.data, 0, sizeof(result.data));
+    memcpy(result.data, &value, sizeof(T));
+    return result;
+}}
+
+// Restores a pointer to member function from `ritual_member_function_pointer`.
+template<typename T>
+T ritual_to_member_function_pointer(ritual_member_function_pointer value) {{
+    static_assert(sizeof(T) <= RITUAL_MEMBER_FUNCTION_POINTER_SIZE,
+        "pointer to member function is too large");
+    T result;
+    memcpy(&result, value.data, sizeof(T));
+    return result;
+}}
+
 
 #endif // RITUAL_GLOBAL_H
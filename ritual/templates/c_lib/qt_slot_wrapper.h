@@ -10,10 +10,8 @@ public:
     }}
 
 public Q_SLOTS:
-    void custom_slot({method_args}) {{
-        if (m_func) {{
-            m_func({func_args});
-        }}
+    {return_type} custom_slot({method_args}) {{
+        {slot_body}
     }}
 
 private:
@@ -14,9 +14,16 @@
 // placement new statements require this
 #include <new>
 
+// owned arguments are deleted through std::unique_ptr
+#include <memory>
+
 // for exit()
 #include <cstdlib>
 
+// catching exceptions requires this
+#include <exception>
+#include <cstring>
+
 // original C++ library includes generated by cpp_to_rust
 {include_directives_code}
 
@@ -26,6 +33,14 @@
     #define RITUAL_EXPORT
 #endif
 
+#if defined(__GNUC__) || defined(__clang__)
+    #define RITUAL_PURE __attribute__((pure))
+    #define RITUAL_CONST __attribute__((const))
+#else
+    #define RITUAL_PURE
+    #define RITUAL_CONST
+#endif
+
 
 // Calls destructor of `T` class. This template function
 // is necessary because it's not possible to use `x->~T()`
@@ -35,6 +50,52 @@ void ritual_call_destructor(T* x) {{
     x->~T();
 }}
 
+// Size of the buffer receiving the message of a caught exception.
+// Must match `cpp_core::EXCEPTION_BUFFER_SIZE`.
+#define RITUAL_EXCEPTION_BUFFER_SIZE 256
+
+// Writes `message` of a caught exception to `buffer`,
+// truncating it if necessary. An empty buffer means that no exception
+// was thrown, so a placeholder is written if the message is empty.
+inline void ritual_write_exception(char* buffer, const char* message) {{
+    if (!message || !message[0]) {{
+        message = "unknown C++ exception";
+    }}
+    strncpy(buffer, message, RITUAL_EXCEPTION_BUFFER_SIZE - 1);
+    buffer[RITUAL_EXCEPTION_BUFFER_SIZE - 1] = 0;
+}}
+
+// Size of `ritual_member_function_pointer`'s data.
+// Must match `cpp_core::MEMBER_FUNCTION_POINTER_SIZE`.
+#define RITUAL_MEMBER_FUNCTION_POINTER_SIZE 32
+
+// Opaque representation of a pointer to member function
+// that can be passed through FFI by value.
+struct ritual_member_function_pointer {{
+    unsigned char data[RITUAL_MEMBER_FUNCTION_POINTER_SIZE];
+}};
+
+// Copies bytes of a pointer to member function to `ritual_member_function_pointer`.
+template<typename T>
+ritual_member_function_pointer ritual_from_member_function_pointer(T value) {{
+    static_assert(sizeof(T) <= RITUAL_MEMBER_FUNCTION_POINTER_SIZE,
+        "pointer to member function is too large");
+    ritual_member_function_pointer result;
+    memset(result.data, 0, sizeof(result.data));
+    memcpy(result.data, &value, sizeof(T));
+    return result;
+}}
+
+// Restores a pointer to member function from `ritual_member_function_pointer`.
+template<typename T>
+T ritual_to_member_function_pointer(ritual_member_function_pointer value) {{
+    static_assert(sizeof(T) <= RITUAL_MEMBER_FUNCTION_POINTER_SIZE,
+        "pointer to member function is too large");
+    T result;
+    memcpy(&result, value.data, sizeof(T));
+    return result;
+}}
+
 #define ritual_assert(x) \
     if (!(x)) {{ \
         std::cout << "assertion failed: " << #x << "\n"; \
@@ -0,0 +1,10 @@
+//! Build script generated by `ritual` that locates the C++ library using pkg-config.
+//! See the template at `ritual/templates/crate/build_pkg_config.rs`.
+//!
+//! The name of the pkg-config module is stored in `build_script_data.json`.
+//! Include and library paths reported by `pkg-config` are added
+//! to the build paths before building the crate.
+
+fn main() {
+    ritual_build::run_with_pkg_config()
+}
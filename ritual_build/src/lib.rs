@@ -11,11 +11,16 @@
 //!
 //! - Apply extra build configuration passed via environment variables
 //!   (`RITUAL_LIBRARY_PATH`, `RITUAL_FRAMEWORK_PATH`, `RITUAL_INCLUDE_PATH`);
+//! - Optionally query include and library paths from `pkg-config`
+//!   (see `run_with_pkg_config`);
 //! - Select active build configuration based on current target;
+//! - Define C++ feature macros corresponding to enabled cargo features;
 //! - Build the C++ wrapper library;
 //! - Generate `ffi.rs` file with actual linking attributes;
 //! - Determine sizes of C++ types with stack allocation place;
-//! - Print `cargo` attributes required for linking the crate.
+//! - Print `cargo` attributes required for linking the crate;
+//! - Optionally write a report of the C++ wrapper library build times
+//!   if `RITUAL_BUILD_TIME_REPORT` environment variable is set.
 //! It appears a common task to perform other custom operations in the build script,
 //! so all the above operations are implemented as a separate crate
 //! which is used as build dependency of the default generated build script.
@@ -25,15 +30,19 @@
 use log::info;
 pub use ritual_common as common;
 use ritual_common::cpp_build_config::{CppBuildConfig, CppBuildPaths, CppLibraryType};
-use ritual_common::cpp_lib_builder::{BuildType, CMakeConfigData, CppLibBuilder};
+use ritual_common::cpp_lib_builder::{
+    BuildType, CMakeConfigData, CppLibBuildStepTime, CppLibBuilder,
+};
 use ritual_common::errors::{bail, err_msg, FancyUnwrap, Result, ResultExt};
 use ritual_common::file_utils::{create_file, file_to_string, load_json, path_to_str};
 use ritual_common::target::{current_target, LibraryTarget, OS};
+use ritual_common::utils::get_command_output;
 use ritual_common::{env_var_names, BuildScriptData};
 use std::env;
 use std::io::Write;
-use std::path::PathBuf;
-use std::process;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::Duration;
 
 /// Configuration of the build script.
 #[derive(Debug)]
@@ -107,19 +116,78 @@ impl Config {
         self.cpp_build_paths = config;
     }
 
+    /// Adds include and library paths reported by `pkg-config` for the module
+    /// recorded in `build_script_data.json` to the current `CppBuildPaths`.
+    /// Does nothing if the module was not set during generation.
+    pub fn apply_pkg_config(&mut self) -> Result<()> {
+        let module = if let Some(module) = &self.build_script_data.pkg_config_module {
+            module.clone()
+        } else {
+            return Ok(());
+        };
+        println!("cargo:rerun-if-env-changed=PKG_CONFIG_PATH");
+        info!("Querying pkg-config for {}", module);
+        let output = get_command_output(
+            Command::new("pkg-config")
+                .arg("--cflags-only-I")
+                .arg("--libs-only-L")
+                .arg(&module),
+        )
+        .with_context(|_| format!("failed to query pkg-config for {}", module))?;
+        for token in output.split_whitespace() {
+            if token.starts_with("-I") {
+                self.cpp_build_paths.add_include_path(&token[2..]);
+            } else if token.starts_with("-L") {
+                self.cpp_build_paths.add_lib_path(&token[2..]);
+            }
+        }
+        Ok(())
+    }
+
     /// Same as `run()`, but result of the operation is returned to the caller.
     pub fn try_run(mut self) -> Result<()> {
         let current_target = LibraryTarget {
             target: current_target(),
             cpp_library_version: self.current_cpp_library_version.clone(),
+            cpp_std_lib: None,
         };
+        let mut cpp_defines = self
+            .build_script_data
+            .feature_macros
+            .iter()
+            .filter(|feature| {
+                feature.is_cpp_macro_defined(env::var_os(feature.cargo_env_var_name()).is_some())
+            })
+            .map(|feature| feature.cpp_macro.clone())
+            .collect::<Vec<_>>();
 
-        let is_known_target = self
+        let matching_targets = self
             .build_script_data
             .known_targets
-            .contains(&current_target);
+            .iter()
+            .filter(|target| target.is_same_platform(&current_target))
+            .collect::<Vec<_>>();
+
+        // The C++ standard library can't be detected before compiling C++ code,
+        // so the C++ wrapper library checks it at compile time.
+        // Targets without a recorded standard library are ignored.
+        let cpp_std_libs = matching_targets
+            .iter()
+            .filter_map(|target| target.cpp_std_lib)
+            .collect::<Vec<_>>();
+        let expected_cpp_std_lib = cpp_std_libs
+            .first()
+            .cloned()
+            .filter(|first| cpp_std_libs.iter().all(|cpp_std_lib| cpp_std_lib == first));
+        if let Some(cpp_std_lib) = expected_cpp_std_lib {
+            info!(
+                "Expecting C++ standard library: {}",
+                cpp_std_lib.short_text()
+            );
+            cpp_defines.push(cpp_std_lib.expected_macro().to_string());
+        }
 
-        if !is_known_target {
+        if matching_targets.is_empty() {
             println!("Current target is unknown: {}", current_target.short_text());
             println!("Known targets:");
             for target in &self.build_script_data.known_targets {
@@ -150,10 +218,11 @@ impl Config {
             cpp_build_paths: &self.cpp_build_paths,
             library_type: Some(library_type),
             cpp_library_version: self.current_cpp_library_version.clone(),
+            cpp_defines,
         };
 
         let cmake_vars = cmake_config.cmake_vars()?.into_iter().collect();
-        CppLibBuilder {
+        let (_, build_times) = CppLibBuilder {
             cmake_source_dir: manifest_dir.join("c_lib"),
             build_dir: out_dir.join("c_lib_build"),
             install_dir: Some(c_lib_install_dir.clone()),
@@ -168,7 +237,10 @@ impl Config {
             skip_cmake: false,
             skip_cmake_after_first_run: false,
         }
-        .run()?;
+        .run_timed()?;
+        if env::var_os(env_var_names::BUILD_TIME_REPORT).is_some() {
+            write_build_time_report(&out_dir.join("c_lib_build_times.txt"), &build_times)?;
+        }
         {
             info!("Generating ffi.rs file");
             let mut ffi_file = create_file(out_dir.join("ffi.rs"))?;
@@ -212,9 +284,13 @@ impl Config {
         for path in self.cpp_build_paths.framework_paths() {
             println!("cargo:rustc-link-search=framework={}", path_to_str(path)?);
         }
+        let c_lib_dir = match &self.build_script_data.cpp_wrapper_lib_install_dir {
+            Some(dir) => c_lib_install_dir.join(dir),
+            None => c_lib_install_dir.clone(),
+        };
         println!(
             "cargo:rustc-link-search=native={}",
-            path_to_str(&c_lib_install_dir)?
+            path_to_str(&c_lib_dir)?
         );
 
         if let Some(version) = self.current_cpp_library_version {
@@ -224,6 +300,7 @@ impl Config {
             env_var_names::LIBRARY_PATH,
             env_var_names::FRAMEWORK_PATH,
             env_var_names::INCLUDE_PATH,
+            env_var_names::BUILD_TIME_REPORT,
         ] {
             println!("cargo:rerun-if-env-changed={}", var);
         }
@@ -247,6 +324,24 @@ impl Config {
     }
 }
 
+/// Writes durations of C++ wrapper library build steps to `path`
+/// and reports them as cargo warnings, so that they are visible in the cargo output.
+fn write_build_time_report(path: &Path, times: &[CppLibBuildStepTime]) -> Result<()> {
+    let total = times.iter().map(|time| time.duration).sum::<Duration>();
+    let mut file = create_file(path)?;
+    for time in times {
+        let line = format!("c_lib {}: {:.3}s", time.step, time.duration.as_secs_f64());
+        writeln!(file, "{}", line)?;
+        println!("cargo:warning={}", line);
+    }
+    writeln!(file, "c_lib total: {:.3}s", total.as_secs_f64())?;
+    println!(
+        "cargo:warning=c_lib build time report written to {}",
+        path_to_str(path)?
+    );
+    Ok(())
+}
+
 /// Same as `run()`, but result of the operation is returned to the caller.
 pub fn try_run() -> Result<()> {
     Config::new()?.try_run()
@@ -258,3 +353,12 @@ pub fn run() -> ! {
     let config = Config::new().fancy_unwrap();
     config.run()
 }
+
+/// Runs the build script with default configuration and
+/// include and library paths reported by `pkg-config`.
+/// See `Config::apply_pkg_config` and `Config::run` for more information.
+pub fn run_with_pkg_config() -> ! {
+    let mut config = Config::new().fancy_unwrap();
+    config.apply_pkg_config().fancy_unwrap();
+    config.run()
+}
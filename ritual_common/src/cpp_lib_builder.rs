@@ -11,6 +11,7 @@ use std::env;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant};
 
 /// A CMake variable with a name and a value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,9 +108,29 @@ impl CppLibBuilderOutput {
     }
 }
 
+/// Time spent in one CMake invocation performed by `CppLibBuilder`.
+#[derive(Debug, Clone)]
+pub struct CppLibBuildStepTime {
+    /// Name of the step (`configure`, `clean` or `build`)
+    pub step: &'static str,
+    pub duration: Duration,
+}
+
 impl CppLibBuilder {
     /// Builds the library.
     pub fn run(&mut self) -> Result<CppLibBuilderOutput> {
+        self.run_timed().map(|(output, _)| output)
+    }
+
+    /// Builds the library and returns time spent in each CMake invocation
+    /// along with the result. If the build fails, only the error is returned.
+    pub fn run_timed(&mut self) -> Result<(CppLibBuilderOutput, Vec<CppLibBuildStepTime>)> {
+        let mut times = Vec::new();
+        let output = self.run_steps(&mut times)?;
+        Ok((output, times))
+    }
+
+    fn run_steps(&mut self, times: &mut Vec<CppLibBuildStepTime>) -> Result<CppLibBuilderOutput> {
         if !self.build_dir.exists() {
             create_dir_all(&self.build_dir)?;
         }
@@ -159,13 +180,20 @@ impl CppLibBuilder {
                 cmake_command.args(shell_words::split(&args)?);
             }
 
-            if self.capture_output {
-                let output = run_command_and_capture_output(&mut cmake_command)?;
+            let start = Instant::now();
+            let result = if self.capture_output {
+                run_command_and_capture_output(&mut cmake_command).map(Some)
+            } else {
+                run_command(&mut cmake_command).map(|_| None)
+            };
+            times.push(CppLibBuildStepTime {
+                step: "configure",
+                duration: start.elapsed(),
+            });
+            if let Some(output) = result? {
                 if !output.is_success() {
                     return Ok(CppLibBuilderOutput::Fail(output));
                 }
-            } else {
-                run_command(&mut cmake_command)?;
             }
         }
 
@@ -173,6 +201,7 @@ impl CppLibBuilder {
             self.skip_cmake = true;
         }
 
+        let start = Instant::now();
         if target::current_env() == target::Env::Msvc && self.capture_output {
             let path = self.build_dir.join("nmake_output.txt");
             run_command(
@@ -194,6 +223,10 @@ impl CppLibBuilder {
                     .current_dir(&self.build_dir),
             )?;
         }
+        times.push(CppLibBuildStepTime {
+            step: "clean",
+            duration: start.elapsed(),
+        });
 
         let mut make_args = vec!["--build".to_string(), ".".to_string(), "--".to_string()];
         let num_jobs = if let Some(x) = self.num_jobs {
@@ -228,6 +261,20 @@ impl CppLibBuilder {
         };
 
         make_command.current_dir(&self.build_dir);
+        let start = Instant::now();
+        let output = self.run_make_command(make_command, capture_output_file);
+        times.push(CppLibBuildStepTime {
+            step: "build",
+            duration: start.elapsed(),
+        });
+        output
+    }
+
+    fn run_make_command(
+        &self,
+        mut make_command: Command,
+        capture_output_file: Option<PathBuf>,
+    ) -> Result<CppLibBuilderOutput> {
         if self.capture_output {
             if let Some(capture_output_file) = capture_output_file {
                 if let Err(err) = run_command(&mut make_command) {
@@ -260,6 +307,7 @@ pub struct CMakeConfigData<'a, 'b> {
     pub cpp_build_paths: &'b CppBuildPaths,
     pub library_type: Option<CppLibraryType>,
     pub cpp_library_version: Option<String>,
+    pub cpp_defines: Vec<String>,
 }
 
 pub fn version_to_number(version: &str) -> Result<u32> {
@@ -307,6 +355,7 @@ impl<'a, 'b> CMakeConfigData<'a, 'b> {
             "RITUAL_LINKED_FRAMEWORKS",
             self.cpp_build_config_data.linked_frameworks(),
         )?);
+        cmake_vars.push(CMakeVar::new_list("RITUAL_DEFINES", &self.cpp_defines)?);
         cmake_vars.push(CMakeVar::new(
             "RITUAL_COMPILER_FLAGS",
             self.cpp_build_config_data.compiler_flags().join(" "),
@@ -4,3 +4,4 @@ pub const FRAMEWORK_PATH: &str = "RITUAL_FRAMEWORK_PATH";
 pub const INCLUDE_PATH: &str = "RITUAL_INCLUDE_PATH";
 pub const CLANG_SYSTEM_INCLUDE_PATH: &str = "CLANG_SYSTEM_INCLUDE_PATH";
 pub const CMAKE_ARGS: &str = "RITUAL_CMAKE_ARGS";
+pub const BUILD_TIME_REPORT: &str = "RITUAL_BUILD_TIME_REPORT";
@@ -122,6 +122,25 @@ pub fn file_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
     f.read_all()
 }
 
+/// Returns a hash of `content` that is the same across runs, platforms and
+/// compiler versions, so it can be compared between the generator and the build script.
+pub fn content_hash(content: &[u8]) -> String {
+    // 64-bit FNV-1a
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in content {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Returns `content_hash` of the content of the file `path`.
+pub fn file_content_hash<P: AsRef<Path>>(path: P) -> Result<String> {
+    let content = fs::read(path.as_ref())
+        .with_context(|_| format!("Failed to read file: {:?}", path.as_ref()))?;
+    Ok(content_hash(&content))
+}
+
 /// A wrapper over `std::fs::File::create` with better error reporting.
 pub fn create_file<P: AsRef<Path>>(path: P) -> Result<File<BufWriter<fs::File>>> {
     let file = fs::File::create(path.as_ref())
@@ -338,6 +357,25 @@ pub fn rename_file<P: AsRef<Path>, P2: AsRef<Path>>(path1: P, path2: P2) -> Resu
     Ok(())
 }
 
+/// Renames the directory `src` to `dst`, which must not exist.
+/// If renaming fails (e.g. because the paths are on different devices),
+/// the directory is copied to `dst` and removed instead.
+pub fn rename_or_move_dir<P: AsRef<Path>, P2: AsRef<Path>>(src: P, dst: P2) -> Result<()> {
+    let src = src.as_ref().to_path_buf();
+    let dst = dst.as_ref().to_path_buf();
+    if let Err(err) = fs::rename(&src, &dst) {
+        trace!(
+            "Failed to rename {:?} to {:?} ({}), copying instead",
+            src,
+            dst,
+            err
+        );
+        copy_recursively(&src, &dst)?;
+        remove_dir_all(&src)?;
+    }
+    Ok(())
+}
+
 /// A wrapper over `std::fs::copy` with better error reporting
 pub fn copy_file<P: AsRef<Path>, P2: AsRef<Path>>(path1: P, path2: P2) -> Result<()> {
     fs::copy(path1.as_ref(), path2.as_ref())
@@ -7,6 +7,7 @@
 
 use crate::target::LibraryTarget;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub mod cpp_build_config;
 pub mod cpp_lib_builder;
@@ -28,8 +29,74 @@ pub struct BuildScriptData {
     pub cpp_build_config: cpp_build_config::CppBuildConfig,
     /// Name of C++ wrapper library
     pub cpp_wrapper_lib_name: String,
+    /// Directory of the installed C++ wrapper library, relative to
+    /// the install prefix. `None` means the prefix itself.
+    #[serde(default)]
+    pub cpp_wrapper_lib_install_dir: Option<String>,
     /// Environments the generator was used in
     pub known_targets: Vec<LibraryTarget>,
+    /// C++ feature macros controlled by cargo features of the crate
+    #[serde(default)]
+    pub feature_macros: Vec<FeatureMacro>,
+    /// Name of the pkg-config module providing include and library paths
+    /// of the C++ library. Only used by the pkg-config build script template.
+    #[serde(default)]
+    pub pkg_config_module: Option<String>,
+    /// Hashes (see `file_utils::content_hash`) of the generated C++ files
+    /// of the wrapper library, keyed by paths relative to the crate root.
+    /// `None` if the hashes were not recorded, in which case the C++ files
+    /// should be considered changed.
+    #[serde(default)]
+    pub generated_file_hashes: Option<BTreeMap<String, String>>,
+}
+
+/// Mapping of a C++ feature macro to a cargo feature of the generated crate.
+/// The macro is defined for the C++ wrapper library when the cargo feature
+/// is enabled, and the wrappers depending on it are only available
+/// with this feature. For negated macros (e.g. `QT_NO_SSL`), the API is guarded
+/// with `#ifndef`, and the macro is defined when the cargo feature is disabled.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeatureMacro {
+    /// Name of the C++ macro
+    pub cpp_macro: String,
+    /// Name of the cargo feature
+    pub cargo_feature: String,
+    /// True if the C++ macro disables the API instead of enabling it
+    #[serde(default)]
+    pub is_negated: bool,
+}
+
+impl FeatureMacro {
+    pub fn new(cpp_macro: impl Into<String>, cargo_feature: impl Into<String>) -> Self {
+        FeatureMacro {
+            cpp_macro: cpp_macro.into(),
+            cargo_feature: cargo_feature.into(),
+            is_negated: false,
+        }
+    }
+
+    /// Creates a mapping for a C++ macro that disables the API when defined.
+    pub fn negated(cpp_macro: impl Into<String>, cargo_feature: impl Into<String>) -> Self {
+        FeatureMacro {
+            is_negated: true,
+            ..FeatureMacro::new(cpp_macro, cargo_feature)
+        }
+    }
+
+    /// Returns true if the C++ macro should be defined when the state
+    /// of the cargo feature is `is_feature_enabled`.
+    pub fn is_cpp_macro_defined(&self, is_feature_enabled: bool) -> bool {
+        is_feature_enabled != self.is_negated
+    }
+
+    /// Returns name of the environment variable `cargo` sets for the build script
+    /// when the feature is enabled.
+    pub fn cargo_env_var_name(&self) -> String {
+        format!(
+            "CARGO_FEATURE_{}",
+            self.cargo_feature.to_uppercase().replace('-', "_")
+        )
+    }
 }
 
 #[derive(Debug)]
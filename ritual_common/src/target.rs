@@ -291,18 +291,78 @@ impl Condition {
     }
 }
 
+/// Implementation of the C++ standard library. Layout of standard library types
+/// (e.g. `std::string`) differs between implementations, so wrappers generated
+/// against one of them can't be used with another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum CppStdLib {
+    /// GNU C++ library (`libstdc++`)
+    LibStdCxx,
+    /// LLVM C++ library (`libc++`)
+    LibCxx,
+    /// Microsoft C++ standard library
+    MsvcStl,
+}
+
+impl CppStdLib {
+    /// Returns all supported standard library implementations.
+    pub fn all() -> &'static [CppStdLib] {
+        &[CppStdLib::LibStdCxx, CppStdLib::LibCxx, CppStdLib::MsvcStl]
+    }
+
+    /// Returns the C++ macro defined by headers of this implementation.
+    pub fn detection_macro(self) -> &'static str {
+        match self {
+            CppStdLib::LibStdCxx => "__GLIBCXX__",
+            CppStdLib::LibCxx => "_LIBCPP_VERSION",
+            CppStdLib::MsvcStl => "_CPPLIB_VER",
+        }
+    }
+
+    /// Returns the C++ macro the build script defines to request
+    /// validation of the standard library used by the C++ wrapper library.
+    pub fn expected_macro(self) -> &'static str {
+        match self {
+            CppStdLib::LibStdCxx => "RITUAL_EXPECTED_STD_LIB_LIBSTDCXX",
+            CppStdLib::LibCxx => "RITUAL_EXPECTED_STD_LIB_LIBCXX",
+            CppStdLib::MsvcStl => "RITUAL_EXPECTED_STD_LIB_MSVC_STL",
+        }
+    }
+
+    pub fn short_text(self) -> &'static str {
+        match self {
+            CppStdLib::LibStdCxx => "libstdc++",
+            CppStdLib::LibCxx => "libc++",
+            CppStdLib::MsvcStl => "msvc-stl",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct LibraryTarget {
     pub target: Target,
     pub cpp_library_version: Option<String>,
+    /// C++ standard library detected in the environment, if known
+    #[serde(default)]
+    pub cpp_std_lib: Option<CppStdLib>,
 }
 
 impl LibraryTarget {
     pub fn short_text(&self) -> String {
+        let mut text = self.target.short_text();
+        if let Some(cpp_std_lib) = self.cpp_std_lib {
+            text = format!("{} ({})", text, cpp_std_lib.short_text());
+        }
         if let Some(cpp_library_version) = &self.cpp_library_version {
-            format!("v{} on {}", cpp_library_version, self.target.short_text())
+            format!("v{} on {}", cpp_library_version, text)
         } else {
-            self.target.short_text()
+            text
         }
     }
+
+    /// Returns true if `other` has the same target and C++ library version,
+    /// regardless of the standard library.
+    pub fn is_same_platform(&self, other: &LibraryTarget) -> bool {
+        self.target == other.target && self.cpp_library_version == other.cpp_library_version
+    }
 }
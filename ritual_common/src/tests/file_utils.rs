@@ -0,0 +1,9 @@
+use crate::file_utils::content_hash;
+
+#[test]
+fn content_hashes() {
+    assert_eq!(content_hash(b""), "cbf29ce484222325");
+    assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+    assert_eq!(content_hash(b"int x;"), content_hash(b"int x;"));
+    assert_ne!(content_hash(b"int x;"), content_hash(b"int y;"));
+}
@@ -1 +1,3 @@
+mod file_utils;
+mod target;
 mod utils;
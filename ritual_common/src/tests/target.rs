@@ -0,0 +1,20 @@
+use crate::target::{current_target, CppStdLib, LibraryTarget};
+
+#[test]
+fn library_target_without_std_lib() {
+    let target = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: Some("5.11.0".to_string()),
+        cpp_std_lib: Some(CppStdLib::LibCxx),
+    };
+    let mut json = serde_json::to_value(&target).unwrap();
+    json.as_object_mut().unwrap().remove("cpp_std_lib");
+    let old_target: LibraryTarget = serde_json::from_value(json).unwrap();
+    assert_eq!(old_target.cpp_std_lib, None);
+    assert_ne!(old_target, target);
+    assert!(old_target.is_same_platform(&target));
+    assert_eq!(
+        target.short_text(),
+        format!("v5.11.0 on {} (libc++)", current_target().short_text())
+    );
+}